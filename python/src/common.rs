@@ -1,9 +1,19 @@
+use std::marker::PhantomData;
+
+use arrow_array::cast::AsArray;
+use arrow_array::ffi::{from_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use arrow_array::types::{
+    ArrowPrimitiveType, Float32Type, Float64Type, Int16Type, Int32Type, Int8Type, UInt16Type,
+    UInt32Type, UInt8Type,
+};
+use arrow_array::{make_array, Array, ArrayRef};
 use geo_index::IndexableNum;
 use numpy::{dtype_bound, PyArray1, PyArrayDescr, PyUntypedArray};
 use pyo3::buffer::PyBuffer;
 use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::ffi;
 use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
 use std::os::raw::c_int;
 
 /// A Rust buffer that implements the Python buffer protocol
@@ -46,7 +56,15 @@ impl RustBuffer {
 
 /// A Rust representation of a Python object that implements the Python buffer protocol, exporting
 /// a 1-dimensional `&[u8]` slice.
-pub(crate) struct PyU8Buffer(PyBuffer<u8>);
+///
+/// `Borrowed` zero-copies a C-contiguous buffer directly. `Owned` is used when the source buffer
+/// is strided (e.g. a `numpy` view like `arr[::2]`), gathering the logical 1-D sequence of bytes
+/// into freshly allocated storage so that [`as_ref`][AsRef::as_ref] can still hand back a plain
+/// contiguous slice.
+pub(crate) enum PyU8Buffer {
+    Borrowed(PyBuffer<u8>),
+    Owned(Vec<u8>),
+}
 
 impl<'py> FromPyObject<'py> for PyU8Buffer {
     fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
@@ -57,40 +75,141 @@ impl<'py> FromPyObject<'py> for PyU8Buffer {
         if buffer.dimensions() != 1 {
             return Err(PyValueError::new_err("Expected 1-dimensional array."));
         }
-        // Note: this is probably superfluous for 1D array
-        if !buffer.is_c_contiguous() {
-            return Err(PyValueError::new_err("Expected c-contiguous array."));
-        }
         if buffer.len_bytes() == 0 {
             return Err(PyValueError::new_err("Buffer has no data."));
         }
 
-        Ok(Self(buffer))
+        if buffer.is_c_contiguous() {
+            return Ok(Self::Borrowed(buffer));
+        }
+
+        let len = buffer.item_count();
+        let stride = buffer.strides()[0];
+        let data = buffer.buf_ptr() as *const u8;
+        let mut out = Vec::with_capacity(len);
+        for i in 0..len {
+            // Safety: `buffer` was validated above to be a read-only, 1-dimensional buffer of
+            // `len` items, and `stride` is the byte offset the buffer protocol reports between
+            // successive items.
+            unsafe {
+                out.push(*data.offset(i as isize * stride));
+            }
+        }
+
+        Ok(Self::Owned(out))
     }
 }
 
 impl AsRef<[u8]> for PyU8Buffer {
     /// Extract a slice from a Python object implementing the buffer protocol
     fn as_ref(&self) -> &[u8] {
-        let len = self.0.item_count();
-        let data = self.0.buf_ptr() as *const u8;
-        unsafe { std::slice::from_raw_parts(data, len) }
+        match self {
+            Self::Borrowed(buffer) => {
+                let len = buffer.item_count();
+                let data = buffer.buf_ptr() as *const u8;
+                unsafe { std::slice::from_raw_parts(data, len) }
+            }
+            Self::Owned(vec) => vec.as_slice(),
+        }
     }
 }
 
+/// Maps an [`IndexableNum`] to the `arrow` primitive type with the same native representation, so
+/// that a Python object imported through the Arrow C Data Interface can be downcast to the
+/// correct concrete array type.
+trait ArrowNativeType: IndexableNum {
+    type ArrowType: ArrowPrimitiveType<Native = Self>;
+}
+
+impl ArrowNativeType for i8 {
+    type ArrowType = Int8Type;
+}
+impl ArrowNativeType for u8 {
+    type ArrowType = UInt8Type;
+}
+impl ArrowNativeType for i16 {
+    type ArrowType = Int16Type;
+}
+impl ArrowNativeType for u16 {
+    type ArrowType = UInt16Type;
+}
+impl ArrowNativeType for i32 {
+    type ArrowType = Int32Type;
+}
+impl ArrowNativeType for u32 {
+    type ArrowType = UInt32Type;
+}
+impl ArrowNativeType for f32 {
+    type ArrowType = Float32Type;
+}
+impl ArrowNativeType for f64 {
+    type ArrowType = Float64Type;
+}
+
 pub(crate) enum PyTypedArrayRef<'py, N: IndexableNum + numpy::Element> {
-    // Arrow((ArrayRef, PhantomData<N>)),
+    Arrow((ArrayRef, PhantomData<N>)),
     Numpy(&'py PyArray1<N>),
+    /// A strided `numpy` array, gathered into owned storage since it can't be viewed as a
+    /// contiguous `&[N]` slice.
+    NumpyStrided(Vec<N>),
 }
 
 impl<'py, N: IndexableNum + numpy::Element> PyTypedArrayRef<'py, N> {
-    pub(crate) fn as_slice(&self) -> &[N] {
+    pub(crate) fn as_slice(&self) -> &[N]
+    where
+        N: ArrowNativeType,
+    {
         match self {
+            Self::Arrow((array, _)) => array.as_primitive::<N::ArrowType>().values(),
             Self::Numpy(arr) => unsafe { arr.as_slice() }.unwrap(),
+            Self::NumpyStrided(vec) => vec.as_slice(),
         }
     }
 }
 
+/// Extract a `numpy` array of the given native type, gathering into owned storage if the array
+/// is strided rather than C-contiguous (e.g. a view like `arr[::2]` or a column of a 2-D array).
+fn extract_numpy_typed<'py, N: IndexableNum + numpy::Element>(
+    array: &'py PyUntypedArray,
+) -> PyResult<PyTypedArrayRef<'py, N>> {
+    let arr = array.downcast::<PyArray1<N>>()?;
+    if arr.strides()[0] == std::mem::size_of::<N>() as isize {
+        Ok(PyTypedArrayRef::Numpy(arr))
+    } else {
+        // Safety: `arr` is a valid, readonly 1-dimensional array for the duration of this call;
+        // its contents are copied out into owned storage immediately below.
+        let view = unsafe { arr.as_array() };
+        Ok(PyTypedArrayRef::NumpyStrided(view.iter().copied().collect()))
+    }
+}
+
+/// Import a Python object exposing the
+/// [Arrow C Data Interface](https://arrow.apache.org/docs/format/CDataInterface/PyCapsuleInterface.html)
+/// (e.g. `pyarrow`, `arro3`, or a Polars `Series`) into an owned [`ArrayRef`], without copying the
+/// underlying buffer.
+fn import_arrow_c_array(ob: &Bound<PyAny>) -> PyResult<ArrayRef> {
+    let py = ob.py();
+    let tuple = ob.call_method0(pyo3::intern!(py, "__arrow_c_array__"))?;
+    let (schema_capsule, array_capsule): (Bound<PyCapsule>, Bound<PyCapsule>) = tuple.extract()?;
+
+    let schema_ptr = schema_capsule.pointer() as *mut FFI_ArrowSchema;
+    let array_ptr = array_capsule.pointer() as *mut FFI_ArrowArray;
+
+    // Safety: the Arrow PyCapsule Interface guarantees these capsules hold live, non-null
+    // `FFI_ArrowSchema`/`FFI_ArrowArray` pointers for the scope of this call; we move their
+    // contents out so the capsules' own destructors don't double-free them.
+    let (array, schema) = unsafe {
+        (
+            std::ptr::replace(array_ptr, FFI_ArrowArray::empty()),
+            std::ptr::replace(schema_ptr, FFI_ArrowSchema::empty()),
+        )
+    };
+
+    let array_data =
+        unsafe { from_ffi(array, &schema) }.map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(make_array(array_data))
+}
+
 pub(crate) enum PyArray<'py> {
     Int8(PyTypedArrayRef<'py, i8>),
     Int16(PyTypedArrayRef<'py, i16>),
@@ -104,6 +223,45 @@ pub(crate) enum PyArray<'py> {
 
 impl<'py> FromPyObject<'py> for PyArray<'py> {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if ob.hasattr("__arrow_c_array__")? {
+            let array = import_arrow_c_array(ob)?;
+            if array.null_count() > 0 {
+                return Err(PyValueError::new_err(
+                    "Cannot import Arrow array containing nulls.",
+                ));
+            }
+
+            return match array.data_type() {
+                arrow_schema::DataType::Int8 => {
+                    Ok(Self::Int8(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                arrow_schema::DataType::Int16 => {
+                    Ok(Self::Int16(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                arrow_schema::DataType::Int32 => {
+                    Ok(Self::Int32(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                arrow_schema::DataType::UInt8 => {
+                    Ok(Self::UInt8(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                arrow_schema::DataType::UInt16 => {
+                    Ok(Self::UInt16(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                arrow_schema::DataType::UInt32 => {
+                    Ok(Self::UInt32(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                arrow_schema::DataType::Float32 => {
+                    Ok(Self::Float32(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                arrow_schema::DataType::Float64 => {
+                    Ok(Self::Float64(PyTypedArrayRef::Arrow((array, PhantomData))))
+                }
+                dt => Err(PyTypeError::new_err(format!(
+                    "Unexpected dtype of Arrow array: {dt:?}"
+                ))),
+            };
+        }
+
         let mut ob = ob.to_owned();
         // call __array__ if it exists
         if ob.hasattr("__array__")? {
@@ -118,49 +276,43 @@ impl<'py> FromPyObject<'py> for PyArray<'py> {
             let dtype = array.dtype();
 
             if is_type::<i8>(dtype) {
-                let arr = array.downcast::<PyArray1<i8>>()?;
-                return Ok(Self::Int8(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::Int8(extract_numpy_typed(array)?));
             }
 
             if is_type::<i16>(dtype) {
-                let arr = array.downcast::<PyArray1<i16>>()?;
-                return Ok(Self::Int16(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::Int16(extract_numpy_typed(array)?));
             }
 
             if is_type::<i32>(dtype) {
-                let arr = array.downcast::<PyArray1<i32>>()?;
-                return Ok(Self::Int32(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::Int32(extract_numpy_typed(array)?));
             }
 
             if is_type::<u8>(dtype) {
-                let arr = array.downcast::<PyArray1<u8>>()?;
-                return Ok(Self::UInt8(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::UInt8(extract_numpy_typed(array)?));
             }
 
             if is_type::<u16>(dtype) {
-                let arr = array.downcast::<PyArray1<u16>>()?;
-                return Ok(Self::UInt16(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::UInt16(extract_numpy_typed(array)?));
             }
 
             if is_type::<u32>(dtype) {
-                let arr = array.downcast::<PyArray1<u32>>()?;
-                return Ok(Self::UInt32(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::UInt32(extract_numpy_typed(array)?));
             }
 
             if is_type::<f32>(dtype) {
-                let arr = array.downcast::<PyArray1<f32>>()?;
-                return Ok(Self::Float32(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::Float32(extract_numpy_typed(array)?));
             }
 
             if is_type::<f64>(dtype) {
-                let arr = array.downcast::<PyArray1<f64>>()?;
-                return Ok(Self::Float64(PyTypedArrayRef::Numpy(arr)));
+                return Ok(Self::Float64(extract_numpy_typed(array)?));
             }
 
             return Err(PyTypeError::new_err("Unexpected dtype of numpy array."));
         }
 
-        Err(PyTypeError::new_err("Expected numpy array input."))
+        Err(PyTypeError::new_err(
+            "Expected numpy array or Arrow array input.",
+        ))
     }
 }
 
@@ -6,13 +6,55 @@ use arrow_array::types::{Float32Type, Float64Type};
 use arrow_cast::cast;
 use arrow_schema::DataType;
 use geo_index::kdtree::{KDTree, KDTreeBuilder, KDTreeIndex, DEFAULT_KDTREE_NODE_SIZE};
+use geo_index::IndexableNum;
+use numpy::PyArray1;
 use pyo3::exceptions::PyValueError;
 use pyo3::ffi;
 use pyo3::prelude::*;
+use pyo3::types::PyType;
 use pyo3_arrow::PyArray;
 use std::os::raw::c_int;
 
 use crate::coord_type::CoordType;
+use crate::kdtree::input::{ExternalKDTree, PyKDTreeRef};
+
+/// Find the `k` nearest items to `(qx, qy)`, in ascending order of distance, via the same
+/// best-first min-heap traversal as [`KDTreeIndex::neighbors`], then filter down to `max_distance`
+/// if given.
+///
+/// `KDTreeIndex::neighbors` has no `max_distance` cutoff of its own (unlike `RTreeIndex::neighbors`),
+/// so when one is requested this builds a one-off position lookup from [`KDTreeIndex::indices`] to
+/// recover each result's coordinates for the distance check.
+fn neighbors_impl<N: IndexableNum>(
+    tree: &impl KDTreeIndex<N>,
+    qx: N,
+    qy: N,
+    k: Option<usize>,
+    max_distance: Option<N>,
+) -> Vec<u32> {
+    let k = k.unwrap_or(tree.num_items() as usize);
+    let mut ids = tree.neighbors(qx, qy, k);
+
+    if let Some(max_distance) = max_distance {
+        let max_sq = max_distance * max_distance;
+        let coords = tree.coords();
+        let indices = tree.indices();
+
+        let mut position_of_id = vec![0usize; indices.len()];
+        for position in 0..indices.len() {
+            position_of_id[indices.get(position)] = position;
+        }
+
+        ids.retain(|&id| {
+            let position = position_of_id[id as usize];
+            let dx = coords[position * 2] - qx;
+            let dy = coords[position * 2 + 1] - qy;
+            dx * dx + dy * dy <= max_sq
+        });
+    }
+
+    ids
+}
 
 enum PyKDTreeBuilderInner {
     Float32(KDTreeBuilder<f32>),
@@ -193,6 +235,8 @@ impl PyKDTreeBuilder {
 enum PyKDTreeInner {
     Float32(KDTree<f32>),
     Float64(KDTree<f64>),
+    ExternalFloat32(ExternalKDTree<f32>),
+    ExternalFloat64(ExternalKDTree<f64>),
 }
 
 impl PyKDTreeInner {
@@ -200,6 +244,8 @@ impl PyKDTreeInner {
         match self {
             Self::Float32(index) => index.node_size(),
             Self::Float64(index) => index.node_size(),
+            Self::ExternalFloat32(index) => index.node_size(),
+            Self::ExternalFloat64(index) => index.node_size(),
         }
     }
 
@@ -207,6 +253,8 @@ impl PyKDTreeInner {
         match self {
             Self::Float32(index) => index.num_items(),
             Self::Float64(index) => index.num_items(),
+            Self::ExternalFloat32(index) => index.num_items(),
+            Self::ExternalFloat64(index) => index.num_items(),
         }
     }
 
@@ -214,15 +262,131 @@ impl PyKDTreeInner {
         match self {
             Self::Float32(index) => index.as_ref(),
             Self::Float64(index) => index.as_ref(),
+            Self::ExternalFloat32(index) => index.buffer().as_slice(),
+            Self::ExternalFloat64(index) => index.buffer().as_slice(),
+        }
+    }
+
+    fn neighbors(
+        &self,
+        py: Python,
+        qx: f64,
+        qy: f64,
+        k: Option<usize>,
+        max_distance: Option<f64>,
+    ) -> Vec<u32> {
+        match self {
+            Self::Float32(tree) => py.allow_threads(move || {
+                neighbors_impl(
+                    tree,
+                    qx as f32,
+                    qy as f32,
+                    k,
+                    max_distance.map(|d| d as f32),
+                )
+            }),
+            Self::Float64(tree) => {
+                py.allow_threads(move || neighbors_impl(tree, qx, qy, k, max_distance))
+            }
+            Self::ExternalFloat32(tree) => py.allow_threads(move || {
+                neighbors_impl(
+                    tree,
+                    qx as f32,
+                    qy as f32,
+                    k,
+                    max_distance.map(|d| d as f32),
+                )
+            }),
+            Self::ExternalFloat64(tree) => {
+                py.allow_threads(move || neighbors_impl(tree, qx, qy, k, max_distance))
+            }
         }
     }
 }
 
+impl From<PyKDTreeRef> for PyKDTreeInner {
+    fn from(value: PyKDTreeRef) -> Self {
+        match value {
+            PyKDTreeRef::Float32(index) => Self::ExternalFloat32(index),
+            PyKDTreeRef::Float64(index) => Self::ExternalFloat64(index),
+        }
+    }
+}
+
+/// Build a `KDTree` in one shot from point coordinates, reusing [`PyKDTreeBuilder::add`]'s
+/// input-form dispatch so `from_interleaved`/`from_separated` stay in sync with `add` as new
+/// forms are supported there.
+///
+/// `x`'s length is the number of points regardless of form: a plain array, a `FixedSizeList[2]`,
+/// and a 2-field `Struct` are all one row per point.
+fn build_from_coords(
+    py: Python,
+    x: PyArray,
+    y: Option<PyArray>,
+    node_size: u16,
+    coord_type: Option<CoordType>,
+) -> PyResult<PyKDTree> {
+    let num_items = x.as_ref().len() as u32;
+    let mut builder = PyKDTreeBuilder::new(num_items, node_size, coord_type);
+    builder.add(py, x, y)?;
+    builder.finish()
+}
+
 #[pyclass(name = "KDTree", frozen)]
 pub struct PyKDTree(PyKDTreeInner);
 
 #[pymethods]
 impl PyKDTree {
+    /// Construct a KDTree from an existing KDTree buffer, without copying or re-sorting.
+    ///
+    /// You can pass any buffer protocol object into this constructor, such as a previously
+    /// persisted or memory-mapped buffer, or an Arrow buffer. The header is validated against
+    /// the buffer length, and the coordinate type is inferred from it.
+    #[classmethod]
+    fn from_buffer(_cls: &Bound<PyType>, buffer: PyKDTreeRef) -> Self {
+        Self(buffer.into())
+    }
+
+    /// Construct a KDTree from coordinates stored in a single interleaved array.
+    ///
+    /// Args:
+    ///     coords: a `FixedSizeList[2]` or 2-field `Struct` array of point coordinates.
+    ///     node_size: the number of items per node in the tree.
+    ///     coord_type: the coordinate type to build the tree with. Defaults to `Float64`; pass
+    ///         `Float32` to halve memory use for point sets that don't need `f64` precision.
+    #[classmethod]
+    #[pyo3(signature = (coords, *, node_size = DEFAULT_KDTREE_NODE_SIZE, coord_type = None))]
+    fn from_interleaved(
+        _cls: &Bound<PyType>,
+        py: Python,
+        coords: PyArray,
+        node_size: u16,
+        coord_type: Option<CoordType>,
+    ) -> PyResult<Self> {
+        build_from_coords(py, coords, None, node_size, coord_type)
+    }
+
+    /// Construct a KDTree from separated `x` and `y` coordinate arrays.
+    ///
+    /// Args:
+    ///     x: x coordinates of the points.
+    ///     y: y coordinates of the points.
+    ///     node_size: the number of items per node in the tree.
+    ///     coord_type: the coordinate type to build the tree with. Defaults to `Float64`; pass
+    ///         `Float32` to halve memory use for point sets that don't need `f64` precision.
+    #[classmethod]
+    #[pyo3(signature = (x, y, *, node_size = DEFAULT_KDTREE_NODE_SIZE, coord_type = None))]
+    fn from_separated(
+        _cls: &Bound<PyType>,
+        py: Python,
+        x: PyArray,
+        y: PyArray,
+        node_size: u16,
+        coord_type: Option<CoordType>,
+    ) -> PyResult<Self> {
+        build_from_coords(py, x, Some(y), node_size, coord_type)
+    }
+
     // pre PEP 688 buffer protocol
     pub unsafe fn __getbuffer__(
         slf: PyRef<'_, Self>,
@@ -255,4 +419,27 @@ impl PyKDTree {
             self.0.node_size()
         )
     }
+
+    /// Find the `k` nearest items to a query point, in ascending order of distance.
+    ///
+    /// Args:
+    ///     qx: x value of query point
+    ///     qy: y value of query point
+    ///     k: number of neighbors to find. If `None`, all items are returned.
+    ///     max_distance: if provided, filters out candidates farther away than this.
+    ///
+    /// Returns:
+    ///     A `PyArray1` of `UInt32` item indices.
+    #[pyo3(signature = (qx, qy, k = None, max_distance = None))]
+    fn neighbors<'py>(
+        &'py self,
+        py: Python<'py>,
+        qx: f64,
+        qy: f64,
+        k: Option<usize>,
+        max_distance: Option<f64>,
+    ) -> &'py PyArray1<u32> {
+        let result = self.0.neighbors(py, qx, qy, k, max_distance);
+        PyArray1::from_vec(py, result)
+    }
 }
@@ -29,7 +29,6 @@ impl<N: IndexableNum> KDTreeIndex<N> for ExternalKDTree<N> {
 }
 
 impl<N: IndexableNum> ExternalKDTree<N> {
-    #[allow(dead_code)]
     pub(crate) fn buffer(&self) -> &Arc<Buffer> {
         &self.buffer
     }
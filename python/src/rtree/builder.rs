@@ -1,8 +1,9 @@
-use arrow_array::builder::UInt32Builder;
+use arrow_array::builder::{ListBuilder, UInt32Builder};
 use arrow_array::cast::AsArray;
 use arrow_array::types::{Float32Type, Float64Type};
+use arrow_array::{Array, Float64Array, StructArray};
 use arrow_cast::cast;
-use arrow_schema::DataType;
+use arrow_schema::{DataType, Field, Fields};
 use geo_index::rtree::sort::{HilbertSort, STRSort};
 use geo_index::rtree::util::f64_box_to_f32;
 use geo_index::rtree::{RTree, RTreeBuilder, RTreeIndex, DEFAULT_RTREE_NODE_SIZE};
@@ -12,6 +13,7 @@ use pyo3::ffi;
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
 use pyo3_arrow::PyArray;
+use rayon::prelude::*;
 use std::os::raw::c_int;
 use std::sync::Arc;
 
@@ -378,6 +380,197 @@ impl PyRTreeInner {
             }
         }
     }
+
+    /// Run one range query per row of `min_x`/`min_y`/`max_x`/`max_y`, releasing the GIL and
+    /// spreading the queries across a rayon thread pool since each row is independent.
+    ///
+    /// `min_x`/`min_y`/`max_x`/`max_y` are first normalized to plain `Float64Array`s regardless of
+    /// which of `add`'s three input forms was used, then coerced to the tree's own coordinate type
+    /// per query box, reusing [`f64_box_to_f32`] so a Float32 tree can still be queried with
+    /// Float64 boxes.
+    fn search(
+        &self,
+        py: Python,
+        min_x: &Float64Array,
+        min_y: &Float64Array,
+        max_x: &Float64Array,
+        max_y: &Float64Array,
+    ) -> PyResult<PyObject> {
+        let results = self.search_per_query(py, min_x, min_y, max_x, max_y);
+
+        let mut builder = ListBuilder::new(UInt32Builder::new());
+        for result in results {
+            builder.append_value(result.into_iter().map(Some));
+        }
+        PyArray::from_array_ref(Arc::new(builder.finish())).to_arro3(py)
+    }
+
+    /// Like [`Self::search`], but return the per-query matches as a flat `Vec<u32>` plus a
+    /// GeoArrow-style `offsets` array (`offsets.len() == min_x.len() + 1`) instead of an Arrow
+    /// `ListArray`, for [`PyRTree::search_batch`].
+    fn search_batch(
+        &self,
+        py: Python,
+        min_x: &Float64Array,
+        min_y: &Float64Array,
+        max_x: &Float64Array,
+        max_y: &Float64Array,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let results = self.search_per_query(py, min_x, min_y, max_x, max_y);
+
+        let mut offsets = Vec::with_capacity(results.len() + 1);
+        offsets.push(0u32);
+        let mut indices = Vec::new();
+        for result in results {
+            indices.extend(result);
+            offsets.push(indices.len() as u32);
+        }
+        (indices, offsets)
+    }
+
+    /// Run one range query per row of `min_x`/`min_y`/`max_x`/`max_y` in parallel across a rayon
+    /// thread pool, releasing the GIL for the duration since each row is independent. Shared by
+    /// [`Self::search`] and [`Self::search_batch`], which only differ in how they repack the
+    /// per-query `Vec<u32>`s.
+    fn search_per_query(
+        &self,
+        py: Python,
+        min_x: &Float64Array,
+        min_y: &Float64Array,
+        max_x: &Float64Array,
+        max_y: &Float64Array,
+    ) -> Vec<Vec<u32>> {
+        assert_eq!(min_x.len(), min_y.len());
+        assert_eq!(min_x.len(), max_x.len());
+        assert_eq!(min_x.len(), max_y.len());
+
+        let min_x = min_x.values();
+        let min_y = min_y.values();
+        let max_x = max_x.values();
+        let max_y = max_y.values();
+
+        match self {
+            Self::Float32(tree) => py.allow_threads(move || {
+                (0..min_x.len())
+                    .into_par_iter()
+                    .map(|i| {
+                        let (min_x, min_y, max_x, max_y) =
+                            f64_box_to_f32(min_x[i], min_y[i], max_x[i], max_y[i]);
+                        tree.search(min_x, min_y, max_x, max_y)
+                    })
+                    .collect()
+            }),
+            Self::Float64(tree) => py.allow_threads(move || {
+                (0..min_x.len())
+                    .into_par_iter()
+                    .map(|i| tree.search(min_x[i], min_y[i], max_x[i], max_y[i]))
+                    .collect()
+            }),
+        }
+    }
+
+    /// Find the `k` nearest items to a query point, in ascending order of distance, via the same
+    /// best-first min-heap traversal as [`RTreeIndex::neighbors`].
+    fn neighbors(
+        &self,
+        py: Python,
+        qx: f64,
+        qy: f64,
+        k: Option<usize>,
+        max_distance: Option<f64>,
+    ) -> Vec<u32> {
+        match self {
+            Self::Float32(tree) => py.allow_threads(move || {
+                tree.neighbors(qx as f32, qy as f32, k, max_distance.map(|d| d as f32))
+            }),
+            Self::Float64(tree) => py.allow_threads(move || tree.neighbors(qx, qy, k, max_distance)),
+        }
+    }
+}
+
+/// Cast an arbitrary numeric array to `Float64Array`, for normalizing [`PyRTree::search`]'s
+/// separated/`FixedSizeList`/`Struct` input forms to a single representation before querying.
+fn cast_to_f64(array: &dyn Array) -> PyResult<Float64Array> {
+    assert_eq!(array.null_count(), 0, "Cannot pass array with null values");
+    let casted = cast(array, &DataType::Float64)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(casted.as_primitive::<Float64Type>().clone())
+}
+
+/// Normalize [`PyRTree::search`]/[`PyRTree::search_batch`]'s three accepted query box forms
+/// (separated `min_x`/`min_y`/`max_x`/`max_y` arrays, a `FixedSizeList[4]`, or a 4-field `Struct`)
+/// into four plain `Float64Array`s.
+fn parse_query_boxes(
+    min_x: PyArray,
+    min_y: Option<PyArray>,
+    max_x: Option<PyArray>,
+    max_y: Option<PyArray>,
+) -> PyResult<(Float64Array, Float64Array, Float64Array, Float64Array)> {
+    let min_x = min_x.as_ref();
+
+    match (min_x.data_type(), min_y, max_x, max_y) {
+        (DataType::FixedSizeList(_, list_size), min_y, max_x, max_y) => {
+            assert_eq!(
+                *list_size, 4,
+                "Expected list size to be 4 for fixed size list"
+            );
+            assert!(
+                min_y.is_none(),
+                "Cannot pass min_y when min_x is a FixedSizeList"
+            );
+            assert!(
+                max_x.is_none(),
+                "Cannot pass max_x when min_x is a FixedSizeList"
+            );
+            assert!(
+                max_y.is_none(),
+                "Cannot pass max_y when min_x is a FixedSizeList"
+            );
+
+            let values = cast_to_f64(min_x.as_fixed_size_list().values().as_ref())?;
+            let mut min_x = Vec::with_capacity(values.len() / 4);
+            let mut min_y = Vec::with_capacity(values.len() / 4);
+            let mut max_x = Vec::with_capacity(values.len() / 4);
+            let mut max_y = Vec::with_capacity(values.len() / 4);
+            for i in (0..values.len()).step_by(4) {
+                min_x.push(values.value(i));
+                min_y.push(values.value(i + 1));
+                max_x.push(values.value(i + 2));
+                max_y.push(values.value(i + 3));
+            }
+            Ok((
+                Float64Array::from(min_x),
+                Float64Array::from(min_y),
+                Float64Array::from(max_x),
+                Float64Array::from(max_y),
+            ))
+        }
+        (DataType::Struct(inner_fields), min_y, max_x, max_y) => {
+            assert_eq!(
+                inner_fields.len(),
+                4,
+                "Expected struct to have four inner fields"
+            );
+            assert!(min_y.is_none(), "Cannot pass min_y when min_x is a struct");
+            assert!(max_x.is_none(), "Cannot pass max_x when min_x is a struct");
+            assert!(max_y.is_none(), "Cannot pass max_y when min_x is a struct");
+
+            let struct_arr = min_x.as_struct();
+            Ok((
+                cast_to_f64(struct_arr.column(0).as_ref())?,
+                cast_to_f64(struct_arr.column(1).as_ref())?,
+                cast_to_f64(struct_arr.column(2).as_ref())?,
+                cast_to_f64(struct_arr.column(3).as_ref())?,
+            ))
+        }
+        (_, Some(min_y), Some(max_x), Some(max_y)) => Ok((
+            cast_to_f64(min_x)?,
+            cast_to_f64(min_y.as_ref())?,
+            cast_to_f64(max_x.as_ref())?,
+            cast_to_f64(max_y.as_ref())?,
+        )),
+        _ => Err(PyValueError::new_err("Unsupported argument types")),
+    }
 }
 
 #[pymethods]
@@ -444,4 +637,178 @@ impl PyRTree {
     fn boxes_at_level<'py>(&'py self, py: Python<'py>, level: usize) -> PyResult<PyObject> {
         self.0.boxes_at_level(py, level)
     }
+
+    /// Search this tree against a batch of query boxes, in any of the forms accepted by
+    /// [`RTreeBuilder.add`][crate::rtree::builder::PyRTreeBuilder::add]: separated
+    /// `min_x`/`min_y`/`max_x`/`max_y` arrays, a `FixedSizeList[4]`, or a 4-field `Struct`.
+    ///
+    /// Args:
+    ///     min_x: min x coordinates of the query boxes, or the full box array/struct.
+    ///     min_y: min y coordinates of the query boxes, if `min_x` is separated.
+    ///     max_x: max x coordinates of the query boxes, if `min_x` is separated.
+    ///     max_y: max y coordinates of the query boxes, if `min_x` is separated.
+    ///
+    /// Returns:
+    ///     A `ListArray` of `UInt32`, where element `i` holds the matched item indices for query
+    ///     box `i`.
+    #[pyo3(signature = (min_x, min_y = None, max_x = None, max_y = None))]
+    fn search(
+        &self,
+        py: Python,
+        min_x: PyArray,
+        min_y: Option<PyArray>,
+        max_x: Option<PyArray>,
+        max_y: Option<PyArray>,
+    ) -> PyResult<PyObject> {
+        let (min_x, min_y, max_x, max_y) = parse_query_boxes(min_x, min_y, max_x, max_y)?;
+        self.0.search(py, &min_x, &min_y, &max_x, &max_y)
+    }
+
+    /// Search this tree against a batch of query boxes like [`Self::search`], but return a flat
+    /// `PyArray1` of matched indices plus a GeoArrow-style `offsets` array instead of a
+    /// `ListArray`.
+    ///
+    /// Args:
+    ///     min_x: min x coordinates of the query boxes, or the full box array/struct.
+    ///     min_y: min y coordinates of the query boxes, if `min_x` is separated.
+    ///     max_x: max x coordinates of the query boxes, if `min_x` is separated.
+    ///     max_y: max y coordinates of the query boxes, if `min_x` is separated.
+    ///
+    /// Returns:
+    ///     A `(indices, offsets)` tuple, where `indices[offsets[i]..offsets[i + 1]]` holds the
+    ///     matched item indices for query box `i`. `offsets` has length `N + 1`.
+    #[pyo3(signature = (min_x, min_y = None, max_x = None, max_y = None))]
+    fn search_batch<'py>(
+        &'py self,
+        py: Python<'py>,
+        min_x: PyArray,
+        min_y: Option<PyArray>,
+        max_x: Option<PyArray>,
+        max_y: Option<PyArray>,
+    ) -> PyResult<(&'py PyArray1<u32>, &'py PyArray1<u32>)> {
+        let (min_x, min_y, max_x, max_y) = parse_query_boxes(min_x, min_y, max_x, max_y)?;
+        let (indices, offsets) = self.0.search_batch(py, &min_x, &min_y, &max_x, &max_y);
+        Ok((PyArray1::from_vec(py, indices), PyArray1::from_vec(py, offsets)))
+    }
+
+    /// Find the `k` nearest items to a query point, in ascending order of distance.
+    ///
+    /// Args:
+    ///     qx: x value of query point
+    ///     qy: y value of query point
+    ///     k: number of neighbors to find. If `None`, all items are returned.
+    ///     max_distance: if provided, stop once the next candidate's distance exceeds this.
+    ///
+    /// Returns:
+    ///     A `PyArray1` of `UInt32` item indices.
+    #[pyo3(signature = (qx, qy, k = None, max_distance = None))]
+    fn neighbors<'py>(
+        &'py self,
+        py: Python<'py>,
+        qx: f64,
+        qy: f64,
+        k: Option<usize>,
+        max_distance: Option<f64>,
+    ) -> &'py PyArray1<u32> {
+        let result = self.0.neighbors(py, qx, qy, k, max_distance);
+        PyArray1::from_vec(py, result)
+    }
+
+    /// Merge several already-built trees into a single combined index, re-deriving a single
+    /// Hilbert or STR ordering across the union of all their leaf boxes instead of re-reading raw
+    /// coordinates.
+    ///
+    /// This supports a map-reduce build pattern: index each partition in parallel on separate
+    /// workers, then stitch the finished trees back together with `concat`.
+    ///
+    /// Args:
+    ///     trees: the trees to merge. Must all share the same coordinate type.
+    ///     method: the sort method to use when building the combined tree. Defaults to
+    ///         `"hilbert"`.
+    ///
+    /// Returns:
+    ///     A tuple of the combined tree and a `Struct` array of `(source_tree, original_index)`,
+    ///     one row per item in the combined tree in its insertion order, mapping each combined
+    ///     item index back to which input tree and original item index it came from.
+    #[staticmethod]
+    #[pyo3(signature = (trees, method = None))]
+    fn concat(
+        py: Python,
+        trees: Vec<PyRef<PyRTree>>,
+        method: Option<RTreeMethod>,
+    ) -> PyResult<(PyRTree, PyObject)> {
+        if trees.is_empty() {
+            return Err(PyValueError::new_err("Cannot concat an empty list of trees"));
+        }
+
+        let method = method.unwrap_or(RTreeMethod::Hilbert);
+        let total_items: u32 = trees.iter().map(|tree| tree.0.num_items()).sum();
+        let mut source_trees = UInt32Builder::with_capacity(total_items as usize);
+        let mut original_indices = UInt32Builder::with_capacity(total_items as usize);
+
+        let combined = match &trees[0].0 {
+            PyRTreeInner::Float32(_) => {
+                let mut builder = RTreeBuilder::<f32>::new(total_items);
+                for (source_tree_id, tree) in trees.iter().enumerate() {
+                    let PyRTreeInner::Float32(tree) = &tree.0 else {
+                        return Err(PyValueError::new_err(
+                            "All trees passed to concat must share the same coordinate type",
+                        ));
+                    };
+                    let boxes = tree
+                        .boxes_at_level(0)
+                        .map_err(|err| PyIndexError::new_err(err.to_string()))?;
+                    let indices = tree.indices();
+                    for pos in (0..boxes.len()).step_by(4) {
+                        builder.add(boxes[pos], boxes[pos + 1], boxes[pos + 2], boxes[pos + 3]);
+                        source_trees.append_value(source_tree_id as u32);
+                        original_indices.append_value(indices.get(pos / 4) as u32);
+                    }
+                }
+                match method {
+                    RTreeMethod::Hilbert => PyRTreeInner::Float32(builder.finish::<HilbertSort>()),
+                    RTreeMethod::STR => PyRTreeInner::Float32(builder.finish::<STRSort>()),
+                }
+            }
+            PyRTreeInner::Float64(_) => {
+                let mut builder = RTreeBuilder::<f64>::new(total_items);
+                for (source_tree_id, tree) in trees.iter().enumerate() {
+                    let PyRTreeInner::Float64(tree) = &tree.0 else {
+                        return Err(PyValueError::new_err(
+                            "All trees passed to concat must share the same coordinate type",
+                        ));
+                    };
+                    let boxes = tree
+                        .boxes_at_level(0)
+                        .map_err(|err| PyIndexError::new_err(err.to_string()))?;
+                    let indices = tree.indices();
+                    for pos in (0..boxes.len()).step_by(4) {
+                        builder.add(boxes[pos], boxes[pos + 1], boxes[pos + 2], boxes[pos + 3]);
+                        source_trees.append_value(source_tree_id as u32);
+                        original_indices.append_value(indices.get(pos / 4) as u32);
+                    }
+                }
+                match method {
+                    RTreeMethod::Hilbert => PyRTreeInner::Float64(builder.finish::<HilbertSort>()),
+                    RTreeMethod::STR => PyRTreeInner::Float64(builder.finish::<STRSort>()),
+                }
+            }
+        };
+
+        let mapping = StructArray::try_new(
+            Fields::from(vec![
+                Field::new("source_tree", DataType::UInt32, false),
+                Field::new("original_index", DataType::UInt32, false),
+            ]),
+            vec![
+                Arc::new(source_trees.finish()),
+                Arc::new(original_indices.finish()),
+            ],
+            None,
+        )
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        let mapping = PyArray::from_array_ref(Arc::new(mapping)).to_arro3(py)?.unbind();
+
+        Ok((PyRTree(combined), mapping))
+    }
 }
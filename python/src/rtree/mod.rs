@@ -6,6 +6,7 @@ mod metadata;
 mod neighbors;
 mod partitions;
 mod search;
+mod search_batch;
 
 use pyo3::intern;
 use pyo3::prelude::*;
@@ -24,12 +25,16 @@ pub fn register_rtree_module(
     child_module.add_class::<builder::PyRTree>()?;
     child_module.add_class::<builder::PyRTreeBuilder>()?;
     child_module.add_class::<metadata::PyRTreeMetadata>()?;
+    child_module.add_class::<search::PySearchIterator>()?;
     child_module.add_wrapped(wrap_pyfunction!(boxes_at_level::boxes_at_level))?;
     child_module.add_wrapped(wrap_pyfunction!(intersection::tree_join))?;
     child_module.add_wrapped(wrap_pyfunction!(neighbors::neighbors))?;
     child_module.add_wrapped(wrap_pyfunction!(partitions::partition_boxes))?;
     child_module.add_wrapped(wrap_pyfunction!(partitions::partitions))?;
+    child_module.add_wrapped(wrap_pyfunction!(partitions::partitions_for_box))?;
     child_module.add_wrapped(wrap_pyfunction!(search::search))?;
+    child_module.add_wrapped(wrap_pyfunction!(search::search_iter))?;
+    child_module.add_wrapped(wrap_pyfunction!(search_batch::search_batch))?;
 
     parent_module.add_submodule(&child_module)?;
 
@@ -8,6 +8,22 @@ use pyo3_arrow::PyArray;
 
 use crate::rtree::input::PyRTreeRef;
 
+/// Find the nearest items in an RTree to a query point, in ascending order of distance.
+///
+/// This is a zero-copy counterpart to
+/// [`RTree.neighbors`][crate::rtree::builder::PyRTree::neighbors] that takes a borrowed
+/// `PyRTreeRef` (any buffer implementing the Python buffer protocol) instead of requiring an
+/// owned, already-built tree.
+///
+/// Args:
+///     index: the RTree (or a buffer containing one) to search.
+///     x: x value of the query point.
+///     y: y value of the query point.
+///     max_results: maximum number of neighbors to return. If `None`, all items are returned.
+///     max_distance: if provided, stop once the next candidate's distance exceeds this.
+///
+/// Returns:
+///     A `UInt32Array` of item indices, closest first.
 #[pyfunction]
 #[pyo3(signature = (index, x, y, *, max_results = None, max_distance = None))]
 pub fn neighbors(
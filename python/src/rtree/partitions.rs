@@ -4,9 +4,11 @@ use arrow_array::builder::{UInt16Builder, UInt32Builder};
 use arrow_array::types::{UInt16Type, UInt32Type};
 use arrow_array::{ArrayRef, RecordBatch, UInt16Array, UInt32Array};
 use arrow_buffer::alloc::Allocation;
+use arrow_buffer::ScalarBuffer;
 use arrow_schema::{Field, Schema};
 use geo_index::indices::Indices;
 use geo_index::rtree::RTreeIndex;
+use pyo3::exceptions::PyIndexError;
 use pyo3::prelude::*;
 use pyo3_arrow::{PyArray, PyRecordBatch};
 
@@ -101,3 +103,49 @@ pub fn partition_boxes(py: Python, index: PyRTreeRef) -> PyResult<PyObject> {
     PyRecordBatch::new(RecordBatch::try_new(schema.into(), vec![array, partition_ids]).unwrap())
         .to_arro3(py)
 }
+
+/// Find the partitions (see [`partitions`]/[`partition_boxes`]) whose bounding box intersects
+/// the given query box.
+///
+/// This walks the tree from the root down to the partition level, pruning subtrees whose boxes
+/// are disjoint from the query instead of scanning every partition. Engines like
+/// DataFusion/Dask can use the returned `partition_id`s to read only the row groups/files that
+/// can contain matches for a query, rather than scanning all of them.
+///
+/// Args:
+///     min_x: min x coordinate of bounding box
+///     min_y: min y coordinate of bounding box
+///     max_x: max x coordinate of bounding box
+///     max_y: max y coordinate of bounding box
+#[pyfunction]
+pub fn partitions_for_box(
+    py: Python,
+    index: PyRTreeRef,
+    min_x: Bound<PyAny>,
+    min_y: Bound<PyAny>,
+    max_x: Bound<PyAny>,
+    max_y: Bound<PyAny>,
+) -> PyResult<Py<PyAny>> {
+    let partition_ids = match index {
+        PyRTreeRef::Float32(tree) => tree
+            .partitions_for_box(
+                min_x.extract()?,
+                min_y.extract()?,
+                max_x.extract()?,
+                max_y.extract()?,
+            )
+            .map_err(|err| PyIndexError::new_err(err.to_string()))?,
+        PyRTreeRef::Float64(tree) => tree
+            .partitions_for_box(
+                min_x.extract()?,
+                min_y.extract()?,
+                max_x.extract()?,
+                max_y.extract()?,
+            )
+            .map_err(|err| PyIndexError::new_err(err.to_string()))?,
+    };
+    let partition_ids = UInt32Array::new(ScalarBuffer::from(partition_ids), None);
+    Ok(PyArray::from_array_ref(Arc::new(partition_ids))
+        .to_arro3(py)?
+        .unbind())
+}
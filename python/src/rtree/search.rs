@@ -3,10 +3,11 @@ use std::sync::Arc;
 use arrow_array::UInt32Array;
 use arrow_buffer::ScalarBuffer;
 use geo_index::rtree::RTreeIndex;
+use geo_index::IndexableNum;
 use pyo3::prelude::*;
 use pyo3_arrow::PyArray;
 
-use crate::rtree::input::PyRTreeRef;
+use crate::rtree::input::{ExternalRTree, PyRTreeRef};
 
 /// Search an RTree given the provided bounding box.
 ///
@@ -45,3 +46,177 @@ pub fn search(
         .to_arro3(py)?
         .unbind())
 }
+
+/// Search an RTree given the provided bounding box, returning a lazy Python iterator of matching
+/// item indices instead of eagerly collecting them into an array.
+///
+/// This walks the tree with an explicit `(level, node offset)` stack rather than [`search`]'s
+/// recursive visitor, so the traversal can pause between `__next__` calls: a caller that stops
+/// iterating early (e.g. after the first match, or because a `limit` was hit) never visits the
+/// rest of the tree.
+///
+/// Args:
+///     min_x: min x coordinate of bounding box
+///     min_y: min y coordinate of bounding box
+///     max_x: max x coordinate of bounding box
+///     max_y: max y coordinate of bounding box
+///     limit: maximum number of indices to yield. If `None`, all matches are yielded.
+#[pyfunction]
+#[pyo3(signature = (index, min_x, min_y, max_x, max_y, *, limit = None))]
+pub fn search_iter(
+    index: PyRTreeRef,
+    min_x: Bound<PyAny>,
+    min_y: Bound<PyAny>,
+    max_x: Bound<PyAny>,
+    max_y: Bound<PyAny>,
+    limit: Option<usize>,
+) -> PyResult<PySearchIterator> {
+    let inner = match index {
+        PyRTreeRef::Float32(tree) => SearchIteratorInner::Float32 {
+            stack: root_stack_frame(&tree),
+            tree,
+            min_x: min_x.extract()?,
+            min_y: min_y.extract()?,
+            max_x: max_x.extract()?,
+            max_y: max_y.extract()?,
+        },
+        PyRTreeRef::Float64(tree) => SearchIteratorInner::Float64 {
+            stack: root_stack_frame(&tree),
+            tree,
+            min_x: min_x.extract()?,
+            min_y: min_y.extract()?,
+            max_x: max_x.extract()?,
+            max_y: max_y.extract()?,
+        },
+    };
+    Ok(PySearchIterator { inner, limit, yielded: 0 })
+}
+
+/// The single `(level, node offset)` frame covering the whole tree, i.e. its root node.
+///
+/// Level `0` is the base of the tree (matching [`RTreeIndex::boxes_at_level`]'s convention), so
+/// the root starts at `num_levels() - 1`. An empty tree has no root box to push.
+fn root_stack_frame<N: IndexableNum>(tree: &ExternalRTree<N>) -> Vec<(usize, usize)> {
+    let boxes = tree.boxes();
+    if boxes.is_empty() {
+        return Vec::new();
+    }
+    vec![(tree.num_levels() - 1, boxes.len() - 4)]
+}
+
+/// The smallest entry of the sorted `level_bounds` that is strictly greater than `value`.
+///
+/// Mirrors `geo_index`'s private `rtree::util::upper_bound`, which caps a node's children range
+/// at the end of its level since the last node in a level may have fewer than `node_size`
+/// children. Reimplemented here since that helper isn't exported across the crate boundary.
+fn upper_bound(value: usize, level_bounds: &[usize]) -> usize {
+    let idx = level_bounds.partition_point(|&bound| bound <= value);
+    level_bounds[idx]
+}
+
+/// Pop `(level, node offset)` frames from `stack` until a leaf item overlapping the query box is
+/// found (returning its index) or the stack is exhausted (returning `None`).
+///
+/// Interior frames whose box overlaps the query push each child back onto the stack as its own
+/// frame one level down; frames whose box is disjoint from the query are dropped without
+/// descending into their children.
+fn advance<N: IndexableNum>(
+    tree: &ExternalRTree<N>,
+    min_x: N,
+    min_y: N,
+    max_x: N,
+    max_y: N,
+    stack: &mut Vec<(usize, usize)>,
+) -> Option<u32> {
+    let boxes = tree.boxes();
+    let indices = tree.indices();
+
+    while let Some((level, pos)) = stack.pop() {
+        let (node_min_x, node_min_y, node_max_x, node_max_y) =
+            (boxes[pos], boxes[pos + 1], boxes[pos + 2], boxes[pos + 3]);
+
+        if max_x < node_min_x || max_y < node_min_y || min_x > node_max_x || min_y > node_max_y {
+            continue;
+        }
+
+        if level == 0 {
+            return Some(indices.get(pos >> 2) as u32);
+        }
+
+        let start = indices.get(pos >> 2);
+        let end = (start + tree.node_size() as usize * 4).min(upper_bound(start, tree.level_bounds()));
+        for child_pos in (start..end).step_by(4).rev() {
+            stack.push((level - 1, child_pos));
+        }
+    }
+
+    None
+}
+
+enum SearchIteratorInner {
+    Float32 {
+        tree: ExternalRTree<f32>,
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+        stack: Vec<(usize, usize)>,
+    },
+    Float64 {
+        tree: ExternalRTree<f64>,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+        stack: Vec<(usize, usize)>,
+    },
+}
+
+impl SearchIteratorInner {
+    fn advance(&mut self) -> Option<u32> {
+        match self {
+            Self::Float32 {
+                tree,
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+                stack,
+            } => advance(tree, *min_x, *min_y, *max_x, *max_y, stack),
+            Self::Float64 {
+                tree,
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+                stack,
+            } => advance(tree, *min_x, *min_y, *max_x, *max_y, stack),
+        }
+    }
+}
+
+/// A lazy iterator of item indices matching a search query, returned by [`search_iter`].
+#[pyclass(name = "SearchIterator")]
+pub struct PySearchIterator {
+    inner: SearchIteratorInner,
+    limit: Option<usize>,
+    yielded: usize,
+}
+
+#[pymethods]
+impl PySearchIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<u32> {
+        if self.limit.is_some_and(|limit| self.yielded >= limit) {
+            return None;
+        }
+        let next = self.inner.advance();
+        if next.is_some() {
+            self.yielded += 1;
+        }
+        next
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use arrow_array::builder::UInt32Builder;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type};
+use arrow_array::UInt32Array;
+use arrow_cast::cast;
+use arrow_schema::DataType;
+use geo_index::rtree::RTreeIndex;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3_arrow::PyArray;
+
+use crate::rtree::input::PyRTreeRef;
+
+/// Search an RTree against a batch of query boxes, returning a flattened join table.
+///
+/// This is equivalent to calling [`search`][super::search::search] once per row of
+/// `min_x`/`min_y`/`max_x`/`max_y`, but shares the tree and a single pair of output
+/// buffers across every query instead of paying the Python call overhead per box, which matters
+/// when joining millions of query rectangles against the index.
+///
+/// Args:
+///     min_x: min x coordinates of the query boxes
+///     min_y: min y coordinates of the query boxes
+///     max_x: max x coordinates of the query boxes
+///     max_y: max y coordinates of the query boxes
+///
+/// Returns:
+///     A `(query_indices, result_indices)` tuple of `UInt32Array`, where row `i` means query
+///     `query_indices[i]` matched item `result_indices[i]`.
+#[pyfunction]
+pub fn search_batch(
+    py: Python,
+    index: PyRTreeRef,
+    min_x: PyArray,
+    min_y: PyArray,
+    max_x: PyArray,
+    max_y: PyArray,
+) -> PyResult<(PyObject, PyObject)> {
+    let min_x = min_x.as_ref();
+    let min_y = min_y.as_ref();
+    let max_x = max_x.as_ref();
+    let max_y = max_y.as_ref();
+
+    let num_queries = min_x.len();
+    if min_y.len() != num_queries || max_x.len() != num_queries || max_y.len() != num_queries {
+        return Err(PyValueError::new_err(
+            "min_x, min_y, max_x, and max_y must have the same length",
+        ));
+    }
+    if min_x.null_count() > 0 || min_y.null_count() > 0 || max_x.null_count() > 0 || max_y.null_count() > 0
+    {
+        return Err(PyValueError::new_err("Cannot pass arrays with null values"));
+    }
+
+    let mut query_indices = UInt32Builder::new();
+    let mut result_indices = UInt32Builder::new();
+
+    match &index {
+        PyRTreeRef::Float32(tree) => {
+            let min_x = cast(min_x, &DataType::Float32).unwrap();
+            let min_y = cast(min_y, &DataType::Float32).unwrap();
+            let max_x = cast(max_x, &DataType::Float32).unwrap();
+            let max_y = cast(max_y, &DataType::Float32).unwrap();
+            let min_x = min_x.as_primitive::<Float32Type>();
+            let min_y = min_y.as_primitive::<Float32Type>();
+            let max_x = max_x.as_primitive::<Float32Type>();
+            let max_y = max_y.as_primitive::<Float32Type>();
+
+            for i in 0..num_queries {
+                let results =
+                    tree.search(min_x.value(i), min_y.value(i), max_x.value(i), max_y.value(i));
+                query_indices.append_n(results.len(), i as u32);
+                result_indices.append_slice(&results);
+            }
+        }
+        PyRTreeRef::Float64(tree) => {
+            let min_x = cast(min_x, &DataType::Float64).unwrap();
+            let min_y = cast(min_y, &DataType::Float64).unwrap();
+            let max_x = cast(max_x, &DataType::Float64).unwrap();
+            let max_y = cast(max_y, &DataType::Float64).unwrap();
+            let min_x = min_x.as_primitive::<Float64Type>();
+            let min_y = min_y.as_primitive::<Float64Type>();
+            let max_x = max_x.as_primitive::<Float64Type>();
+            let max_y = max_y.as_primitive::<Float64Type>();
+
+            for i in 0..num_queries {
+                let results =
+                    tree.search(min_x.value(i), min_y.value(i), max_x.value(i), max_y.value(i));
+                query_indices.append_n(results.len(), i as u32);
+                result_indices.append_slice(&results);
+            }
+        }
+    };
+
+    let query_indices: UInt32Array = query_indices.finish();
+    let result_indices: UInt32Array = result_indices.finish();
+
+    Ok((
+        PyArray::from_array_ref(Arc::new(query_indices))
+            .to_arro3(py)?
+            .unbind(),
+        PyArray::from_array_ref(Arc::new(result_indices))
+            .to_arro3(py)?
+            .unbind(),
+    ))
+}
@@ -0,0 +1,135 @@
+//! Shared framing for the pluggable, checksummed compressed buffer formats used by
+//! [`crate::rtree::OwnedRTree::to_compressed`] and [`crate::kdtree::OwnedKDTree::to_compressed`].
+//!
+//! Each tree's compression module only has to compress its own ABI header (8 bytes, always
+//! stored uncompressed and readable) and hand the rest of its buffer to [`write_compressed_stream`]
+//! / [`read_compressed_stream`], which take care of the codec, the xxh3 checksum, and the framing
+//! header recording both the codec and the compressed/uncompressed lengths.
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::error::{GeoIndexError, Result};
+
+/// Bit set in the version nibble (the upper 4 bits of header byte 1) to mark a compressed
+/// stream, so it can be told apart from a plain, uncompressed tree buffer without first
+/// decompressing it.
+pub(crate) const COMPRESSED_FLAG: u8 = 0x80;
+
+/// Size, in bytes, of the framing header that follows the 8-byte ABI header in a compressed
+/// stream: 1 codec tag byte, 1 codec parameter byte, an 8-byte uncompressed length, an 8-byte
+/// compressed length, and an 8-byte xxh3 checksum of the uncompressed body.
+const FRAME_SIZE: usize = 1 + 1 + 8 + 8 + 8;
+
+/// Which block codec compresses the body of a compressed tree buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionType {
+    /// Store the body as-is; only the xxh3 checksum and length framing are added.
+    None,
+    /// LZ4 block compression: fast to compress and decompress.
+    Lz4,
+    /// Deflate-family compression via `miniz_oxide`, at the given level (0..=10, higher means
+    /// smaller but slower).
+    Miniz(u8),
+}
+
+impl CompressionType {
+    fn to_tag(self) -> (u8, u8) {
+        match self {
+            Self::None => (0, 0),
+            Self::Lz4 => (1, 0),
+            Self::Miniz(level) => (2, level),
+        }
+    }
+
+    fn from_tag(tag: u8, level: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Lz4),
+            2 => Ok(Self::Miniz(level)),
+            _ => Err(GeoIndexError::General(format!(
+                "Unknown compression codec tag {tag}."
+            ))),
+        }
+    }
+
+    fn compress(self, body: &[u8]) -> Vec<u8> {
+        match self {
+            Self::None => body.to_vec(),
+            Self::Lz4 => lz4_flex::compress(body),
+            Self::Miniz(level) => miniz_oxide::deflate::compress_to_vec(body, level),
+        }
+    }
+
+    fn decompress(self, body: &[u8], uncompressed_len: usize) -> Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(body.to_vec()),
+            Self::Lz4 => lz4_flex::decompress(body, uncompressed_len)
+                .map_err(|err| GeoIndexError::General(format!("Failed to decompress: {err}"))),
+            Self::Miniz(_) => miniz_oxide::inflate::decompress_to_vec(body)
+                .map_err(|err| GeoIndexError::General(format!("Failed to decompress: {err:?}"))),
+        }
+    }
+}
+
+/// Compress `body` (everything after a tree's 8-byte ABI header) under `compression`, and frame
+/// it behind `header` so the result can be told apart from, and later converted back into, a
+/// plain uncompressed buffer.
+pub(crate) fn write_compressed_stream(
+    header: &[u8; 8],
+    body: &[u8],
+    compression: CompressionType,
+) -> Vec<u8> {
+    let checksum = xxh3_64(body);
+    let compressed_body = compression.compress(body);
+    let (codec_tag, codec_param) = compression.to_tag();
+
+    let mut out = Vec::with_capacity(header.len() + FRAME_SIZE + compressed_body.len());
+    out.extend_from_slice(header);
+    out[1] |= COMPRESSED_FLAG;
+    out.push(codec_tag);
+    out.push(codec_param);
+    out.extend_from_slice(&(body.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(compressed_body.len() as u64).to_le_bytes());
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out.extend_from_slice(&compressed_body);
+    out
+}
+
+/// Reverse [`write_compressed_stream`], decompressing and checksum-verifying a stream back into
+/// a plain buffer with the original 8-byte ABI header, ready to be handed to a `*Metadata::from_slice`.
+pub(crate) fn read_compressed_stream(data: &[u8]) -> Result<Vec<u8>> {
+    let frame_start = 8;
+    if data.len() < frame_start + FRAME_SIZE || data[1] & COMPRESSED_FLAG == 0 {
+        return Err(GeoIndexError::General(
+            "Data is not in a compressed format.".to_string(),
+        ));
+    }
+
+    let compression = CompressionType::from_tag(data[8], data[9])?;
+    let uncompressed_len = u64::from_le_bytes(data[10..18].try_into().unwrap()) as usize;
+    let compressed_len = u64::from_le_bytes(data[18..26].try_into().unwrap()) as usize;
+    let checksum = u64::from_le_bytes(data[26..34].try_into().unwrap());
+
+    let body_buf = data.get(34..34 + compressed_len).ok_or(GeoIndexError::TooShort {
+        expected: 34 + compressed_len,
+        actual: data.len(),
+    })?;
+    let body = compression.decompress(body_buf, uncompressed_len)?;
+    if body.len() != uncompressed_len {
+        return Err(GeoIndexError::LengthMismatch {
+            expected: uncompressed_len,
+            actual: body.len(),
+        });
+    }
+    if xxh3_64(&body) != checksum {
+        return Err(GeoIndexError::General(
+            "Checksum mismatch: buffer is corrupt.".to_string(),
+        ));
+    }
+
+    let mut buffer = Vec::with_capacity(8 + uncompressed_len);
+    buffer.extend_from_slice(&data[0..8]);
+    buffer[1] &= !COMPRESSED_FLAG;
+    buffer.extend_from_slice(&body);
+    Ok(buffer)
+}
@@ -7,6 +7,42 @@ pub enum GeoIndexError {
     /// General errors
     #[error("General error: {0}")]
     General(String),
+
+    /// The byte slice is too short to contain a valid header, or too short for the
+    /// boxes/indices/coords regions its header describes.
+    #[error("Buffer too short: expected at least {expected} bytes, got {actual}")]
+    TooShort {
+        /// The minimum number of bytes required.
+        expected: usize,
+        /// The number of bytes actually present.
+        actual: usize,
+    },
+
+    /// The byte slice's length doesn't match the length implied by its header.
+    #[error("Length mismatch: expected {expected} bytes, got {actual}")]
+    LengthMismatch {
+        /// The number of bytes implied by the header.
+        expected: usize,
+        /// The number of bytes actually present.
+        actual: usize,
+    },
+
+    /// A region of the buffer wasn't aligned correctly to be cast to its target numeric type.
+    #[error("Misaligned buffer: {0}")]
+    Misaligned(String),
+
+    /// A buffer passed the header-only checks in `*Metadata::from_slice`/`*Ref::try_new` but
+    /// failed a deeper structural check performed by `validate()`, e.g. a child box escaping its
+    /// parent's box, an out-of-range leaf index, or a KD level that isn't correctly partitioned
+    /// around its median.
+    #[error("Structural validation failed at root->{path:?}: {reason}")]
+    Invalid {
+        /// What invariant failed, at the node named by `path`.
+        reason: String,
+        /// The `(level, node-offset)` of every node from the root down to the offending one,
+        /// inclusive.
+        path: Vec<(usize, usize)>,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, GeoIndexError>;
@@ -0,0 +1,472 @@
+//! A dynamic, insert-capable wrapper around the immutable [`OwnedFlatbush`].
+//!
+//! [`FlatbushBuilder`]/[`FlatbushIndex`] produce an immutable, bulk-loaded tree with no way to add
+//! items after `finish`. [`DynamicFlatbush`] restores incremental insertion by applying the same
+//! "logarithmic method" of dynamization that [`DynamicKDTree`][crate::kdtree::DynamicKDTree] and
+//! [`DynamicRTree`][crate::rtree::DynamicRTree] use on top of their own static builders: a small
+//! linear buffer absorbs new inserts, and once it fills its contents are merged with existing
+//! trees and rebuilt into a single new immutable tree, the way a binary counter carries.
+
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::flatbush::{FlatbushBuilder, FlatbushIndex, HilbertSort, OwnedFlatbush};
+use crate::r#type::IndexableNum;
+
+/// The number of bits of buffer capacity: the linear buffer holds up to `1 << BUFFER_BITS` items
+/// before it is flushed into a tree.
+const BUFFER_BITS: u32 = 6;
+
+/// Once a tree's live fraction (non-tombstoned items) drops below this threshold, it is rebuilt
+/// during the next compaction pass to reclaim space.
+const COMPACTION_THRESHOLD: f64 = 0.5;
+
+struct BufferedItem<N: IndexableNum, D> {
+    id: u64,
+    min_x: N,
+    min_y: N,
+    max_x: N,
+    max_y: N,
+    data: D,
+}
+
+/// One occupied slot of the dynamization forest: an immutable [`OwnedFlatbush`] together with the
+/// global item id and user data for each of its local leaf positions.
+struct Slot<N: IndexableNum, D> {
+    tree: OwnedFlatbush<N>,
+    ids: Vec<u64>,
+    data: Vec<D>,
+    /// Each item's `[min_x, min_y, max_x, max_y]`, in insertion order (the tree's own `boxes()`
+    /// holds its leaves in Hilbert-sorted order instead).
+    boxes: Vec<N>,
+    live_count: usize,
+}
+
+/// A dynamic, insert-capable Flatbush index.
+///
+/// This wraps [`FlatbushBuilder`]/[`OwnedFlatbush`] with the classic dynamization scheme used to
+/// add incremental insertion to an otherwise-static structure: a small flat buffer absorbs new
+/// inserts and is searched linearly, while a vector of optional immutable trees holds
+/// geometrically-sized batches (slot `i`, when occupied, holds at most `2^(i + BUFFER_BITS)`
+/// items). When the buffer fills, its items plus every occupied consecutive low slot are merged
+/// and rebuilt into one new tree placed at the first empty slot, amortizing rebuild cost to
+/// `O(log n)` per insert.
+///
+/// Deletion is logical: a tombstone set is consulted at query time, and [`Self::compact`] rebuilds
+/// any slot whose live fraction has dropped below a threshold.
+///
+/// ```
+/// use geo_index::flatbush::DynamicFlatbush;
+///
+/// let mut tree = DynamicFlatbush::<f64>::new();
+/// let id0 = tree.insert(0., 0., 1., 1., "a");
+/// let id1 = tree.insert(5., 5., 6., 6., "b");
+///
+/// let results = tree.search(0., 0., 2., 2.);
+/// assert_eq!(results, vec![id0]);
+///
+/// tree.remove(id1);
+/// assert_eq!(tree.search(0., 0., 10., 10.), vec![id0]);
+/// ```
+pub struct DynamicFlatbush<N: IndexableNum, D = u64> {
+    buffer: Vec<BufferedItem<N, D>>,
+    slots: Vec<Option<Slot<N, D>>>,
+    tombstones: HashSet<u64>,
+    next_id: u64,
+}
+
+impl<N: IndexableNum, D: Clone> Default for DynamicFlatbush<N, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: IndexableNum, D: Clone> DynamicFlatbush<N, D> {
+    /// Create a new, empty dynamic Flatbush index.
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::with_capacity(1 << BUFFER_BITS),
+            slots: Vec::new(),
+            tombstones: HashSet::new(),
+            next_id: 0,
+        }
+    }
+
+    /// The total number of live (non-deleted) items in this tree.
+    pub fn len(&self) -> usize {
+        self.buffer
+            .iter()
+            .filter(|item| !self.tombstones.contains(&item.id))
+            .count()
+            + self
+                .slots
+                .iter()
+                .filter_map(|slot| slot.as_ref().map(|s| s.live_count))
+                .sum::<usize>()
+    }
+
+    /// Returns `true` if this tree contains no live items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a new rectangle with associated data, returning a stable id that can later be
+    /// passed to [`Self::remove`].
+    pub fn insert(&mut self, min_x: N, min_y: N, max_x: N, max_y: N, data: D) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.buffer.push(BufferedItem {
+            id,
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            data,
+        });
+
+        if self.buffer.len() >= 1 << BUFFER_BITS {
+            self.flush_buffer();
+        }
+
+        id
+    }
+
+    /// Logically delete an item by id. The item is skipped by future queries but its storage is
+    /// only reclaimed the next time its containing slot is compacted.
+    pub fn remove(&mut self, id: u64) {
+        if !self.tombstones.insert(id) {
+            return;
+        }
+        for slot in self.slots.iter_mut().flatten() {
+            if slot.ids.contains(&id) {
+                slot.live_count = slot.live_count.saturating_sub(1);
+                break;
+            }
+        }
+    }
+
+    /// Search for items whose bounding box intersects the query rectangle.
+    pub fn search(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Vec<u64> {
+        let mut results = Vec::new();
+
+        for item in &self.buffer {
+            if self.tombstones.contains(&item.id) {
+                continue;
+            }
+            if item.max_x < min_x || item.min_x > max_x || item.max_y < min_y || item.min_y > max_y
+            {
+                continue;
+            }
+            results.push(item.id);
+        }
+
+        for slot in self.slots.iter().flatten() {
+            for local_index in slot.tree.search(min_x, min_y, max_x, max_y) {
+                let id = slot.ids[local_index];
+                if !self.tombstones.contains(&id) {
+                    results.push(id);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Find the items in order of increasing distance from `(x, y)`.
+    ///
+    /// Since each slot is its own independently-sorted tree, this queries the buffer and every
+    /// occupied tree for their own candidates and merges the per-component results, the same way
+    /// [`FlatbushIndex::neighbors`] merges subtree results internally.
+    pub fn neighbors(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+    ) -> Vec<u64> {
+        let mut candidates: Vec<DynamicNeighbor> = Vec::new();
+
+        for item in &self.buffer {
+            if self.tombstones.contains(&item.id) {
+                continue;
+            }
+            let dist = box_dist(x, y, item.min_x, item.min_y, item.max_x, item.max_y);
+            if max_distance.is_some_and(|max_distance| dist > max_distance * max_distance) {
+                continue;
+            }
+            candidates.push(DynamicNeighbor {
+                dist: dist.to_f64().unwrap_or(f64::MAX),
+                id: item.id,
+            });
+        }
+
+        for slot in self.slots.iter().flatten() {
+            // Over-fetch from each tree so tombstoned items within its own prefix don't starve
+            // the live merged result of candidates that would otherwise have made the cut.
+            for local_index in slot.tree.neighbors(x, y, None, max_distance) {
+                let id = slot.ids[local_index];
+                if self.tombstones.contains(&id) {
+                    continue;
+                }
+                let boxes = &slot.boxes;
+                let pos = local_index * 4;
+                let dist = box_dist(
+                    x,
+                    y,
+                    boxes[pos],
+                    boxes[pos + 1],
+                    boxes[pos + 2],
+                    boxes[pos + 3],
+                );
+                candidates.push(DynamicNeighbor {
+                    dist: dist.to_f64().unwrap_or(f64::MAX),
+                    id,
+                });
+            }
+        }
+
+        let mut heap: BinaryHeap<DynamicNeighbor> = BinaryHeap::new();
+        for candidate in candidates {
+            heap.push(candidate);
+        }
+        let mut sorted = heap.into_sorted_vec();
+        if let Some(max_results) = max_results {
+            sorted.truncate(max_results);
+        }
+        sorted.into_iter().map(|c| c.id).collect()
+    }
+
+    /// Rebuild any slot whose live fraction has dropped below [`COMPACTION_THRESHOLD`], reclaiming
+    /// the space occupied by tombstoned items.
+    pub fn compact(&mut self) {
+        for slot_opt in self.slots.iter_mut() {
+            let needs_compaction = match slot_opt {
+                Some(slot) if slot.tree.num_items() > 0 => {
+                    (slot.live_count as f64) / (slot.tree.num_items() as f64) < COMPACTION_THRESHOLD
+                }
+                _ => false,
+            };
+            if !needs_compaction {
+                continue;
+            }
+
+            let slot = slot_opt.take().unwrap();
+            let live: Vec<_> = slot
+                .ids
+                .iter()
+                .zip(slot.data.iter())
+                .enumerate()
+                .filter(|(_, (id, _))| !self.tombstones.contains(id))
+                .map(|(local_index, (&id, data))| {
+                    let boxes = &slot.boxes;
+                    let pos = local_index * 4;
+                    (
+                        id,
+                        boxes[pos],
+                        boxes[pos + 1],
+                        boxes[pos + 2],
+                        boxes[pos + 3],
+                        data.clone(),
+                    )
+                })
+                .collect();
+
+            if live.is_empty() {
+                *slot_opt = None;
+                continue;
+            }
+
+            *slot_opt = Some(build_slot(live));
+        }
+    }
+
+    fn flush_buffer(&mut self) {
+        let mut items: Vec<_> = self
+            .buffer
+            .drain(..)
+            .map(|item| {
+                (
+                    item.id, item.min_x, item.min_y, item.max_x, item.max_y, item.data,
+                )
+            })
+            .collect();
+
+        // Merge with every occupied consecutive low slot, like carrying a binary counter.
+        let mut slot_index = 0;
+        loop {
+            match self.slots.get_mut(slot_index) {
+                Some(slot @ Some(_)) => {
+                    let occupied = slot.take().unwrap();
+                    for (local_index, (id, data)) in
+                        occupied.ids.into_iter().zip(occupied.data).enumerate()
+                    {
+                        let boxes = &occupied.boxes;
+                        let pos = local_index * 4;
+                        items.push((
+                            id,
+                            boxes[pos],
+                            boxes[pos + 1],
+                            boxes[pos + 2],
+                            boxes[pos + 3],
+                            data,
+                        ));
+                    }
+                    slot_index += 1;
+                }
+                Some(None) => break,
+                None => {
+                    self.slots.push(None);
+                    break;
+                }
+            }
+        }
+
+        let mut new_slot = build_slot(items);
+        new_slot.live_count = new_slot
+            .ids
+            .iter()
+            .filter(|id| !self.tombstones.contains(id))
+            .count();
+        self.slots[slot_index] = Some(new_slot);
+    }
+}
+
+/// Squared point-to-box distance (`dx * dx + dy * dy`) matching [`FlatbushIndex::neighbors`]'s
+/// own `axis_dist` pruning, so the merged ordering here agrees with each per-tree traversal.
+fn box_dist<N: IndexableNum>(x: N, y: N, min_x: N, min_y: N, max_x: N, max_y: N) -> N {
+    let dx = axis_dist(x, min_x, max_x);
+    let dy = axis_dist(y, min_y, max_y);
+    dx * dx + dy * dy
+}
+
+#[inline]
+fn axis_dist<N: IndexableNum>(k: N, min: N, max: N) -> N {
+    if k < min {
+        min - k
+    } else if k <= max {
+        N::zero()
+    } else {
+        k - max
+    }
+}
+
+/// A candidate `(distance, id)` pair in [`DynamicFlatbush::neighbors`]'s merge step, ordered so
+/// the closest candidate sorts first via [`BinaryHeap::into_sorted_vec`].
+struct DynamicNeighbor {
+    dist: f64,
+    id: u64,
+}
+
+impl PartialEq for DynamicNeighbor {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+
+impl Eq for DynamicNeighbor {}
+
+impl PartialOrd for DynamicNeighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DynamicNeighbor {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist
+            .partial_cmp(&other.dist)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+fn build_slot<N: IndexableNum, D>(items: Vec<(u64, N, N, N, N, D)>) -> Slot<N, D> {
+    let mut builder = FlatbushBuilder::<N>::new(items.len() as u32);
+    let mut ids = Vec::with_capacity(items.len());
+    let mut data = Vec::with_capacity(items.len());
+    let mut boxes = Vec::with_capacity(items.len() * 4);
+    for (id, min_x, min_y, max_x, max_y, item_data) in items {
+        builder.add(min_x, min_y, max_x, max_y);
+        ids.push(id);
+        data.push(item_data);
+        boxes.extend_from_slice(&[min_x, min_y, max_x, max_y]);
+    }
+    let tree = builder.finish::<HilbertSort>();
+    let live_count = ids.len();
+    Slot {
+        tree,
+        ids,
+        data,
+        boxes,
+        live_count,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_and_search_within_buffer() {
+        let mut tree = DynamicFlatbush::<f64>::new();
+        let id0 = tree.insert(0., 0., 1., 1., 0u64);
+        let id1 = tree.insert(5., 5., 6., 6., 1u64);
+        assert_eq!(tree.search(0., 0., 2., 2.), vec![id0]);
+        assert_eq!(tree.len(), 2);
+        let _ = id1;
+    }
+
+    #[test]
+    fn flushes_buffer_into_a_tree() {
+        let mut tree = DynamicFlatbush::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..100 {
+            let x = i as f64;
+            ids.push(tree.insert(x, x, x + 1., x + 1., i));
+        }
+        assert_eq!(tree.len(), 100);
+        let results = tree.search(0., 0., 3., 3.);
+        assert!(results.contains(&ids[0]));
+        assert!(results.contains(&ids[1]));
+        assert!(results.contains(&ids[2]));
+    }
+
+    #[test]
+    fn remove_is_logical_and_hides_results() {
+        let mut tree = DynamicFlatbush::<f64>::new();
+        let id0 = tree.insert(0., 0., 1., 1., "a");
+        tree.remove(id0);
+        assert_eq!(tree.search(0., 0., 1., 1.), Vec::<u64>::new());
+        assert_eq!(tree.len(), 0);
+    }
+
+    #[test]
+    fn compact_reclaims_tombstoned_slots() {
+        let mut tree = DynamicFlatbush::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..64 {
+            let x = i as f64;
+            ids.push(tree.insert(x, x, x + 1., x + 1., i));
+        }
+        for &id in &ids[..40] {
+            tree.remove(id);
+        }
+        tree.compact();
+        assert_eq!(tree.len(), 24);
+        for &id in &ids[40..] {
+            assert!(tree.search(0., 0., 100., 100.).contains(&id));
+        }
+    }
+
+    #[test]
+    fn neighbors_merges_across_buffer_and_trees() {
+        let mut tree = DynamicFlatbush::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..70 {
+            let x = i as f64;
+            ids.push(tree.insert(x, 0., x + 1., 0., i));
+        }
+        // First 64 items flushed into a tree, remaining 6 still in the buffer.
+        let nearest = tree.neighbors(0., 0., Some(3), None);
+        assert_eq!(nearest, vec![ids[0], ids[1], ids[2]]);
+    }
+}
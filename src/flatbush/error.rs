@@ -0,0 +1,33 @@
+use std::fmt::Debug;
+use thiserror::Error;
+
+/// Errors raised while decoding a Flatbush buffer.
+#[derive(Error, Debug)]
+pub enum FlatbushError {
+    /// General errors
+    #[error("General error: {0}")]
+    General(String),
+
+    /// The byte slice is too short to contain a valid Flatbush header, or too short for the
+    /// boxes/indices regions its header describes.
+    #[error("Buffer too short: expected at least {expected} bytes, got {actual}")]
+    TooShort {
+        /// The minimum number of bytes required.
+        expected: usize,
+        /// The number of bytes actually present.
+        actual: usize,
+    },
+
+    /// The byte slice's length doesn't match the length implied by its header.
+    #[error("Length mismatch: expected {expected} bytes, got {actual}")]
+    LengthMismatch {
+        /// The number of bytes implied by the header.
+        expected: usize,
+        /// The number of bytes actually present.
+        actual: usize,
+    },
+
+    /// A region of the buffer wasn't aligned correctly to be cast to its target numeric type.
+    #[error("Misaligned buffer: {0}")]
+    Misaligned(String),
+}
@@ -1,7 +1,5 @@
 use std::marker::PhantomData;
 
-use bytemuck::cast_slice;
-
 use crate::flatbush::constants::VERSION;
 use crate::flatbush::error::FlatbushError;
 use crate::flatbush::r#trait::FlatbushIndex;
@@ -9,6 +7,25 @@ use crate::flatbush::util::compute_num_nodes;
 use crate::indices::Indices;
 use crate::r#type::IndexableNum;
 
+/// The size, in bytes, of the flatbush-ABI header preceding the boxes/indices regions.
+const HEADER_SIZE: usize = 8;
+
+/// Like [`bytemuck::try_cast_slice`], but maps the error to a [`FlatbushError::Misaligned`].
+fn try_cast_slice<T: bytemuck::Pod, U: bytemuck::Pod>(
+    region: &[T],
+) -> Result<&[U], FlatbushError> {
+    bytemuck::try_cast_slice(region).map_err(|err| FlatbushError::Misaligned(format!("{err:?}")))
+}
+
+/// Slice `data[start..end]`, returning [`FlatbushError::TooShort`] instead of panicking if
+/// `data` isn't long enough.
+fn checked_slice(data: &[u8], start: usize, end: usize) -> Result<&[u8], FlatbushError> {
+    data.get(start..end).ok_or(FlatbushError::TooShort {
+        expected: end,
+        actual: data.len(),
+    })
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct OwnedFlatbush<N: IndexableNum> {
     pub(crate) buffer: Vec<u8>,
@@ -53,9 +70,22 @@ pub struct FlatbushRef<'a, N: IndexableNum> {
 }
 
 impl<'a, N: IndexableNum> FlatbushRef<'a, N> {
+    /// Construct a new [`FlatbushRef`] from an external byte slice, fully validating the buffer
+    /// before casting any of it, rather than trusting the header.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `data` is too short for its header, too short or too long for the
+    /// tree its header describes, or if a region can't be safely cast to its target numeric type
+    /// because of misalignment.
     pub fn try_new<T: AsRef<[u8]>>(data: &'a T) -> Result<Self, FlatbushError> {
         let data = data.as_ref();
-        // TODO: validate length of slice?
+        if data.len() < HEADER_SIZE {
+            return Err(FlatbushError::TooShort {
+                expected: HEADER_SIZE,
+                actual: data.len(),
+            });
+        }
 
         let magic = data[0];
         if magic != 0xfb {
@@ -72,10 +102,15 @@ impl<'a, N: IndexableNum> FlatbushRef<'a, N> {
             ));
         }
 
-        let node_size: u16 = cast_slice(&data[2..4])[0];
-        let num_items: u32 = cast_slice(&data[4..8])[0];
+        let node_size: u16 = try_cast_slice::<u8, u16>(&data[2..4])?[0];
+        let num_items: u32 = try_cast_slice::<u8, u32>(&data[4..8])?[0];
         let node_size = node_size as usize;
         let num_items = num_items as usize;
+        if !(2..=65535).contains(&node_size) {
+            return Err(FlatbushError::General(format!(
+                "Invalid node size {node_size}; must be in 2..=65535."
+            )));
+        }
 
         let (num_nodes, level_bounds) = compute_num_nodes(num_items, node_size);
 
@@ -83,10 +118,27 @@ impl<'a, N: IndexableNum> FlatbushRef<'a, N> {
         let nodes_byte_length = num_nodes * 4 * N::BYTES_PER_ELEMENT;
         let indices_byte_length = num_nodes * indices_bytes_per_element;
 
-        // TODO: assert length of `data` matches expected
-        let boxes = cast_slice(&data[8..8 + nodes_byte_length]);
-        let indices_buf = &data[8 + nodes_byte_length..8 + nodes_byte_length + indices_byte_length];
-        let indices = Indices::new(indices_buf, num_nodes);
+        let expected_len = HEADER_SIZE + nodes_byte_length + indices_byte_length;
+        if expected_len != data.len() {
+            return Err(FlatbushError::LengthMismatch {
+                expected: expected_len,
+                actual: data.len(),
+            });
+        }
+
+        let boxes_buf = checked_slice(data, HEADER_SIZE, HEADER_SIZE + nodes_byte_length)?;
+        let boxes = try_cast_slice(boxes_buf)?;
+
+        let indices_buf = checked_slice(
+            data,
+            HEADER_SIZE + nodes_byte_length,
+            HEADER_SIZE + nodes_byte_length + indices_byte_length,
+        )?;
+        let indices = if indices_bytes_per_element == 2 {
+            Indices::U16(try_cast_slice(indices_buf)?)
+        } else {
+            Indices::U32(try_cast_slice(indices_buf)?)
+        };
 
         Ok(Self {
             boxes,
@@ -1,5 +1,6 @@
 pub mod builder;
 pub mod constants;
+pub mod dynamic;
 pub mod error;
 pub mod index;
 pub mod sort;
@@ -7,6 +8,7 @@ pub mod r#trait;
 pub mod util;
 
 pub use builder::FlatbushBuilder;
+pub use dynamic::DynamicFlatbush;
 pub use index::{FlatbushRef, OwnedFlatbush};
 pub use r#trait::FlatbushIndex;
 pub use sort::HilbertSort;
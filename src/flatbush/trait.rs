@@ -1,11 +1,15 @@
 use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 use bytemuck::cast_slice;
 
 use crate::flatbush::index::{FlatbushRef, OwnedFlatbush};
 use crate::flatbush::traversal::{IntersectionIterator, Node};
 use crate::indices::Indices;
+use crate::kdtree::SearchParameters;
 use crate::r#type::IndexableNum;
+use crate::rtree::SimpleDistanceMetric;
 
 pub trait FlatbushIndex<N: IndexableNum>: Sized {
     fn boxes(&self) -> &[N];
@@ -60,16 +64,214 @@ pub trait FlatbushIndex<N: IndexableNum>: Sized {
         results
     }
 
-    #[allow(unused_mut, unused_labels, unused_variables)]
-    fn neighbors(&self, x: N, y: N, max_distance: Option<N>) -> Vec<usize> {
+    /// Find the indexes of items in order of increasing distance from the given point.
+    ///
+    /// Traverses nodes in a best-first order using a min-heap keyed by squared box distance
+    /// (`axis_dist` on each axis), so the nearest items are discovered, and can be returned,
+    /// before farther-away subtrees are ever visited. Stops once `max_results` items have been
+    /// found or the next-closest candidate is farther than `max_distance`.
+    ///
+    /// This method uses squared Euclidean distance. For other distance metrics, such as
+    /// great-circle distance on longitude/latitude data, use
+    /// [`neighbors_metric`][Self::neighbors_metric].
+    fn neighbors(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+    ) -> Vec<usize> {
         let boxes = self.boxes();
         let indices = self.indices();
         let max_distance = max_distance.unwrap_or(N::max_value());
+        let max_dist_squared = max_distance * max_distance;
 
         let mut outer_node_index = Some(boxes.len() - 4);
+        let mut queue: BinaryHeap<Reverse<NeighborNode<N>>> = BinaryHeap::new();
+        let mut results = vec![];
+
+        'outer: while let Some(node_index) = outer_node_index {
+            // find the end index of the node
+            let end = (node_index + self.node_size() * 4)
+                .min(upper_bound(node_index, self.level_bounds()));
+
+            // add child nodes to the queue
+            for pos in (node_index..end).step_by(4) {
+                let index = indices.get(pos >> 2);
+
+                let dx = axis_dist(x, boxes[pos], boxes[pos + 2]);
+                let dy = axis_dist(y, boxes[pos + 1], boxes[pos + 3]);
+                let dist = dx * dx + dy * dy;
+                if dist > max_dist_squared {
+                    continue;
+                }
+
+                if node_index >= self.num_items() * 4 {
+                    // node (use even id)
+                    queue.push(Reverse(NeighborNode {
+                        id: index << 1,
+                        dist,
+                    }));
+                } else {
+                    // leaf item (use odd id)
+                    queue.push(Reverse(NeighborNode {
+                        id: (index << 1) + 1,
+                        dist,
+                    }));
+                }
+            }
 
+            // pop items from the queue
+            while !queue.is_empty() && queue.peek().is_some_and(|val| (val.0.id & 1) != 0) {
+                let dist = queue.peek().unwrap().0.dist;
+                if dist > max_dist_squared {
+                    break 'outer;
+                }
+                let item = queue.pop().unwrap();
+                results.push(item.0.id >> 1);
+                if max_results.is_some_and(|max_results| results.len() == max_results) {
+                    break 'outer;
+                }
+            }
+
+            if let Some(item) = queue.pop() {
+                outer_node_index = Some(item.0.id >> 1);
+            } else {
+                outer_node_index = None;
+            }
+        }
+
+        results
+    }
+
+    /// Find the indexes of items in order of increasing distance from the given point, under a
+    /// pluggable [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric] such as
+    /// [`HaversineDistance`][crate::rtree::HaversineDistance] or
+    /// [`SpheroidDistance`][crate::rtree::SpheroidDistance].
+    ///
+    /// [`neighbors`][Self::neighbors] hardcodes squared Euclidean distance, which is the wrong
+    /// notion of distance for longitude/latitude boxes. This runs the same best-first traversal,
+    /// using `metric`'s `cmp_distance_to_bbox` as the heap's ordering key (so a metric that can
+    /// order candidates more cheaply than computing an exact distance, like
+    /// [`EuclideanDistance`][crate::rtree::EuclideanDistance], doesn't have to). `max_distance`
+    /// is converted once into that same comparison space via `cmp_max_distance`, and both nodes
+    /// and leaves are cut off against it there.
+    fn neighbors_metric<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+        metric: &M,
+    ) -> Vec<usize> {
+        let boxes = self.boxes();
+        let indices = self.indices();
+        let max_distance = max_distance.unwrap_or(metric.max_distance());
+        let cmp_max_distance = metric.cmp_max_distance(max_distance);
+
+        let mut outer_node_index = Some(boxes.len() - 4);
+        let mut queue: BinaryHeap<Reverse<NeighborNode<N>>> = BinaryHeap::new();
         let mut results = vec![];
+
+        'outer: while let Some(node_index) = outer_node_index {
+            // find the end index of the node
+            let end = (node_index + self.node_size() * 4)
+                .min(upper_bound(node_index, self.level_bounds()));
+
+            // add child nodes to the queue
+            for pos in (node_index..end).step_by(4) {
+                let index = indices.get(pos >> 2);
+
+                let dist = metric.cmp_distance_to_bbox(
+                    x,
+                    y,
+                    boxes[pos],
+                    boxes[pos + 1],
+                    boxes[pos + 2],
+                    boxes[pos + 3],
+                );
+                if dist > cmp_max_distance {
+                    continue;
+                }
+
+                if node_index >= self.num_items() * 4 {
+                    // node (use even id)
+                    queue.push(Reverse(NeighborNode {
+                        id: index << 1,
+                        dist,
+                    }));
+                } else {
+                    // leaf item (use odd id)
+                    queue.push(Reverse(NeighborNode {
+                        id: (index << 1) + 1,
+                        dist,
+                    }));
+                }
+            }
+
+            // pop items from the queue
+            while !queue.is_empty() && queue.peek().is_some_and(|val| (val.0.id & 1) != 0) {
+                let dist = queue.peek().unwrap().0.dist;
+                if dist > cmp_max_distance {
+                    break 'outer;
+                }
+                let item = queue.pop().unwrap();
+                results.push(item.0.id >> 1);
+                if max_results.is_some_and(|max_results| results.len() == max_results) {
+                    break 'outer;
+                }
+            }
+
+            if let Some(item) = queue.pop() {
+                outer_node_index = Some(item.0.id >> 1);
+            } else {
+                outer_node_index = None;
+            }
+        }
+
+        results
+    }
+
+    /// Find the indexes of items in order of increasing distance from the given point, exposing
+    /// the approximate-pruning, radius-cap, and result-ordering knobs of
+    /// [`SearchParameters`][crate::kdtree::SearchParameters], plus a touch counter.
+    ///
+    /// Runs the same best-first traversal as [`neighbors`][Self::neighbors], except nodes and
+    /// items are pruned against `params.max_radius / (1+params.epsilon)` rather than
+    /// `params.max_radius` itself — the same relaxation
+    /// [`KDTreeIndex::within_approx`][crate::kdtree::KDTreeIndex::within_approx] applies to its own
+    /// radius — and `touch_count`, if `Some`, is incremented once per node or item that passes
+    /// that radius prune and is queued.
+    /// Since this tightens a radius cutoff, `params.epsilon` only has an effect when
+    /// `params.max_radius` is set; with no radius there is no finite bound left to tighten.
+    /// `params.sort_results` is accepted for parity with
+    /// [`KdbushIndex::nearest_advanced`][crate::kdbush::KdbushIndex::nearest_advanced]/
+    /// [`KDTreeIndex::nearest_advanced`][crate::kdtree::KDTreeIndex::nearest_advanced], but this
+    /// traversal always discovers items in ascending order already, so it has no effect here.
+    fn neighbors_advanced(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        params: &SearchParameters<N>,
+        touch_count: &mut Option<usize>,
+    ) -> Vec<usize> {
+        assert!(
+            params.epsilon >= N::zero(),
+            "epsilon must be non-negative, got {:?}",
+            params.epsilon
+        );
+
+        let boxes = self.boxes();
+        let indices = self.indices();
+        let max_distance = params.max_radius.unwrap_or(N::max_value());
         let max_dist_squared = max_distance * max_distance;
+        let epsilon_factor = N::one() + params.epsilon;
+        let prune_dist_squared = max_dist_squared / (epsilon_factor * epsilon_factor);
+
+        let mut outer_node_index = Some(boxes.len() - 4);
+        let mut queue: BinaryHeap<Reverse<NeighborNode<N>>> = BinaryHeap::new();
+        let mut results = vec![];
 
         'outer: while let Some(node_index) = outer_node_index {
             // find the end index of the node
@@ -83,17 +285,100 @@ pub trait FlatbushIndex<N: IndexableNum>: Sized {
                 let dx = axis_dist(x, boxes[pos], boxes[pos + 2]);
                 let dy = axis_dist(y, boxes[pos + 1], boxes[pos + 3]);
                 let dist = dx * dx + dy * dy;
-                if dist > max_dist_squared {
+                if dist > prune_dist_squared {
                     continue;
                 }
+                if let Some(count) = touch_count.as_mut() {
+                    *count += 1;
+                }
+
+                if node_index >= self.num_items() * 4 {
+                    // node (use even id)
+                    queue.push(Reverse(NeighborNode {
+                        id: index << 1,
+                        dist,
+                    }));
+                } else {
+                    // leaf item (use odd id)
+                    queue.push(Reverse(NeighborNode {
+                        id: (index << 1) + 1,
+                        dist,
+                    }));
+                }
             }
 
-            // break 'outer;
+            // pop items from the queue
+            while !queue.is_empty() && queue.peek().is_some_and(|val| (val.0.id & 1) != 0) {
+                let dist = queue.peek().unwrap().0.dist;
+                if dist > max_dist_squared {
+                    break 'outer;
+                }
+                let item = queue.pop().unwrap();
+                results.push(item.0.id >> 1);
+                if max_results.is_some_and(|max_results| results.len() == max_results) {
+                    break 'outer;
+                }
+            }
+
+            if let Some(item) = queue.pop() {
+                outer_node_index = Some(item.0.id >> 1);
+            } else {
+                outer_node_index = None;
+            }
         }
 
         results
     }
 
+    /// Find every indexed item that has the query point among its own `k` nearest neighbors —
+    /// the inverse of [`neighbors`][Self::neighbors].
+    ///
+    /// An item is a reverse neighbor of the query iff fewer than `k` *other* items are strictly
+    /// closer to it than the query point is. Rather than brute-forcing every pair of items, each
+    /// item's count is found via [`neighbors`][Self::neighbors] itself, bounded to the item's own
+    /// distance from the query — reusing the same bounding-box pruning that lets `neighbors` skip
+    /// whole subtrees instead of visiting every item. Every item's box is represented by its
+    /// minimum corner when measuring distance to other items, consistent with how `neighbors`
+    /// already treats the query as a point against other items' boxes.
+    fn reverse_neighbors(&self, x: N, y: N, k: usize) -> Vec<usize> {
+        let boxes = self.boxes();
+        let indices = self.indices();
+        let num_items = self.num_items();
+
+        let mut position_of = vec![0; num_items];
+        for pos in 0..num_items {
+            position_of[indices.get(pos)] = pos;
+        }
+
+        let mut result = vec![];
+        for pos in (0..num_items * 4).step_by(4) {
+            let id = indices.get(pos >> 2);
+
+            let dx = axis_dist(x, boxes[pos], boxes[pos + 2]);
+            let dy = axis_dist(y, boxes[pos + 1], boxes[pos + 3]);
+            let dist_to_query = dx * dx + dy * dy;
+
+            let probe_x = boxes[pos];
+            let probe_y = boxes[pos + 1];
+            let radius = dist_to_query.sqrt().unwrap_or(N::max_value());
+            let closer_count = self
+                .neighbors(probe_x, probe_y, None, Some(radius))
+                .into_iter()
+                .filter(|&other| {
+                    let p = position_of[other] * 4;
+                    let ox = axis_dist(probe_x, boxes[p], boxes[p + 2]);
+                    let oy = axis_dist(probe_y, boxes[p + 1], boxes[p + 3]);
+                    other != id && ox * ox + oy * oy < dist_to_query
+                })
+                .count();
+
+            if closer_count < k {
+                result.push(id);
+            }
+        }
+        result
+    }
+
     fn intersection_candidates_with_other_tree<'a>(
         &'a self,
         other: &'a impl FlatbushIndex<N>,
@@ -207,6 +492,31 @@ fn axis_dist<N: IndexableNum>(k: N, min: N, max: N) -> N {
     }
 }
 
+/// An entry in [`FlatbushIndex::neighbors`]'s priority queue.
+///
+/// `id` packs both the kind of entry and its position: the low bit is 0 for an interior node and
+/// 1 for a leaf item, with the actual node/item index in the remaining bits. This lets a single
+/// heap of this one type drive the traversal instead of a heap over a tagged enum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct NeighborNode<N: IndexableNum> {
+    id: usize,
+    dist: N,
+}
+
+impl<N: IndexableNum> Eq for NeighborNode<N> {}
+
+impl<N: IndexableNum> Ord for NeighborNode<N> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist.partial_cmp(&other.dist).unwrap()
+    }
+}
+
+impl<N: IndexableNum> PartialOrd for NeighborNode<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(test)]
 mod test {
     // Replication of tests from flatbush js
@@ -238,4 +548,64 @@ mod test {
             assert_eq!(results, expected);
         }
     }
+
+    mod neighbors {
+        use crate::flatbush::{FlatbushBuilder, FlatbushIndex, HilbertSort};
+
+        #[test]
+        fn finds_nearest_neighbors_in_order() {
+            let mut builder = FlatbushBuilder::<f64>::new(3);
+            builder.add(0., 0., 1., 1.);
+            builder.add(5., 5., 6., 6.);
+            builder.add(10., 10., 11., 11.);
+            let index = builder.finish::<HilbertSort>();
+
+            let results = index.neighbors(0., 0., None, None);
+            assert_eq!(results, vec![0, 1, 2]);
+        }
+
+        #[test]
+        fn honors_max_results_and_max_distance() {
+            let mut builder = FlatbushBuilder::<f64>::new(3);
+            builder.add(0., 0., 1., 1.);
+            builder.add(5., 5., 6., 6.);
+            builder.add(10., 10., 11., 11.);
+            let index = builder.finish::<HilbertSort>();
+
+            assert_eq!(index.neighbors(0., 0., Some(1), None), vec![0]);
+            assert_eq!(index.neighbors(0., 0., None, Some(1.0)), vec![0]);
+        }
+    }
+
+    mod reverse_neighbors {
+        use crate::flatbush::{FlatbushBuilder, FlatbushIndex, HilbertSort};
+
+        #[test]
+        fn finds_items_with_the_query_among_their_k_nearest() {
+            // 0 and 1 are mutual nearest neighbors; 2 is far enough away that the query is not
+            // among its 1 nearest neighbor (1 is closer to 2 than the query is).
+            let mut builder = FlatbushBuilder::<f64>::new(3);
+            builder.add(0., 0., 0., 0.);
+            builder.add(1., 0., 1., 0.);
+            builder.add(20., 0., 20., 0.);
+            let index = builder.finish::<HilbertSort>();
+
+            let mut result = index.reverse_neighbors(0.5, 0., 1);
+            result.sort();
+            assert_eq!(result, vec![0, 1]);
+        }
+
+        #[test]
+        fn larger_k_admits_more_reverse_neighbors() {
+            let mut builder = FlatbushBuilder::<f64>::new(3);
+            builder.add(0., 0., 0., 0.);
+            builder.add(1., 0., 1., 0.);
+            builder.add(20., 0., 20., 0.);
+            let index = builder.finish::<HilbertSort>();
+
+            let mut result = index.reverse_neighbors(0.5, 0., 3);
+            result.sort();
+            assert_eq!(result, vec![0, 1, 2]);
+        }
+    }
 }
@@ -0,0 +1,513 @@
+//! An approximate nearest-neighbor index using Hierarchical Navigable Small World graphs.
+//!
+//! Exact `k`-NN over [`RTreeIndex::neighbors_geometry`][crate::rtree::RTreeIndex::neighbors_geometry]
+//! gets slow as an index grows; [`Hnsw`] trades a small amount of recall for much faster queries
+//! by maintaining a multi-layer proximity graph instead of a bounding-box tree. It's built over
+//! the same [`DistanceMetric`]/[`GeometryAccessor`] abstractions the R-tree distance module uses,
+//! so it works for Euclidean, Haversine, or any custom metric alike.
+//!
+//! ## Creation
+//!
+//! [`Hnsw::build`] inserts every item from a [`GeometryAccessor`] one at a time: each item is
+//! assigned a top layer `l = floor(-ln(U)·mL)` (`U` uniform in `(0, 1]`, `mL = 1/ln(M)`), greedily
+//! descends from the current entry point down to `l + 1` with `ef = 1`, then from `l` down to `0`
+//! gathers `efConstruction` candidates per layer, keeps up to `M` of them via a diversity
+//! heuristic, and adds bidirectional edges (pruned back to `M` on upper layers, `2·M` on layer 0).
+//!
+//! ## Search
+//!
+//! [`Hnsw::search`] descends the same way, then runs an `ef`-bounded best-first search on layer 0
+//! and returns the closest `k` items.
+//!
+//! ## Example
+//!
+//! ```
+//! use geo_index::hnsw::Hnsw;
+//! use geo_index::rtree::distance::{EuclideanDistance, SliceGeometryAccessor};
+//! use geo_0_31::{Geometry, Point};
+//!
+//! let geometries: Vec<Geometry<f64>> = (0..50)
+//!     .map(|i| Geometry::Point(Point::new(i as f64, 0.0)))
+//!     .collect();
+//! let accessor = SliceGeometryAccessor::new(&geometries);
+//!
+//! let index = Hnsw::build(&accessor, geometries.len(), EuclideanDistance, 16, 100);
+//!
+//! let query = Geometry::Point(Point::new(10.2, 0.0));
+//! let results = index.search(&query, 3, 50);
+//! assert_eq!(results[0].0, 10);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use geo_0_31::Geometry;
+
+use crate::rtree::distance::{DistanceMetric, GeometryAccessor};
+
+/// A minimal xorshift64* pseudo-random generator.
+///
+/// HNSW's layer assignment needs a source of uniform randomness; this avoids pulling in an
+/// external RNG crate for a single draw per inserted item. It is not cryptographically secure
+/// and is only used internally during [`Hnsw::build`].
+struct Xorshift64 {
+    state: u64,
+}
+
+impl Xorshift64 {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed.max(1),
+        }
+    }
+
+    /// Returns a value uniformly distributed in `(0, 1)`.
+    fn next_open01(&mut self) -> f64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        let bits = x.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        ((bits >> 11) as f64 + 1.0) / ((1u64 << 53) as f64 + 1.0)
+    }
+}
+
+/// One layer's adjacency, stored as a flat `Vec<u32>` of edges with per-item offsets.
+///
+/// This matches the crate's buffer-oriented layout elsewhere: `edges[offsets[i]..offsets[i +
+/// 1]]` gives item `i`'s neighbors in this layer, with `offsets.len() == num_items + 1`. Items
+/// absent from this layer simply have an empty slice (`offsets[i] == offsets[i + 1]`).
+struct LayerGraph {
+    offsets: Vec<u32>,
+    edges: Vec<u32>,
+}
+
+impl LayerGraph {
+    fn neighbors(&self, item_index: usize) -> &[u32] {
+        let start = self.offsets[item_index] as usize;
+        let end = self.offsets[item_index + 1] as usize;
+        &self.edges[start..end]
+    }
+
+    fn from_adjacency(adjacency: &[Vec<u32>]) -> Self {
+        let mut offsets = Vec::with_capacity(adjacency.len() + 1);
+        let mut edges = Vec::new();
+        offsets.push(0u32);
+        for neighbors in adjacency {
+            edges.extend_from_slice(neighbors);
+            offsets.push(edges.len() as u32);
+        }
+        Self { offsets, edges }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct HeapItem {
+    item_index: u32,
+    dist: f64,
+}
+
+impl PartialEq for HeapItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+impl Eq for HeapItem {}
+impl PartialOrd for HeapItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for HeapItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist.partial_cmp(&other.dist).unwrap()
+    }
+}
+
+/// An approximate nearest-neighbor index over geometries, built as a Hierarchical Navigable
+/// Small World graph.
+///
+/// See the [module documentation][self] for the construction and search algorithms.
+pub struct Hnsw<M: DistanceMetric<f64>> {
+    metric: M,
+    geometries: Vec<Geometry<f64>>,
+    m: usize,
+    m_max0: usize,
+    ef_construction: usize,
+    layers: Vec<LayerGraph>,
+    entry_point: usize,
+}
+
+impl<M: DistanceMetric<f64>> Hnsw<M> {
+    /// Build an HNSW index over the first `num_items` geometries of `accessor`, using `metric`
+    /// for distance calculations.
+    ///
+    /// `m` controls the number of bidirectional edges created per item per layer (`2m` on layer
+    /// 0); higher `m` trades memory and build time for better recall. `ef_construction` controls
+    /// how many candidates are gathered per layer while inserting; higher values trade build
+    /// time for a higher-quality graph.
+    pub fn build<A: GeometryAccessor + ?Sized>(
+        accessor: &A,
+        num_items: usize,
+        metric: M,
+        m: usize,
+        ef_construction: usize,
+    ) -> Self {
+        let m = m.max(1);
+        let m_max0 = 2 * m;
+        let ml = 1.0 / (m as f64).ln();
+
+        let geometries: Vec<Geometry<f64>> = (0..num_items)
+            .map(|i| {
+                accessor
+                    .get_geometry(i)
+                    .cloned()
+                    .unwrap_or(Geometry::Point(geo_0_31::Point::new(0.0, 0.0)))
+            })
+            .collect();
+
+        let mut rng = Xorshift64::new(0x9E37_79B9_7F4A_7C15);
+        let mut adjacency: Vec<Vec<Vec<u32>>> = Vec::new();
+        let mut entry_point: Option<usize> = None;
+
+        for i in 0..num_items {
+            let level = (-rng.next_open01().ln() * ml).floor() as usize;
+            let top_layer_before_insert = adjacency.len().saturating_sub(1);
+            while adjacency.len() <= level {
+                adjacency.push(vec![Vec::new(); num_items]);
+            }
+
+            let Some(mut ep) = entry_point else {
+                entry_point = Some(i);
+                continue;
+            };
+
+            let top_layer = top_layer_before_insert;
+            let mut cur_dist = Self::distance(&metric, &geometries, ep, i);
+
+            // Phase 1: greedily descend from the top layer down to `level + 1` with `ef = 1`.
+            for lc in (level + 1..=top_layer).rev() {
+                loop {
+                    let mut moved = false;
+                    for &neighbor in adjacency[lc][ep].clone().iter() {
+                        let d = Self::distance(&metric, &geometries, neighbor as usize, i);
+                        if d < cur_dist {
+                            cur_dist = d;
+                            ep = neighbor as usize;
+                            moved = true;
+                        }
+                    }
+                    if !moved {
+                        break;
+                    }
+                }
+            }
+
+            // Phase 2: from `min(level, top_layer)` down to 0, gather candidates and connect.
+            for lc in (0..=level.min(top_layer)).rev() {
+                let candidates =
+                    Self::search_layer(&metric, &geometries, &adjacency[lc], i, ep, ef_construction);
+                let selected = Self::select_neighbors(&metric, &geometries, i, &candidates, m);
+
+                let max_degree = if lc == 0 { m_max0 } else { m };
+                for &neighbor in &selected {
+                    adjacency[lc][i].push(neighbor);
+                    let neighbor = neighbor as usize;
+                    adjacency[lc][neighbor].push(i as u32);
+                    if adjacency[lc][neighbor].len() > max_degree {
+                        let pruned = Self::select_neighbors(
+                            &metric,
+                            &geometries,
+                            neighbor,
+                            &adjacency[lc][neighbor].clone(),
+                            max_degree,
+                        );
+                        adjacency[lc][neighbor] = pruned;
+                    }
+                }
+                if let Some(&best) = selected.first() {
+                    ep = best as usize;
+                }
+            }
+
+            if level > top_layer_before_insert {
+                entry_point = Some(i);
+            }
+        }
+
+        let layers = adjacency
+            .iter()
+            .map(|layer_adj| LayerGraph::from_adjacency(layer_adj))
+            .collect();
+
+        Self {
+            metric,
+            geometries,
+            m,
+            m_max0,
+            ef_construction,
+            layers,
+            entry_point: entry_point.unwrap_or(0),
+        }
+    }
+
+    /// Find the approximate `k` nearest items to `query`, sorted by ascending distance.
+    ///
+    /// `ef` is the size of the dynamic candidate list used during the layer-0 search; larger
+    /// values trade query time for better recall, and `ef` is always treated as at least `k`.
+    pub fn search(&self, query: &Geometry<f64>, k: usize, ef: usize) -> Vec<(usize, f64)> {
+        if self.geometries.is_empty() || k == 0 {
+            return Vec::new();
+        }
+
+        let mut ep = self.entry_point;
+        let mut cur_dist = self.distance_to_query(query, ep);
+
+        let top_layer = self.layers.len().saturating_sub(1);
+        for lc in (1..=top_layer).rev() {
+            loop {
+                let mut moved = false;
+                for &neighbor in self.layers[lc].neighbors(ep) {
+                    let d = self.distance_to_query(query, neighbor as usize);
+                    if d < cur_dist {
+                        cur_dist = d;
+                        ep = neighbor as usize;
+                        moved = true;
+                    }
+                }
+                if !moved {
+                    break;
+                }
+            }
+        }
+
+        let ef = ef.max(k);
+        let candidates = self.search_layer_for_query(query, ep, ef);
+        let mut results: Vec<(usize, f64)> = candidates
+            .into_iter()
+            .map(|idx| (idx as usize, self.distance_to_query(query, idx as usize)))
+            .collect();
+        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        results.truncate(k);
+        results
+    }
+
+    /// The number of items in this index.
+    pub fn len(&self) -> usize {
+        self.geometries.len()
+    }
+
+    /// Returns `true` if this index holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.geometries.is_empty()
+    }
+
+    /// The maximum number of bidirectional edges created per item per layer above layer 0.
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    /// The maximum number of bidirectional edges created per item on layer 0 (`2 * m`).
+    pub fn m_max0(&self) -> usize {
+        self.m_max0
+    }
+
+    /// The number of candidates gathered per layer while inserting.
+    pub fn ef_construction(&self) -> usize {
+        self.ef_construction
+    }
+
+    fn distance(metric: &M, geometries: &[Geometry<f64>], a: usize, b: usize) -> f64 {
+        metric.distance_to_geometry(&geometries[a], &geometries[b])
+    }
+
+    fn distance_to_query(&self, query: &Geometry<f64>, item_index: usize) -> f64 {
+        self.metric
+            .distance_to_geometry(query, &self.geometries[item_index])
+    }
+
+    /// A best-first search of `ef` closest items to `geometries[query_index]`, starting from
+    /// `entry_point`, within a single layer's adjacency.
+    fn search_layer(
+        metric: &M,
+        geometries: &[Geometry<f64>],
+        layer_adj: &[Vec<u32>],
+        query_index: usize,
+        entry_point: usize,
+        ef: usize,
+    ) -> Vec<u32> {
+        let mut visited = HashSet::new();
+        visited.insert(entry_point as u32);
+        let d0 = Self::distance(metric, geometries, entry_point, query_index);
+
+        let mut candidates = BinaryHeap::new();
+        candidates.push(std::cmp::Reverse(HeapItem {
+            item_index: entry_point as u32,
+            dist: d0,
+        }));
+        let mut results = BinaryHeap::new();
+        results.push(HeapItem {
+            item_index: entry_point as u32,
+            dist: d0,
+        });
+
+        while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+            if current.dist > results.peek().unwrap().dist && results.len() >= ef {
+                break;
+            }
+            for &neighbor in &layer_adj[current.item_index as usize] {
+                if visited.insert(neighbor) {
+                    let d = Self::distance(metric, geometries, neighbor as usize, query_index);
+                    if results.len() < ef || d < results.peek().unwrap().dist {
+                        candidates.push(std::cmp::Reverse(HeapItem {
+                            item_index: neighbor,
+                            dist: d,
+                        }));
+                        results.push(HeapItem {
+                            item_index: neighbor,
+                            dist: d,
+                        });
+                        if results.len() > ef {
+                            results.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut out: Vec<(u32, f64)> = results
+            .into_iter()
+            .map(|item| (item.item_index, item.dist))
+            .collect();
+        out.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        out.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    /// Same as [`Self::search_layer`] but against an arbitrary query geometry, for layer 0 at
+    /// query time rather than against another indexed item during insertion.
+    fn search_layer_for_query(&self, query: &Geometry<f64>, entry_point: usize, ef: usize) -> Vec<u32> {
+        let layer_adj = &self.layers[0];
+        let mut visited = HashSet::new();
+        visited.insert(entry_point as u32);
+        let d0 = self.distance_to_query(query, entry_point);
+
+        let mut candidates = BinaryHeap::new();
+        candidates.push(std::cmp::Reverse(HeapItem {
+            item_index: entry_point as u32,
+            dist: d0,
+        }));
+        let mut results = BinaryHeap::new();
+        results.push(HeapItem {
+            item_index: entry_point as u32,
+            dist: d0,
+        });
+
+        while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+            if current.dist > results.peek().unwrap().dist && results.len() >= ef {
+                break;
+            }
+            for &neighbor in layer_adj.neighbors(current.item_index as usize) {
+                if visited.insert(neighbor) {
+                    let d = self.distance_to_query(query, neighbor as usize);
+                    if results.len() < ef || d < results.peek().unwrap().dist {
+                        candidates.push(std::cmp::Reverse(HeapItem {
+                            item_index: neighbor,
+                            dist: d,
+                        }));
+                        results.push(HeapItem {
+                            item_index: neighbor,
+                            dist: d,
+                        });
+                        if results.len() > ef {
+                            results.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut out: Vec<(u32, f64)> = results
+            .into_iter()
+            .map(|item| (item.item_index, item.dist))
+            .collect();
+        out.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        out.into_iter().map(|(idx, _)| idx).collect()
+    }
+
+    /// Select up to `m` of `candidates` for `geometries[query_index]` using the diversity
+    /// heuristic: a candidate is kept only if it's closer to the query than to any
+    /// already-selected neighbor, which spreads edges out instead of clustering them.
+    fn select_neighbors(
+        metric: &M,
+        geometries: &[Geometry<f64>],
+        query_index: usize,
+        candidates: &[u32],
+        m: usize,
+    ) -> Vec<u32> {
+        let mut by_distance: Vec<(u32, f64)> = candidates
+            .iter()
+            .map(|&c| (c, Self::distance(metric, geometries, c as usize, query_index)))
+            .collect();
+        by_distance.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        let mut selected: Vec<(u32, f64)> = Vec::with_capacity(m.min(by_distance.len()));
+        for (candidate, dist_to_query) in by_distance {
+            if selected.len() >= m {
+                break;
+            }
+            let is_diverse = selected.iter().all(|&(selected_item, _)| {
+                dist_to_query
+                    < Self::distance(metric, geometries, candidate as usize, selected_item as usize)
+            });
+            if is_diverse {
+                selected.push((candidate, dist_to_query));
+            }
+        }
+        selected.into_iter().map(|(c, _)| c).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rtree::distance::{EuclideanDistance, SliceGeometryAccessor};
+    use geo_0_31::Point;
+
+    #[test]
+    fn finds_approximate_nearest_neighbors() {
+        let geometries: Vec<Geometry<f64>> = (0..100)
+            .map(|i| Geometry::Point(Point::new(i as f64, 0.0)))
+            .collect();
+        let accessor = SliceGeometryAccessor::new(&geometries);
+        let index = Hnsw::build(&accessor, geometries.len(), EuclideanDistance, 8, 50);
+
+        let query = Geometry::Point(Point::new(42.3, 0.0));
+        let results = index.search(&query, 3, 50);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, 42);
+    }
+
+    #[test]
+    fn empty_index_returns_no_neighbors() {
+        let geometries: Vec<Geometry<f64>> = Vec::new();
+        let accessor = SliceGeometryAccessor::new(&geometries);
+        let index = Hnsw::build(&accessor, 0, EuclideanDistance, 8, 50);
+        assert!(index.is_empty());
+        let query = Geometry::Point(Point::new(0.0, 0.0));
+        assert!(index.search(&query, 3, 10).is_empty());
+    }
+
+    #[test]
+    fn returns_fewer_than_k_when_index_is_smaller() {
+        let geometries: Vec<Geometry<f64>> = vec![
+            Geometry::Point(Point::new(0.0, 0.0)),
+            Geometry::Point(Point::new(1.0, 1.0)),
+        ];
+        let accessor = SliceGeometryAccessor::new(&geometries);
+        let index = Hnsw::build(&accessor, geometries.len(), EuclideanDistance, 8, 50);
+        let query = Geometry::Point(Point::new(0.0, 0.0));
+        assert_eq!(index.search(&query, 5, 10).len(), 2);
+    }
+}
@@ -1,11 +1,13 @@
-//! Data structures to hold insertion and internal tree indices that may be either `u16` or `u32`
+//! Data structures to hold insertion and internal tree indices that may be `u8`, `u16`, or `u32`
 //! to save space.
 
 use bytemuck::{cast_slice, cast_slice_mut};
 
-/// A mutable slice of indices that may be either `u16` or `u32`.
+/// A mutable slice of indices that may be `u8`, `u16`, or `u32`.
 #[derive(Debug)]
 pub enum MutableIndices<'a> {
+    /// Indices stored as a u8 byte slice
+    U8(&'a mut [u8]),
     /// Indices stored as a u16 byte slice
     U16(&'a mut [u16]),
     /// Indices stored as a u32 byte slice
@@ -27,6 +29,7 @@ impl MutableIndices<'_> {
     #[allow(dead_code)]
     pub(crate) fn bytes_per_element(&self) -> usize {
         match self {
+            Self::U8(_) => 1,
             Self::U16(_) => 2,
             Self::U32(_) => 4,
         }
@@ -35,6 +38,7 @@ impl MutableIndices<'_> {
     #[inline]
     pub(crate) fn swap(&mut self, a: usize, b: usize) {
         match self {
+            Self::U8(arr) => arr.swap(a, b),
             Self::U16(arr) => arr.swap(a, b),
             Self::U32(arr) => arr.swap(a, b),
         }
@@ -44,6 +48,7 @@ impl MutableIndices<'_> {
     #[allow(dead_code)]
     pub(crate) fn get(&self, index: usize) -> usize {
         match self {
+            Self::U8(arr) => arr[index] as usize,
             Self::U16(arr) => arr[index] as usize,
             Self::U32(arr) => arr[index] as usize,
         }
@@ -52,6 +57,7 @@ impl MutableIndices<'_> {
     #[inline]
     pub(crate) fn set(&mut self, index: usize, value: usize) {
         match self {
+            Self::U8(arr) => arr[index] = value.try_into().unwrap(),
             Self::U16(arr) => arr[index] = value.try_into().unwrap(),
             Self::U32(arr) => arr[index] = value.try_into().unwrap(),
         }
@@ -60,6 +66,10 @@ impl MutableIndices<'_> {
     #[allow(dead_code)]
     pub(crate) fn split_at_mut(&mut self, mid: usize) -> (MutableIndices<'_>, MutableIndices<'_>) {
         match self {
+            Self::U8(arr) => {
+                let (left, right) = arr.split_at_mut(mid);
+                (MutableIndices::U8(left), MutableIndices::U8(right))
+            }
             Self::U16(arr) => {
                 let (left, right) = arr.split_at_mut(mid);
                 (MutableIndices::U16(left), MutableIndices::U16(right))
@@ -74,6 +84,7 @@ impl MutableIndices<'_> {
     #[allow(dead_code)]
     pub(crate) fn chunks_mut(&mut self, chunk_size: usize) -> Vec<MutableIndices<'_>> {
         match self {
+            Self::U8(arr) => arr.chunks_mut(chunk_size).map(MutableIndices::U8).collect(),
             Self::U16(arr) => arr
                 .chunks_mut(chunk_size)
                 .map(MutableIndices::U16)
@@ -86,9 +97,11 @@ impl MutableIndices<'_> {
     }
 }
 
-/// A slice of indices that may be either `u16` or `u32`.
+/// A slice of indices that may be `u8`, `u16`, or `u32`.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Indices<'a> {
+    /// Indices stored as a u8 byte slice
+    U8(&'a [u8]),
     /// Indices stored as a u16 byte slice
     U16(&'a [u16]),
     /// Indices stored as a u32 byte slice
@@ -109,6 +122,7 @@ impl Indices<'_> {
     /// The number of indices in this byte slice
     pub fn len(&self) -> usize {
         match self {
+            Self::U8(arr) => arr.len(),
             Self::U16(arr) => arr.len(),
             Self::U32(arr) => arr.len(),
         }
@@ -121,10 +135,11 @@ impl Indices<'_> {
 
     /// A helper to access a single index from this slice.
     ///
-    /// Values are casted from u16 or u32 to usize.
+    /// Values are casted from u8, u16, or u32 to usize.
     #[inline]
     pub fn get(&self, index: usize) -> usize {
         match self {
+            Self::U8(arr) => arr[index] as usize,
             Self::U16(arr) => arr[index] as usize,
             Self::U32(arr) => arr[index] as usize,
         }
@@ -2,10 +2,12 @@ pub mod builder;
 pub mod constants;
 pub mod error;
 pub mod index;
+pub mod nd;
 pub mod r#trait;
 
 pub use builder::KdbushBuilder;
 pub use index::{KdbushRef, OwnedKdbush};
+pub use nd::{NdKdbushBuilder, NdKdbushIndex, NdKdbushRef, OwnedNdKdbush};
 pub use r#trait::KdbushIndex;
 
 #[cfg(test)]
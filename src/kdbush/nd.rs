@@ -0,0 +1,524 @@
+//! An N-dimensional generalization of the 2D point index in [`super`].
+//!
+//! [`KdbushBuilder`][crate::kdbush::KdbushBuilder] and [`KdbushIndex`][crate::kdbush::KdbushIndex]
+//! are hardwired to 2D `(x, y)` points, matching the upstream JS `kdbush` library byte-for-byte.
+//! [`NdKdbushBuilder`]/[`NdKdbushIndex`] lift that same on-disk layout and kd-sort algorithm to a
+//! compile-time dimension `D`, storing coordinates interleaved as `D`-tuples and splitting on axis
+//! `depth % D` during the build, so point clouds, voxel grids, or time+space data can be indexed
+//! without leaving the 2D-only format behind. The existing 2D types remain the dedicated,
+//! upstream-compatible implementation; reach for this module when `D` genuinely varies or exceeds
+//! 2.
+//!
+//! ```
+//! use geo_index::kdbush::nd::{NdKdbushBuilder, NdKdbushIndex};
+//!
+//! let mut builder = NdKdbushBuilder::<3>::new(2);
+//! builder.add([0., 0., 0.]);
+//! builder.add([1., 1., 1.]);
+//! let index = builder.finish();
+//!
+//! assert_eq!(index.within([0., 0., 0.], 0.5), vec![0]);
+//! ```
+
+use std::borrow::Cow;
+use std::cmp;
+use std::marker::PhantomData;
+
+use arrayvec::ArrayVec;
+use bytemuck::{cast_slice, cast_slice_mut};
+
+use crate::indices::{Indices, MutableIndices};
+use crate::kdbush::constants::{KDBUSH_HEADER_SIZE, KDBUSH_MAGIC, KDBUSH_VERSION};
+use crate::kdbush::error::KdbushError;
+
+// Scalar array type to match js, reused from the 2D kdbush format.
+const ARRAY_TYPE_INDEX: u8 = 8;
+
+const DEFAULT_NODE_SIZE: usize = 64;
+
+/// Builds an [`OwnedNdKdbush`] of compile-time dimension `D`.
+pub struct NdKdbushBuilder<const D: usize> {
+    /// data buffer
+    data: Vec<u8>,
+
+    num_items: usize,
+    node_size: usize,
+
+    coords_byte_size: usize,
+    ids_byte_size: usize,
+    pad_coords_byte_size: usize,
+
+    pos: usize,
+}
+
+impl<const D: usize> NdKdbushBuilder<D> {
+    pub fn new(num_items: usize) -> Self {
+        Self::new_with_node_size(num_items, DEFAULT_NODE_SIZE)
+    }
+
+    pub fn new_with_node_size(num_items: usize, node_size: usize) -> Self {
+        assert!(D >= 1);
+        assert!((2..=65535).contains(&node_size));
+        assert!(num_items <= u32::MAX.try_into().unwrap());
+
+        let f64_bytes_per_element = 8;
+        let coords_byte_size = num_items * D * f64_bytes_per_element;
+        let indices_bytes_per_element = if num_items < 65536 { 2 } else { 4 };
+        let ids_byte_size = num_items * indices_bytes_per_element;
+        let pad_coords_byte_size = (8 - (ids_byte_size % 8)) % 8;
+
+        let data_buffer_length =
+            KDBUSH_HEADER_SIZE + coords_byte_size + ids_byte_size + pad_coords_byte_size;
+        let mut data = vec![0; data_buffer_length];
+
+        // Set data header;
+        data[0] = KDBUSH_MAGIC;
+        data[1] = (KDBUSH_VERSION << 4) + ARRAY_TYPE_INDEX;
+        cast_slice_mut(&mut data[2..4])[0] = node_size as u16;
+        cast_slice_mut(&mut data[4..8])[0] = num_items as u32;
+
+        Self {
+            data,
+            num_items,
+            node_size,
+            coords_byte_size,
+            ids_byte_size,
+            pad_coords_byte_size,
+            pos: 0,
+        }
+    }
+
+    /// Add a `D`-dimensional point to the index.
+    pub fn add(&mut self, coords: [f64; D]) -> usize {
+        let index = self.pos / D;
+        let (coords_buf, mut ids) = split_data_borrow(
+            &mut self.data,
+            self.num_items,
+            self.ids_byte_size,
+            self.coords_byte_size,
+            self.pad_coords_byte_size,
+        );
+
+        ids.set(index, index);
+        for value in coords {
+            coords_buf[self.pos] = value;
+            self.pos += 1;
+        }
+
+        index
+    }
+
+    pub fn finish(mut self) -> OwnedNdKdbush<D> {
+        assert_eq!(
+            self.pos / D,
+            self.num_items,
+            "Added {} items when expected {}.",
+            self.pos / D,
+            self.num_items
+        );
+
+        let (coords, mut ids) = split_data_borrow(
+            &mut self.data,
+            self.num_items,
+            self.ids_byte_size,
+            self.coords_byte_size,
+            self.pad_coords_byte_size,
+        );
+
+        // kd-sort both arrays for efficient search, cycling through all D axes as we descend.
+        sort::<D>(&mut ids, coords, self.node_size, 0, self.num_items - 1, 0);
+
+        OwnedNdKdbush {
+            buffer: self.data,
+            node_size: self.node_size,
+            num_items: self.num_items,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Mutable borrow of coords and ids
+fn split_data_borrow(
+    data: &mut [u8],
+    num_items: usize,
+    ids_byte_size: usize,
+    coords_byte_size: usize,
+    pad_coords: usize,
+) -> (&mut [f64], MutableIndices) {
+    let (ids_buf, padded_coords_buf) = data[KDBUSH_HEADER_SIZE..].split_at_mut(ids_byte_size);
+    let coords_buf = &mut padded_coords_buf[pad_coords..];
+    debug_assert_eq!(coords_buf.len(), coords_byte_size);
+
+    let ids = if num_items < 65536 {
+        MutableIndices::U16(cast_slice_mut(ids_buf))
+    } else {
+        MutableIndices::U32(cast_slice_mut(ids_buf))
+    };
+    let coords = cast_slice_mut(coords_buf);
+
+    (coords, ids)
+}
+
+fn sort<const D: usize>(
+    ids: &mut MutableIndices,
+    coords: &mut [f64],
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+) {
+    if right - left <= node_size {
+        return;
+    }
+
+    // middle index
+    let m = (left + right) >> 1;
+
+    // sort ids and coords around the middle index so that the halves lie on either side of the
+    // current axis (cycling through all `D` axes as we descend)
+    select::<D>(ids, coords, m, left, right, axis);
+
+    // recursively kd-sort first half and second half on the next axis
+    sort::<D>(ids, coords, node_size, left, m - 1, (axis + 1) % D);
+    sort::<D>(ids, coords, node_size, m + 1, right, (axis + 1) % D);
+}
+
+/// Custom Floyd-Rivest selection algorithm: sort ids and coords so that [left..k-1] items are
+/// smaller than k-th item (on the given axis)
+#[inline]
+fn select<const D: usize>(
+    ids: &mut MutableIndices,
+    coords: &mut [f64],
+    k: usize,
+    mut left: usize,
+    mut right: usize,
+    axis: usize,
+) {
+    while right > left {
+        if right - left > 600 {
+            let n = (right - left + 1) as f64;
+            let m = (k - left + 1) as f64;
+            let z = f64::ln(n);
+            let s = 0.5 * f64::exp((2.0 * z) / 3.0);
+            let sd = 0.5
+                * f64::sqrt((z * s * (n - s)) / n)
+                * (if m - n / 2.0 < 0.0 { -1.0 } else { 1.0 });
+            let new_left = cmp::max(left, f64::floor(k as f64 - (m * s) / n + sd) as usize);
+            let new_right = cmp::min(
+                right,
+                f64::floor(k as f64 + ((n - m) * s) / n + sd) as usize,
+            );
+            select::<D>(ids, coords, k, new_left, new_right, axis);
+        }
+
+        let t = coords[D * k + axis];
+        let mut i = left;
+        let mut j = right;
+
+        swap_item::<D>(ids, coords, left, k);
+        if coords[D * right + axis] > t {
+            swap_item::<D>(ids, coords, left, right);
+        }
+
+        while i < j {
+            swap_item::<D>(ids, coords, i, j);
+            i += 1;
+            j -= 1;
+            while coords[D * i + axis] < t {
+                i += 1;
+            }
+            while coords[D * j + axis] > t {
+                j -= 1;
+            }
+        }
+
+        if coords[D * left + axis] == t {
+            swap_item::<D>(ids, coords, left, j);
+        } else {
+            j += 1;
+            swap_item::<D>(ids, coords, j, right);
+        }
+
+        if j <= k {
+            left = j + 1;
+        }
+        if k <= j {
+            right = j - 1;
+        }
+    }
+}
+
+#[inline]
+fn swap_item<const D: usize>(ids: &mut MutableIndices, coords: &mut [f64], i: usize, j: usize) {
+    ids.swap(i, j);
+    for axis in 0..D {
+        coords.swap(D * i + axis, D * j + axis);
+    }
+}
+
+/// An owned, `D`-dimensional point index, as built by [`NdKdbushBuilder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedNdKdbush<const D: usize> {
+    buffer: Vec<u8>,
+    node_size: usize,
+    num_items: usize,
+    _phantom: PhantomData<[(); D]>,
+}
+
+impl<const D: usize> OwnedNdKdbush<D> {
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    pub fn as_ref_index(&self) -> NdKdbushRef<'_, D> {
+        NdKdbushRef::try_new(self).unwrap()
+    }
+}
+
+impl<const D: usize> AsRef<[u8]> for OwnedNdKdbush<D> {
+    fn as_ref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+/// A `D`-dimensional point index as a reference onto an external byte slice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NdKdbushRef<'a, const D: usize> {
+    coords: &'a [f64],
+    ids: Indices<'a>,
+    node_size: usize,
+    num_items: usize,
+}
+
+impl<'a, const D: usize> NdKdbushRef<'a, D> {
+    pub fn try_new<T: AsRef<[u8]>>(data: &'a T) -> Result<Self, KdbushError> {
+        let data = data.as_ref();
+
+        if data[0] != KDBUSH_MAGIC {
+            return Err(KdbushError::General(
+                "Data does not appear to be in a Kdbush format.".to_string(),
+            ));
+        }
+
+        let version_and_type = data[1];
+        let version = version_and_type >> 4;
+        if version != KDBUSH_VERSION {
+            return Err(KdbushError::General(
+                format!("Got v{} data when expected v{}.", version, KDBUSH_VERSION).to_string(),
+            ));
+        }
+
+        let node_size: u16 = cast_slice(&data[2..4])[0];
+        let num_items: u32 = cast_slice(&data[4..8])[0];
+        let node_size = node_size as usize;
+        let num_items = num_items as usize;
+
+        let f64_bytes_per_element = 8;
+        let coords_byte_size = num_items * D * f64_bytes_per_element;
+        let indices_bytes_per_element = if num_items < 65536 { 2 } else { 4 };
+        let ids_byte_size = num_items * indices_bytes_per_element;
+        let pad_coords_byte_size = (8 - (ids_byte_size % 8)) % 8;
+
+        let data_buffer_length =
+            KDBUSH_HEADER_SIZE + coords_byte_size + ids_byte_size + pad_coords_byte_size;
+        assert_eq!(data.len(), data_buffer_length);
+
+        let indices_buf = &data[KDBUSH_HEADER_SIZE..KDBUSH_HEADER_SIZE + ids_byte_size];
+        let ids = if num_items < 65536 {
+            Indices::U16(cast_slice(indices_buf))
+        } else {
+            Indices::U32(cast_slice(indices_buf))
+        };
+        let coords_byte_start = KDBUSH_HEADER_SIZE + ids_byte_size + pad_coords_byte_size;
+        let coords_byte_end =
+            KDBUSH_HEADER_SIZE + ids_byte_size + pad_coords_byte_size + coords_byte_size;
+        let coords = cast_slice(&data[coords_byte_start..coords_byte_end]);
+
+        Ok(Self {
+            coords,
+            ids,
+            node_size,
+            num_items,
+        })
+    }
+}
+
+/// Trait shared by the owned and ref-backed `D`-dimensional kdbush indexes.
+pub trait NdKdbushIndex<const D: usize> {
+    fn num_items(&self) -> usize;
+    fn node_size(&self) -> usize;
+    fn coords(&self) -> &[f64];
+    fn ids(&self) -> Cow<'_, Indices>;
+
+    /// Search the index for items within a given `D`-dimensional axis-aligned bounding box.
+    ///
+    /// Returns indices of found items.
+    fn range(&self, min: [f64; D], max: [f64; D]) -> Vec<usize> {
+        let ids = self.ids();
+        let coords = self.coords();
+        let node_size = self.node_size();
+
+        let mut stack = ArrayVec::<_, 3>::new();
+        stack.push(0);
+        stack.push(ids.len() - 1);
+        stack.push(0);
+
+        let mut result = vec![];
+
+        let in_range = |i: usize| {
+            (0..D).all(|axis| {
+                let v = coords[D * i + axis];
+                v >= min[axis] && v <= max[axis]
+            })
+        };
+
+        while !stack.is_empty() {
+            let axis = stack.pop().unwrap_or(0);
+            let right = stack.pop().unwrap_or(0);
+            let left = stack.pop().unwrap_or(0);
+
+            if right - left <= node_size {
+                for i in left..right + 1 {
+                    if in_range(i) {
+                        result.push(ids.get(i));
+                    }
+                }
+                continue;
+            }
+
+            let m = (left + right) >> 1;
+
+            if in_range(m) {
+                result.push(ids.get(m));
+            }
+
+            let split = coords[D * m + axis];
+            if min[axis] <= split {
+                stack.push(left);
+                stack.push(m - 1);
+                stack.push((axis + 1) % D);
+            }
+            if max[axis] >= split {
+                stack.push(m + 1);
+                stack.push(right);
+                stack.push((axis + 1) % D);
+            }
+        }
+
+        result
+    }
+
+    /// Search the index for items within a given radius of a `D`-dimensional query point.
+    ///
+    /// Returns indices of found items.
+    fn within(&self, query: [f64; D], r: f64) -> Vec<usize> {
+        let ids = self.ids();
+        let coords = self.coords();
+        let node_size = self.node_size();
+
+        let mut stack = ArrayVec::<_, 3>::new();
+        stack.push(0);
+        stack.push(ids.len() - 1);
+        stack.push(0);
+
+        let mut result = vec![];
+        let r2 = r * r;
+
+        let sq_dist = |i: usize| -> f64 {
+            (0..D)
+                .map(|axis| {
+                    let d = coords[D * i + axis] - query[axis];
+                    d * d
+                })
+                .sum()
+        };
+
+        while !stack.is_empty() {
+            let axis = stack.pop().unwrap_or(0);
+            let right = stack.pop().unwrap_or(0);
+            let left = stack.pop().unwrap_or(0);
+
+            if right - left <= node_size {
+                for i in left..right + 1 {
+                    if sq_dist(i) <= r2 {
+                        result.push(ids.get(i));
+                    }
+                }
+                continue;
+            }
+
+            let m = (left + right) >> 1;
+
+            if sq_dist(m) <= r2 {
+                result.push(ids.get(m));
+            }
+
+            let split = coords[D * m + axis];
+            if query[axis] - r <= split {
+                stack.push(left);
+                stack.push(m - 1);
+                stack.push((axis + 1) % D);
+            }
+            if query[axis] + r >= split {
+                stack.push(m + 1);
+                stack.push(right);
+                stack.push((axis + 1) % D);
+            }
+        }
+
+        result
+    }
+}
+
+impl<const D: usize> NdKdbushIndex<D> for NdKdbushRef<'_, D> {
+    fn num_items(&self) -> usize {
+        self.num_items
+    }
+
+    fn node_size(&self) -> usize {
+        self.node_size
+    }
+
+    fn coords(&self) -> &[f64] {
+        self.coords
+    }
+
+    fn ids(&self) -> Cow<'_, Indices> {
+        Cow::Borrowed(&self.ids)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn range_and_within_in_3d() {
+        let mut builder = NdKdbushBuilder::<3>::new(4);
+        builder.add([0., 0., 0.]);
+        builder.add([1., 1., 1.]);
+        builder.add([10., 10., 10.]);
+        builder.add([1., 0., 1.]);
+        let index = builder.finish();
+
+        let mut found = index.range([0., 0., 0.], [1., 1., 1.]);
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 1, 3]);
+
+        let mut found = index.within([0., 0., 0.], 1.5);
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let mut builder = NdKdbushBuilder::<4>::new(2);
+        builder.add([0., 0., 0., 0.]);
+        builder.add([5., 5., 5., 5.]);
+        let index = builder.finish();
+        let buffer = index.into_inner();
+
+        let index_ref = NdKdbushRef::<4>::try_new(&buffer).unwrap();
+        assert_eq!(index_ref.within([0., 0., 0., 0.], 1.), vec![0]);
+    }
+}
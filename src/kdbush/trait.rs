@@ -1,9 +1,12 @@
 use std::borrow::Cow;
+use std::collections::BinaryHeap;
 
 use arrayvec::ArrayVec;
 
 use crate::indices::Indices;
 use crate::kdbush::KdbushRef;
+use crate::kdtree::SearchParameters;
+use crate::rtree::SimpleDistanceMetric;
 
 pub trait KdbushIndex {
     fn num_items(&self) -> usize;
@@ -13,6 +16,13 @@ pub trait KdbushIndex {
 
     /// Search the index for items within a given bounding box.
     ///
+    /// Gives a point-only Kdbush index (such as [`KdbushRef`][crate::kdbush::KdbushRef]) the same
+    /// bounding-box query capability [`RTreeIndex::search`][crate::rtree::RTreeIndex::search]
+    /// gives a box index, walking the packed kd-tree stored in [`coords`][Self::coords]/
+    /// [`ids`][Self::ids] with an explicit `(left, right, axis)` stack rather than recursion, and
+    /// falling back to a linear scan once a range is no larger than
+    /// [`node_size`][Self::node_size].
+    ///
     /// - min_x: bbox
     /// - min_y: bbox
     /// - max_x: bbox
@@ -81,6 +91,13 @@ pub trait KdbushIndex {
 
     /// Search the index for items within a given radius.
     ///
+    /// Gives a point-only Kdbush index the same radius-query capability
+    /// [`RTreeIndex::within_distance`][crate::rtree::RTreeIndex::within_distance] gives a box
+    /// index; compares squared distances against `r * r` to avoid a `sqrt` per candidate.
+    ///
+    /// This method uses Euclidean distance. For other distance metrics, such as great-circle
+    /// distance on longitude/latitude data, use [`within_metric`][Self::within_metric].
+    ///
     /// - qx: x value of query point
     /// - qy: y value of query point
     /// - r: radius
@@ -143,6 +160,474 @@ pub trait KdbushIndex {
         }
         result
     }
+
+    /// Search the index for items within a given radius under a pluggable
+    /// [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric], such as
+    /// [`HaversineDistance`][crate::rtree::HaversineDistance] or
+    /// [`SpheroidDistance`][crate::rtree::SpheroidDistance].
+    ///
+    /// [`within`][Self::within] hardcodes squared Euclidean distance, which is the wrong notion
+    /// of distance for longitude/latitude points where "within N meters" needs a geodesic metric.
+    /// The kd descent still prunes correctly for a non-Euclidean metric: rather than comparing
+    /// `qx`/`qy` against the splitting coordinate directly, each subtree is only skipped once
+    /// `metric`'s distance from the query to the splitting plane itself already exceeds `r` (the
+    /// plane is the nearest any point past it can be, so this bound is always safe to prune on).
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - r: radius
+    ///
+    /// Returns indices of found items
+    fn within_metric<M: SimpleDistanceMetric<f64> + ?Sized>(
+        &self,
+        qx: f64,
+        qy: f64,
+        r: f64,
+        metric: &M,
+    ) -> Vec<usize> {
+        let ids = self.ids();
+        let coords = self.coords();
+        let node_size = self.node_size();
+
+        // Use arrayvec to avoid heap allocations
+        let mut stack = ArrayVec::<_, 3>::new();
+        stack.push(0);
+        stack.push(ids.len() - 1);
+        stack.push(0);
+
+        let mut result = vec![];
+
+        // recursively search for items within radius in the kd-sorted arrays
+        while !stack.is_empty() {
+            let axis = stack.pop().unwrap_or(0);
+            let right = stack.pop().unwrap_or(0);
+            let left = stack.pop().unwrap_or(0);
+
+            // if we reached "tree node", search linearly
+            if right - left <= node_size {
+                for i in left..right + 1 {
+                    if metric.distance(coords[2 * i], coords[2 * i + 1], qx, qy) <= r {
+                        result.push(ids.get(i));
+                    }
+                }
+                continue;
+            }
+
+            // otherwise find the middle index
+            let m = (left + right) >> 1;
+
+            // include the middle item if it's in range
+            let x = coords[2 * m];
+            let y = coords[2 * m + 1];
+            if metric.distance(x, y, qx, qy) <= r {
+                result.push(ids.get(m));
+            }
+
+            let split = if axis == 0 { x } else { y };
+            let query_on_left = if axis == 0 { qx <= split } else { qy <= split };
+
+            // the half containing the query always needs to be searched
+            if query_on_left {
+                stack.push(left);
+                stack.push(m - 1);
+                stack.push(1 - axis);
+            } else {
+                stack.push(m + 1);
+                stack.push(right);
+                stack.push(1 - axis);
+            }
+
+            // the far half is only reachable if the splitting plane itself is within `r`
+            if axis_plane_distance(metric, qx, qy, axis, split) <= r {
+                if query_on_left {
+                    stack.push(m + 1);
+                    stack.push(right);
+                    stack.push(1 - axis);
+                } else {
+                    stack.push(left);
+                    stack.push(m - 1);
+                    stack.push(1 - axis);
+                }
+            }
+        }
+        result
+    }
+
+    /// Find the `k` nearest items to a query point, in ascending order of distance.
+    ///
+    /// Maintains a bounded max-heap of the `k` best candidates seen so far while descending the
+    /// kd-sorted `coords` array: at each split, it recurses into the half containing the query
+    /// first, then only recurses into the far half if the squared distance from the query to the
+    /// splitting plane could still beat the current worst of the `k` best, pruning it otherwise.
+    /// Falls back to a linear scan once a range is no larger than
+    /// [`node_size`][Self::node_size], exactly as [`range`][Self::range]/[`within`][Self::within]
+    /// do.
+    ///
+    /// This method uses Euclidean distance. For other distance metrics, such as great-circle
+    /// distance on longitude/latitude data, use [`nearest_metric`][Self::nearest_metric].
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn nearest(&self, qx: f64, qy: f64, k: usize) -> Vec<usize> {
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let ids = self.ids();
+        let coords = self.coords();
+        let node_size = self.node_size();
+
+        let mut best: BinaryHeap<Candidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn(
+            coords,
+            &ids,
+            node_size,
+            0,
+            ids.len() - 1,
+            0,
+            qx,
+            qy,
+            k,
+            &mut best,
+        );
+
+        let mut sorted: Vec<Candidate> = best.into_vec();
+        sorted.sort_by(|a, b| a.dist.partial_cmp(&b.dist).unwrap());
+        sorted.into_iter().map(|c| c.id).collect()
+    }
+
+    /// Find the `k` nearest items to a query point under a pluggable
+    /// [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric], such as
+    /// [`HaversineDistance`][crate::rtree::HaversineDistance] or
+    /// [`SpheroidDistance`][crate::rtree::SpheroidDistance], in ascending order of distance.
+    ///
+    /// This is the same best-first descent as [`nearest`][Self::nearest], except the
+    /// splitting-plane pruning bound is computed via `metric`'s `distance_to_bbox` (against a
+    /// degenerate bbox collapsed onto the splitting plane) instead of assuming Euclidean
+    /// geometry, so geographic metrics prune correctly too.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn nearest_metric<M: SimpleDistanceMetric<f64> + ?Sized>(
+        &self,
+        qx: f64,
+        qy: f64,
+        k: usize,
+        metric: &M,
+    ) -> Vec<usize> {
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let ids = self.ids();
+        let coords = self.coords();
+        let node_size = self.node_size();
+
+        let mut best: BinaryHeap<Candidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn_metric(
+            coords,
+            &ids,
+            node_size,
+            0,
+            ids.len() - 1,
+            0,
+            qx,
+            qy,
+            k,
+            metric,
+            &mut best,
+        );
+
+        let mut sorted: Vec<Candidate> = best.into_vec();
+        sorted.sort_by(|a, b| a.dist.partial_cmp(&b.dist).unwrap());
+        sorted.into_iter().map(|c| c.id).collect()
+    }
+
+    /// Find the `k` nearest items to a query point, exposing the approximate-pruning, radius-cap,
+    /// and result-ordering knobs of [`SearchParameters`][crate::kdtree::SearchParameters], plus a
+    /// touch counter, all in one call.
+    ///
+    /// Runs the same descent as [`nearest`][Self::nearest], except the far-subtree prune is
+    /// relaxed by `(1+params.epsilon)`, candidates beyond `params.max_radius` (if set) are
+    /// discarded outright, `params.sort_results` controls whether the final ascending-distance
+    /// sort runs at all, and `touch_count`, if `Some`, is incremented once per point tested
+    /// against.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - params: approximation, radius, and ordering knobs
+    /// - touch_count: if `Some`, incremented once per point tested against
+    ///
+    /// Returns indices of found items, ordered by ascending distance if `params.sort_results`.
+    fn nearest_advanced(
+        &self,
+        qx: f64,
+        qy: f64,
+        k: usize,
+        params: &SearchParameters<f64>,
+        touch_count: &mut Option<usize>,
+    ) -> Vec<usize> {
+        assert!(
+            params.epsilon >= 0.0,
+            "epsilon must be non-negative, got {}",
+            params.epsilon
+        );
+
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let ids = self.ids();
+        let coords = self.coords();
+        let node_size = self.node_size();
+        let epsilon_factor = 1.0 + params.epsilon;
+        let max_radius_sq = params.max_radius.map(|r| r * r);
+
+        let mut best: BinaryHeap<Candidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn_advanced(
+            coords,
+            &ids,
+            node_size,
+            0,
+            ids.len() - 1,
+            0,
+            qx,
+            qy,
+            k,
+            epsilon_factor,
+            max_radius_sq,
+            &mut best,
+            touch_count,
+        );
+
+        let candidates: Vec<Candidate> = best.into_vec();
+        if params.sort_results {
+            let mut sorted = candidates;
+            sorted.sort_by(|a, b| a.dist.partial_cmp(&b.dist).unwrap());
+            sorted.into_iter().map(|c| c.id).collect()
+        } else {
+            candidates.into_iter().map(|c| c.id).collect()
+        }
+    }
+
+    /// Find the `k` nearest items to a query point, stopping early once a candidate is farther
+    /// than `max_distance`.
+    ///
+    /// A thin wrapper over [`nearest_advanced`][Self::nearest_advanced] with `max_distance`
+    /// plugged in as [`SearchParameters::max_radius`][crate::kdtree::SearchParameters::max_radius]
+    /// and no epsilon relaxation, matching the `neighbors(x, y, max_results, max_distance)` naming
+    /// [`RTreeIndex::neighbors`][crate::rtree::RTreeIndex::neighbors] and
+    /// [`FlatbushIndex::neighbors`][crate::flatbush::FlatbushIndex::neighbors] already use for the
+    /// same kind of bounded k-NN query.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - max_distance: if `Some`, discard candidates farther than this distance
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn neighbors(&self, qx: f64, qy: f64, k: usize, max_distance: Option<f64>) -> Vec<usize> {
+        self.nearest_advanced(
+            qx,
+            qy,
+            k,
+            &SearchParameters {
+                max_radius: max_distance,
+                ..Default::default()
+            },
+            &mut None,
+        )
+    }
+
+    /// Find every indexed item that has the query point among its own `k` nearest neighbors —
+    /// the inverse of [`nearest`][Self::nearest].
+    ///
+    /// An item is a reverse neighbor of the query iff fewer than `k` *other* items are strictly
+    /// closer to it than the query point is. Rather than brute-forcing every pair of items, each
+    /// item's count is found via [`within`][Self::within] itself, bounded to the item's own
+    /// distance from the query — reusing the same splitting-plane pruning that lets `within` skip
+    /// whole subtrees instead of visiting every item.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors an item must have the query among
+    ///
+    /// Returns indices of found items.
+    fn reverse_neighbors(&self, qx: f64, qy: f64, k: usize) -> Vec<usize> {
+        let ids = self.ids();
+        let coords = self.coords();
+        let num_items = self.num_items();
+
+        let mut position_of = vec![0; num_items];
+        for i in 0..num_items {
+            position_of[ids.get(i)] = i;
+        }
+
+        let mut result = vec![];
+        for i in 0..num_items {
+            let id = ids.get(i);
+            let px = coords[2 * i];
+            let py = coords[2 * i + 1];
+
+            let d2 = sq_dist(px, py, qx, qy);
+            let r = d2.sqrt();
+            let closer_count = self
+                .within(px, py, r)
+                .into_iter()
+                .filter(|&other| {
+                    let p = position_of[other];
+                    other != id && sq_dist(coords[2 * p], coords[2 * p + 1], px, py) < d2
+                })
+                .count();
+
+            if closer_count < k {
+                result.push(id);
+            }
+        }
+        result
+    }
+
+    /// Search the index for items within a given bounding box on a periodic (wrap-around)
+    /// domain, such as global longitude wrapping at the antimeridian.
+    ///
+    /// `domain` is `(x_min, x_max, y_min, y_max)`, the extents of the torus this index's points
+    /// live on. A query bound that strays outside those extents on an axis (e.g. `max_x` past
+    /// `domain.1`, or `min_x` before `domain.0`) means the window wraps back around through that
+    /// axis's seam; the query box is split into the (at most two, per wrapped axis) in-domain
+    /// boxes that don't cross the seam, and [`range`][Self::range] is run on each, unioning the
+    /// results.
+    ///
+    /// - min_x: bbox
+    /// - min_y: bbox
+    /// - max_x: bbox
+    /// - max_y: bbox
+    /// - domain: `(x_min, x_max, y_min, y_max)` extents of the periodic domain
+    ///
+    /// Returns indices of found items
+    fn range_periodic(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+        domain: (f64, f64, f64, f64),
+    ) -> Vec<usize> {
+        let (x_min, x_max, y_min, y_max) = domain;
+        let x_ranges = wrap_axis_range(min_x, max_x, x_min, x_max);
+        let y_ranges = wrap_axis_range(min_y, max_y, y_min, y_max);
+
+        let mut result = vec![];
+        for &(rx0, rx1) in &x_ranges {
+            for &(ry0, ry1) in &y_ranges {
+                result.extend(self.range(rx0, ry0, rx1, ry1));
+            }
+        }
+        result
+    }
+
+    /// Search the index for items within a given radius of a query point on a periodic
+    /// (wrap-around) domain.
+    ///
+    /// `domain` is `(x_min, x_max, y_min, y_max)`, the extents of the torus this index's points
+    /// live on. The distance test reduces each axis's coordinate delta into its minimum-image
+    /// form (`d -= length * round(d / length)`, where `length` is that axis's domain extent), and
+    /// the decision to descend a kd subtree checks the query's distance to the splitting plane
+    /// both directly and wrapped through the seam, so branches straddling the boundary aren't
+    /// pruned.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - r: radius
+    /// - domain: `(x_min, x_max, y_min, y_max)` extents of the periodic domain
+    ///
+    /// Returns indices of found items
+    fn within_periodic(
+        &self,
+        qx: f64,
+        qy: f64,
+        r: f64,
+        domain: (f64, f64, f64, f64),
+    ) -> Vec<usize> {
+        let (x_min, x_max, y_min, y_max) = domain;
+        let lx = x_max - x_min;
+        let ly = y_max - y_min;
+
+        let ids = self.ids();
+        let coords = self.coords();
+        let node_size = self.node_size();
+
+        // A periodic search can push both the left and right branch of a split without the
+        // opposite branch having fully drained first, so it needs more stack depth than the
+        // plain, non-wrapping `within`'s `ArrayVec::<_, 3>`.
+        let mut stack = ArrayVec::<_, 99>::new();
+        stack.push(0);
+        stack.push(ids.len() - 1);
+        stack.push(0);
+
+        let mut result = vec![];
+        let r2 = r * r;
+
+        while !stack.is_empty() {
+            let axis = stack.pop().unwrap_or(0);
+            let right = stack.pop().unwrap_or(0);
+            let left = stack.pop().unwrap_or(0);
+
+            if right - left <= node_size {
+                for i in left..right + 1 {
+                    if sq_dist_periodic(coords[2 * i], coords[2 * i + 1], qx, qy, lx, ly) <= r2 {
+                        result.push(ids.get(i));
+                    }
+                }
+                continue;
+            }
+
+            let m = (left + right) >> 1;
+            let x = coords[2 * m];
+            let y = coords[2 * m + 1];
+            if sq_dist_periodic(x, y, qx, qy, lx, ly) <= r2 {
+                result.push(ids.get(m));
+            }
+
+            let (split, query_coord, lo, hi) = if axis == 0 {
+                (x, qx, x_min, x_max)
+            } else {
+                (y, qy, y_min, y_max)
+            };
+
+            // The half containing the query coordinate directly always needs to be searched; the
+            // other half is reachable either directly (if the query is already past the split)
+            // or, on a wrapped axis, around through the domain's seam.
+            let dist_to_left = if query_coord <= split {
+                0.0
+            } else {
+                (query_coord - split).min(hi - query_coord)
+            };
+            let dist_to_right = if query_coord > split {
+                0.0
+            } else {
+                (split - query_coord).min(query_coord - lo)
+            };
+
+            if dist_to_left <= r {
+                stack.push(left);
+                stack.push(m - 1);
+                stack.push(1 - axis);
+            }
+            if dist_to_right <= r {
+                stack.push(m + 1);
+                stack.push(right);
+                stack.push(1 - axis);
+            }
+        }
+        result
+    }
 }
 
 impl KdbushIndex for KdbushRef<'_> {
@@ -169,3 +654,418 @@ fn sq_dist(ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
     let dy = ay - by;
     dx * dx + dy * dy
 }
+
+/// The minimum-image 1-D gap between two coordinates on an axis of the given `length`, e.g.
+/// `min(|a - b|, length - |a - b|)` for longitude wrapping at the antimeridian.
+#[inline]
+fn periodic_gap(a: f64, b: f64, length: f64) -> f64 {
+    if length <= 0.0 {
+        return (a - b).abs();
+    }
+    let diff = (a - b).abs();
+    let wrapped = length - diff;
+    wrapped.min(diff)
+}
+
+/// Squared periodic distance between `(ax, ay)` and `(bx, by)`, reducing each axis's delta into
+/// its minimum-image form via `d -= length * round(d / length)` before squaring.
+#[inline]
+fn sq_dist_periodic(ax: f64, ay: f64, bx: f64, by: f64, lx: f64, ly: f64) -> f64 {
+    let dx = periodic_delta(ax - bx, lx);
+    let dy = periodic_delta(ay - by, ly);
+    dx * dx + dy * dy
+}
+
+/// Reduce a 1-D coordinate delta into its minimum-image form on an axis of the given `length`.
+#[inline]
+fn periodic_delta(d: f64, length: f64) -> f64 {
+    if length <= 0.0 {
+        return d;
+    }
+    d - length * (d / length).round()
+}
+
+/// Split a 1-D query range against an axis with the given `[domain_min, domain_max)` extent,
+/// decomposing a range that straddles the domain boundary into the (at most two) sub-ranges that
+/// don't.
+///
+/// Mirrors a longitude range crossing the antimeridian: a caller passing `max` past `domain_max`
+/// (meaning the window wraps back around to `max - length`) gets `[min, domain_max]` and
+/// `[domain_min, max - length]` back; a caller passing `min` before `domain_min` gets the
+/// symmetric split. A range that already stays within the domain is returned unchanged.
+fn wrap_axis_range(min: f64, max: f64, domain_min: f64, domain_max: f64) -> Vec<(f64, f64)> {
+    let length = domain_max - domain_min;
+    let mut out = Vec::with_capacity(2);
+    if max > domain_max {
+        out.push((min, domain_max));
+        out.push((domain_min, max - length));
+    } else if min < domain_min {
+        out.push((domain_min, max));
+        out.push((min + length, domain_max));
+    } else {
+        out.push((min, max));
+    }
+    out
+}
+
+/// A candidate tracked by [`KdbushIndex::nearest`]'s bounded max-heap of the `k` best points seen
+/// so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    dist: f64,
+    id: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist.partial_cmp(&other.dist).unwrap()
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A lower bound, under `metric`, on the distance from `(qx, qy)` to anything on the far side of
+/// the splitting plane at `split` along `axis`.
+///
+/// Computed via `metric`'s own `distance_to_bbox` against a bbox collapsed to zero width along
+/// `axis` (and left unconstrained along the other axis), i.e. the plane itself — the closest any
+/// far-side point could possibly be.
+#[inline]
+fn axis_plane_distance<M: SimpleDistanceMetric<f64> + ?Sized>(
+    metric: &M,
+    qx: f64,
+    qy: f64,
+    axis: usize,
+    split: f64,
+) -> f64 {
+    if axis == 0 {
+        metric.distance_to_bbox(qx, qy, split, f64::MIN, split, f64::MAX)
+    } else {
+        metric.distance_to_bbox(qx, qy, f64::MIN, split, f64::MAX, split)
+    }
+}
+
+#[inline]
+fn offer_candidate(best: &mut BinaryHeap<Candidate>, k: usize, candidate: Candidate) {
+    if best.len() < k {
+        best.push(candidate);
+    } else if let Some(worst) = best.peek() {
+        if candidate.dist < worst.dist {
+            best.pop();
+            best.push(candidate);
+        }
+    }
+}
+
+/// Recursive best-first k-nearest-neighbor search over a 2D kd-sorted `coords`/`ids` pair.
+#[allow(clippy::too_many_arguments)]
+fn search_knn(
+    coords: &[f64],
+    ids: &Indices<'_>,
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    qx: f64,
+    qy: f64,
+    k: usize,
+    best: &mut BinaryHeap<Candidate>,
+) {
+    if right - left <= node_size {
+        for i in left..=right {
+            let dist = sq_dist(coords[2 * i], coords[2 * i + 1], qx, qy);
+            offer_candidate(
+                best,
+                k,
+                Candidate {
+                    dist,
+                    id: ids.get(i),
+                },
+            );
+        }
+        return;
+    }
+
+    let m = (left + right) >> 1;
+    let x = coords[2 * m];
+    let y = coords[2 * m + 1];
+    offer_candidate(
+        best,
+        k,
+        Candidate {
+            dist: sq_dist(x, y, qx, qy),
+            id: ids.get(m),
+        },
+    );
+
+    let split = if axis == 0 { x } else { y };
+    let next_axis = 1 - axis;
+    let query_on_left = if axis == 0 { qx <= split } else { qy <= split };
+
+    let left_half_nonempty = m > left;
+    let right_half_nonempty = m < right;
+
+    let (near_half, far_half) = if query_on_left {
+        (left_half_nonempty, right_half_nonempty)
+    } else {
+        (right_half_nonempty, left_half_nonempty)
+    };
+
+    if near_half {
+        if query_on_left {
+            search_knn(
+                coords,
+                ids,
+                node_size,
+                left,
+                m - 1,
+                next_axis,
+                qx,
+                qy,
+                k,
+                best,
+            );
+        } else {
+            search_knn(
+                coords,
+                ids,
+                node_size,
+                m + 1,
+                right,
+                next_axis,
+                qx,
+                qy,
+                k,
+                best,
+            );
+        }
+    }
+
+    let q_axis = if axis == 0 { qx } else { qy };
+    let axis_dist = (q_axis - split) * (q_axis - split);
+    if far_half && (best.len() < k || axis_dist < best.peek().unwrap().dist) {
+        if query_on_left {
+            search_knn(
+                coords,
+                ids,
+                node_size,
+                m + 1,
+                right,
+                next_axis,
+                qx,
+                qy,
+                k,
+                best,
+            );
+        } else {
+            search_knn(
+                coords,
+                ids,
+                node_size,
+                left,
+                m - 1,
+                next_axis,
+                qx,
+                qy,
+                k,
+                best,
+            );
+        }
+    }
+}
+
+/// Like [`search_knn`], but backing [`KdbushIndex::nearest_advanced`]: `epsilon_factor` relaxes
+/// the far-subtree prune exactly as in `search_knn` (`1.0` is exact, larger values prune more
+/// aggressively), `max_radius_sq`, if `Some`, additionally discards candidates beyond that squared
+/// distance and skips subtrees that start beyond it entirely, and `touch_count`, if `Some`, is
+/// incremented once per point tested against.
+#[allow(clippy::too_many_arguments)]
+fn search_knn_advanced(
+    coords: &[f64],
+    ids: &Indices<'_>,
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    qx: f64,
+    qy: f64,
+    k: usize,
+    epsilon_factor: f64,
+    max_radius_sq: Option<f64>,
+    best: &mut BinaryHeap<Candidate>,
+    touch_count: &mut Option<usize>,
+) {
+    let within_radius = |dist: f64| max_radius_sq.map_or(true, |max| dist <= max);
+
+    if right - left <= node_size {
+        for i in left..=right {
+            if let Some(count) = touch_count.as_mut() {
+                *count += 1;
+            }
+            let dist = sq_dist(coords[2 * i], coords[2 * i + 1], qx, qy);
+            if within_radius(dist) {
+                offer_candidate(
+                    best,
+                    k,
+                    Candidate {
+                        dist,
+                        id: ids.get(i),
+                    },
+                );
+            }
+        }
+        return;
+    }
+
+    let m = (left + right) >> 1;
+    let x = coords[2 * m];
+    let y = coords[2 * m + 1];
+    if let Some(count) = touch_count.as_mut() {
+        *count += 1;
+    }
+    let mid_dist = sq_dist(x, y, qx, qy);
+    if within_radius(mid_dist) {
+        offer_candidate(
+            best,
+            k,
+            Candidate {
+                dist: mid_dist,
+                id: ids.get(m),
+            },
+        );
+    }
+
+    let split = if axis == 0 { x } else { y };
+    let next_axis = 1 - axis;
+    let query_on_left = if axis == 0 { qx <= split } else { qy <= split };
+
+    let left_half_nonempty = m > left;
+    let right_half_nonempty = m < right;
+
+    let (near_half, far_half) = if query_on_left {
+        (left_half_nonempty, right_half_nonempty)
+    } else {
+        (right_half_nonempty, left_half_nonempty)
+    };
+
+    if near_half {
+        if query_on_left {
+            search_knn_advanced(
+                coords, ids, node_size, left, m - 1, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, best, touch_count,
+            );
+        } else {
+            search_knn_advanced(
+                coords, ids, node_size, m + 1, right, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, best, touch_count,
+            );
+        }
+    }
+
+    let q_axis = if axis == 0 { qx } else { qy };
+    let axis_dist = (q_axis - split) * (q_axis - split) * epsilon_factor;
+    let within_best = best.len() < k || axis_dist < best.peek().unwrap().dist;
+    if far_half && within_best && within_radius(axis_dist) {
+        if query_on_left {
+            search_knn_advanced(
+                coords, ids, node_size, m + 1, right, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, best, touch_count,
+            );
+        } else {
+            search_knn_advanced(
+                coords, ids, node_size, left, m - 1, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, best, touch_count,
+            );
+        }
+    }
+}
+
+/// Like [`search_knn`], but for a 2D point query under a pluggable
+/// [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric] rather than hardcoded squared
+/// Euclidean distance: the far subtree is pruned using [`axis_plane_distance`] instead of a
+/// hardcoded axis delta.
+#[allow(clippy::too_many_arguments)]
+fn search_knn_metric<M: SimpleDistanceMetric<f64> + ?Sized>(
+    coords: &[f64],
+    ids: &Indices<'_>,
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    qx: f64,
+    qy: f64,
+    k: usize,
+    metric: &M,
+    best: &mut BinaryHeap<Candidate>,
+) {
+    if right - left <= node_size {
+        for i in left..=right {
+            let dist = metric.distance(coords[2 * i], coords[2 * i + 1], qx, qy);
+            offer_candidate(
+                best,
+                k,
+                Candidate {
+                    dist,
+                    id: ids.get(i),
+                },
+            );
+        }
+        return;
+    }
+
+    let m = (left + right) >> 1;
+    let x = coords[2 * m];
+    let y = coords[2 * m + 1];
+    offer_candidate(
+        best,
+        k,
+        Candidate {
+            dist: metric.distance(x, y, qx, qy),
+            id: ids.get(m),
+        },
+    );
+
+    let split = if axis == 0 { x } else { y };
+    let next_axis = 1 - axis;
+    let query_on_left = if axis == 0 { qx <= split } else { qy <= split };
+
+    let left_half_nonempty = m > left;
+    let right_half_nonempty = m < right;
+
+    let (near_half, far_half) = if query_on_left {
+        (left_half_nonempty, right_half_nonempty)
+    } else {
+        (right_half_nonempty, left_half_nonempty)
+    };
+
+    if near_half {
+        if query_on_left {
+            search_knn_metric(
+                coords, ids, node_size, left, m - 1, next_axis, qx, qy, k, metric, best,
+            );
+        } else {
+            search_knn_metric(
+                coords, ids, node_size, m + 1, right, next_axis, qx, qy, k, metric, best,
+            );
+        }
+    }
+
+    let axis_dist = axis_plane_distance(metric, qx, qy, axis, split);
+    if far_half && (best.len() < k || axis_dist < best.peek().unwrap().dist) {
+        if query_on_left {
+            search_knn_metric(
+                coords, ids, node_size, m + 1, right, next_axis, qx, qy, k, metric, best,
+            );
+        } else {
+            search_knn_metric(
+                coords, ids, node_size, left, m - 1, next_axis, qx, qy, k, metric, best,
+            );
+        }
+    }
+}
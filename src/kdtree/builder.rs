@@ -15,15 +15,22 @@ use crate::GeoIndexError;
 pub const DEFAULT_KDTREE_NODE_SIZE: u16 = 64;
 
 /// A builder to create an [`KDTree`].
+///
+/// Generic over its backing storage `B` and, as of [`Self::add_n`], over the number of
+/// dimensions `D` of the indexed points (defaulting to the ordinary 2D case). The default
+/// `B = Vec<u8>` heap-allocates its own buffer; use [`Self::from_metadata_in`]/[`Self::new_in`]
+/// to instead build directly into a caller-owned `&mut [u8]` (an `mmap`'d file or bump arena,
+/// say), avoiding a second full-size allocation when the index is ultimately persisted there.
 #[derive(Debug)]
-pub struct KDTreeBuilder<N: IndexableNum> {
+pub struct KDTreeBuilder<N: IndexableNum, B: AsMut<[u8]> + AsRef<[u8]> = Vec<u8>, const D: usize = 2>
+{
     /// data buffer
-    data: Vec<u8>,
-    metadata: KDTreeMetadata<N>,
+    data: B,
+    metadata: KDTreeMetadata<N, D>,
     pos: usize,
 }
 
-impl<N: IndexableNum> KDTreeBuilder<N> {
+impl<N: IndexableNum, const D: usize> KDTreeBuilder<N, Vec<u8>, D> {
     /// Create a new builder with the provided number of items and the default node size.
     pub fn new(num_items: u32) -> Self {
         Self::new_with_node_size(num_items, DEFAULT_KDTREE_NODE_SIZE)
@@ -35,11 +42,26 @@ impl<N: IndexableNum> KDTreeBuilder<N> {
         Self::from_metadata(metadata)
     }
 
-    /// Create a new builder with the provided metadata
-    pub fn from_metadata(metadata: KDTreeMetadata<N>) -> Self {
+    /// Create a new builder with the provided metadata, heap-allocating its own buffer.
+    pub fn from_metadata(metadata: KDTreeMetadata<N, D>) -> Self {
+        let data = vec![0; metadata.data_buffer_length()];
+        Self::from_metadata_in(metadata, data)
+    }
+}
+
+impl<N: IndexableNum, B: AsMut<[u8]> + AsRef<[u8]>, const D: usize> KDTreeBuilder<N, B, D> {
+    /// Create a new builder with the provided metadata, writing into a caller-provided buffer.
+    ///
+    /// `buffer` must be exactly `metadata.data_buffer_length()` bytes long.
+    pub fn from_metadata_in(metadata: KDTreeMetadata<N, D>, mut buffer: B) -> Self {
         let data_buffer_length = metadata.data_buffer_length();
-        let mut data = vec![0; data_buffer_length];
+        debug_assert_eq!(
+            buffer.as_ref().len(),
+            data_buffer_length,
+            "buffer must be exactly `metadata.data_buffer_length()` bytes long"
+        );
 
+        let data = buffer.as_mut();
         // Set data header;
         data[0] = KDBUSH_MAGIC;
         data[1] = (KDBUSH_VERSION << 4) + N::TYPE_INDEX;
@@ -47,71 +69,58 @@ impl<N: IndexableNum> KDTreeBuilder<N> {
         cast_slice_mut(&mut data[4..8])[0] = metadata.num_items();
 
         Self {
-            data,
+            data: buffer,
             pos: 0,
             metadata,
         }
     }
 
+    /// Create a new builder with the provided number of items and node size, writing into a
+    /// caller-provided buffer.
+    ///
+    /// `buffer` must be exactly as long as
+    /// `KDTreeMetadata::new(num_items, node_size).data_buffer_length()`.
+    pub fn new_in(num_items: u32, node_size: u16, buffer: B) -> Self {
+        let metadata = KDTreeMetadata::new(num_items, node_size);
+        Self::from_metadata_in(metadata, buffer)
+    }
+
     /// Access the underlying [KDTreeMetadata] of this instance.
-    pub fn metadata(&self) -> &KDTreeMetadata<N> {
+    pub fn metadata(&self) -> &KDTreeMetadata<N, D> {
         &self.metadata
     }
 
-    /// Add a point to the KDTree.
+    /// Add a `D`-dimensional point to the KDTree.
     ///
     /// This returns a positional index that provides a lookup back into the original data.
     #[inline]
-    pub fn add(&mut self, x: N, y: N) -> u32 {
-        let index = self.pos >> 1;
-        let (coords, mut ids) = split_data_borrow(&mut self.data, self.metadata);
+    pub fn add_n(&mut self, coord: [N; D]) -> u32 {
+        let index = self.pos / D;
+        let (coords, mut ids) = split_data_borrow(self.data.as_mut(), self.metadata);
 
         ids.set(index, index);
-        coords[self.pos] = x;
-        self.pos += 1;
-        coords[self.pos] = y;
-        self.pos += 1;
+        for value in coord {
+            coords[self.pos] = value;
+            self.pos += 1;
+        }
 
         index.try_into().unwrap()
     }
 
-    /// Add a coord to the KDTree.
-    ///
-    /// This returns a positional index that provides a lookup back into the original data.
-    #[inline]
-    pub fn add_coord(&mut self, coord: &impl CoordTrait<T = N>) -> u32 {
-        self.add(coord.x(), coord.y())
-    }
-
-    /// Add a point to the KDTree.
-    ///
-    /// This returns a positional index that provides a lookup back into the original data.
-    ///
-    /// ## Errors
-    ///
-    /// - If the point is empty.
-    #[inline]
-    pub fn add_point(&mut self, point: &impl PointTrait<T = N>) -> Result<u32> {
-        let coord = point.coord().ok_or(GeoIndexError::General(
-            "Unable to add empty point to KDTree".to_string(),
-        ))?;
-        Ok(self.add_coord(&coord))
-    }
-
     /// Consume this builder, perfoming the k-d sort and generating a KDTree ready for queries.
-    pub fn finish(mut self) -> KDTree<N> {
+    pub fn finish(mut self) -> KDTree<N, B, D> {
         assert_eq!(
-            self.pos >> 1,
+            self.pos / D,
             self.metadata.num_items() as usize,
             "Added {} items when expected {}.",
-            self.pos >> 1,
+            self.pos / D,
             self.metadata.num_items()
         );
 
-        let (coords, mut ids) = split_data_borrow::<N>(&mut self.data, self.metadata);
+        let (coords, mut ids) = split_data_borrow::<N, D>(self.data.as_mut(), self.metadata);
 
         // kd-sort both arrays for efficient search
-        sort(
+        sort::<N, D>(
             &mut ids,
             coords,
             self.metadata.node_size() as usize,
@@ -125,29 +134,88 @@ impl<N: IndexableNum> KDTreeBuilder<N> {
             metadata: self.metadata,
         }
     }
+
+    /// Like [`Self::finish`], but sorts using multiple threads via `rayon` once a subrange of
+    /// the build exceeds an internal threshold, falling back to sequential recursion below it to
+    /// avoid task overhead on small inputs.
+    ///
+    /// Requires the `rayon` feature. [`Self::finish`] always k-d sorts single-threaded and is
+    /// unaffected by whether this feature is enabled.
+    #[cfg(feature = "rayon")]
+    pub fn finish_parallel(mut self) -> KDTree<N, B, D> {
+        assert_eq!(
+            self.pos / D,
+            self.metadata.num_items() as usize,
+            "Added {} items when expected {}.",
+            self.pos / D,
+            self.metadata.num_items()
+        );
+
+        let (coords, mut ids) = split_data_borrow::<N, D>(self.data.as_mut(), self.metadata);
+
+        sort_parallel::<N, D>(&mut ids, coords, self.metadata.node_size() as usize, 0);
+
+        KDTree {
+            buffer: self.data,
+            metadata: self.metadata,
+        }
+    }
+}
+
+impl<N: IndexableNum, B: AsMut<[u8]> + AsRef<[u8]>> KDTreeBuilder<N, B, 2> {
+    /// Add a point to the KDTree.
+    ///
+    /// This returns a positional index that provides a lookup back into the original data.
+    #[inline]
+    pub fn add(&mut self, x: N, y: N) -> u32 {
+        self.add_n([x, y])
+    }
+
+    /// Add a coord to the KDTree.
+    ///
+    /// This returns a positional index that provides a lookup back into the original data.
+    #[inline]
+    pub fn add_coord(&mut self, coord: &impl CoordTrait<T = N>) -> u32 {
+        self.add(coord.x(), coord.y())
+    }
+
+    /// Add a point to the KDTree.
+    ///
+    /// This returns a positional index that provides a lookup back into the original data.
+    ///
+    /// ## Errors
+    ///
+    /// - If the point is empty.
+    #[inline]
+    pub fn add_point(&mut self, point: &impl PointTrait<T = N>) -> Result<u32> {
+        let coord = point.coord().ok_or(GeoIndexError::General(
+            "Unable to add empty point to KDTree".to_string(),
+        ))?;
+        Ok(self.add_coord(&coord))
+    }
 }
 
 /// Mutable borrow of coords and ids
-fn split_data_borrow<N: IndexableNum>(
+fn split_data_borrow<N: IndexableNum, const D: usize>(
     data: &mut [u8],
-    metadata: KDTreeMetadata<N>,
+    metadata: KDTreeMetadata<N, D>,
 ) -> (&mut [N], MutableIndices<'_>) {
     let (ids_buf, padded_coords_buf) =
         data[KDBUSH_HEADER_SIZE..].split_at_mut(metadata.indices_byte_size);
     let coords_buf = &mut padded_coords_buf[metadata.pad_coords_byte_size..];
     debug_assert_eq!(coords_buf.len(), metadata.coords_byte_size);
 
-    let ids = if metadata.num_items() < 65536 {
-        MutableIndices::U16(cast_slice_mut(ids_buf))
-    } else {
-        MutableIndices::U32(cast_slice_mut(ids_buf))
+    let ids = match metadata.indices_bytes_per_element {
+        1 => MutableIndices::U8(ids_buf),
+        2 => MutableIndices::U16(cast_slice_mut(ids_buf)),
+        _ => MutableIndices::U32(cast_slice_mut(ids_buf)),
     };
     let coords = cast_slice_mut(coords_buf);
 
     (coords, ids)
 }
 
-fn sort<N: IndexableNum>(
+fn sort<N: IndexableNum, const D: usize>(
     ids: &mut MutableIndices,
     coords: &mut [N],
     node_size: usize,
@@ -163,18 +231,69 @@ fn sort<N: IndexableNum>(
     let m = (left + right) >> 1;
 
     // sort ids and coords around the middle index so that the halves lie either left/right or
-    // top/bottom correspondingly (taking turns)
-    select(ids, coords, m, left, right, axis);
+    // top/bottom correspondingly (cycling through all `D` axes in turn)
+    select::<N, D>(ids, coords, m, left, right, axis);
 
-    // recursively kd-sort first half and second half on the opposite axis
-    sort(ids, coords, node_size, left, m - 1, 1 - axis);
-    sort(ids, coords, node_size, m + 1, right, 1 - axis);
+    // recursively kd-sort first half and second half on the next axis
+    let next_axis = (axis + 1) % D;
+    sort::<N, D>(ids, coords, node_size, left, m - 1, next_axis);
+    sort::<N, D>(ids, coords, node_size, m + 1, right, next_axis);
+}
+
+/// Tunable threshold below which [`sort_parallel`] falls back to sequential recursion rather
+/// than spawning a `rayon` task, to avoid paying task overhead on small subranges.
+#[cfg(feature = "rayon")]
+const PARALLEL_SORT_THRESHOLD: usize = 10_000;
+
+/// Like [`sort`], but splits the two recursive calls across threads via `rayon::join` once a
+/// subrange exceeds [`PARALLEL_SORT_THRESHOLD`] items.
+///
+/// `sort` indexes into the full buffer with absolute `left`/`right` bounds, which two
+/// concurrently-running recursive calls can't safely share as two `&mut` borrows of the same
+/// slice. This instead always receives `coords`/`ids` already sliced down to exactly the range
+/// being sorted (dropping the pivot item itself, which is already in its final place after
+/// `select`), so each half can be physically split into disjoint mutable subslices and recursed
+/// into concurrently.
+#[cfg(feature = "rayon")]
+fn sort_parallel<N: IndexableNum, const D: usize>(
+    ids: &mut MutableIndices,
+    coords: &mut [N],
+    node_size: usize,
+    axis: usize,
+) {
+    let num_items = coords.len() / D;
+    if num_items <= node_size {
+        return;
+    }
+
+    let right = num_items - 1;
+    let m = right >> 1;
+
+    // sort ids and coords around the middle index so that the halves lie either left/right or
+    // top/bottom correspondingly (cycling through all `D` axes in turn)
+    select::<N, D>(ids, coords, m, 0, right, axis);
+    let next_axis = (axis + 1) % D;
+
+    let (left_coords, rest_coords) = coords.split_at_mut(D * m);
+    let (_pivot_coords, right_coords) = rest_coords.split_at_mut(D);
+    let (mut left_ids, rest_ids) = ids.split_at_mut(m);
+    let (_pivot_id, mut right_ids) = rest_ids.split_at_mut(1);
+
+    if num_items > PARALLEL_SORT_THRESHOLD {
+        rayon::join(
+            || sort_parallel::<N, D>(&mut left_ids, left_coords, node_size, next_axis),
+            || sort_parallel::<N, D>(&mut right_ids, right_coords, node_size, next_axis),
+        );
+    } else {
+        sort_parallel::<N, D>(&mut left_ids, left_coords, node_size, next_axis);
+        sort_parallel::<N, D>(&mut right_ids, right_coords, node_size, next_axis);
+    }
 }
 
 /// Custom Floyd-Rivest selection algorithm: sort ids and coords so that [left..k-1] items are
-/// smaller than k-th item (on either x or y axis)
+/// smaller than k-th item (on the given axis, one of `D` dimensions)
 #[inline]
-fn select<N: IndexableNum>(
+fn select<N: IndexableNum, const D: usize>(
     ids: &mut MutableIndices,
     coords: &mut [N],
     k: usize,
@@ -196,35 +315,35 @@ fn select<N: IndexableNum>(
                 right,
                 f64::floor(k as f64 + ((n - m) * s) / n + sd) as usize,
             );
-            select(ids, coords, k, new_left, new_right, axis);
+            select::<N, D>(ids, coords, k, new_left, new_right, axis);
         }
 
-        let t = coords[2 * k + axis];
+        let t = coords[D * k + axis];
         let mut i = left;
         let mut j = right;
 
-        swap_item(ids, coords, left, k);
-        if coords[2 * right + axis] > t {
-            swap_item(ids, coords, left, right);
+        swap_item::<N, D>(ids, coords, left, k);
+        if coords[D * right + axis] > t {
+            swap_item::<N, D>(ids, coords, left, right);
         }
 
         while i < j {
-            swap_item(ids, coords, i, j);
+            swap_item::<N, D>(ids, coords, i, j);
             i += 1;
             j -= 1;
-            while coords[2 * i + axis] < t {
+            while coords[D * i + axis] < t {
                 i += 1;
             }
-            while coords[2 * j + axis] > t {
+            while coords[D * j + axis] > t {
                 j -= 1;
             }
         }
 
-        if coords[2 * left + axis] == t {
-            swap_item(ids, coords, left, j);
+        if coords[D * left + axis] == t {
+            swap_item::<N, D>(ids, coords, left, j);
         } else {
             j += 1;
-            swap_item(ids, coords, j, right);
+            swap_item::<N, D>(ids, coords, j, right);
         }
 
         if j <= k {
@@ -237,8 +356,14 @@ fn select<N: IndexableNum>(
 }
 
 #[inline]
-fn swap_item<N: IndexableNum>(ids: &mut MutableIndices, coords: &mut [N], i: usize, j: usize) {
+fn swap_item<N: IndexableNum, const D: usize>(
+    ids: &mut MutableIndices,
+    coords: &mut [N],
+    i: usize,
+    j: usize,
+) {
     ids.swap(i, j);
-    coords.swap(2 * i, 2 * j);
-    coords.swap(2 * i + 1, 2 * j + 1);
+    for lane in 0..D {
+        coords.swap(D * i + lane, D * j + lane);
+    }
 }
@@ -0,0 +1,94 @@
+//! Optional xxh3 checksum footer for persisted [`KDTree`] buffers.
+//!
+//! Unlike [`crate::kdtree::compression`], which wraps a buffer in a separate framing that must be
+//! decoded before it's queryable again, a checksummed buffer stays in the ordinary kdbush ABI:
+//! [`append`] only sets [`CHECKSUM_FLAG`] and appends an 8-byte hash, and
+//! [`KDTreeMetadata::data_buffer_length`][crate::kdtree::KDTreeMetadata::data_buffer_length]
+//! already accounts for the footer, so [`KDTreeRef::try_new`][crate::kdtree::KDTreeRef::try_new]
+//! reads it exactly like an unchecksummed buffer. [`verify`] is only called when a caller opts in
+//! via [`KDTree::verify_checksum`][crate::kdtree::KDTree::verify_checksum]/
+//! [`KDTreeRef::verify_checksum`][crate::kdtree::KDTreeRef::verify_checksum]/
+//! [`KDTreeRef::try_new_checked`][crate::kdtree::KDTreeRef::try_new_checked].
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::error::{GeoIndexError, Result};
+use crate::kdtree::index::{KDTreeMetadata, CHECKSUM_FLAG, CHECKSUM_FOOTER_SIZE};
+use crate::r#type::IndexableNum;
+
+/// Set [`CHECKSUM_FLAG`] on a copy of `data` and append an 8-byte xxh3 checksum of the result.
+pub(crate) fn append(data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    out[1] |= CHECKSUM_FLAG;
+    let checksum = xxh3_64(&out);
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+/// Verify `data`'s checksum footer against `metadata`, if it has one.
+///
+/// A no-op if `metadata` indicates no footer is present.
+pub(crate) fn verify<N: IndexableNum, const D: usize>(
+    data: &[u8],
+    metadata: &KDTreeMetadata<N, D>,
+) -> Result<()> {
+    if !metadata.has_checksum() {
+        return Ok(());
+    }
+
+    let footer_start = data.len() - CHECKSUM_FOOTER_SIZE;
+    let expected = u64::from_le_bytes(data[footer_start..].try_into().unwrap());
+    let actual = xxh3_64(&data[..footer_start]);
+    if actual != expected {
+        return Err(GeoIndexError::General(
+            "Checksum mismatch: buffer is corrupt.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kdtree::{KDTreeBuilder, KDTreeIndex, KDTreeRef};
+
+    #[test]
+    fn checksummed_buffer_still_queries_normally() {
+        let mut builder = KDTreeBuilder::<f64>::new(3);
+        builder.add(0., 0.);
+        builder.add(1., 1.);
+        builder.add(2., 2.);
+        let tree = builder.finish();
+
+        let checksummed = tree.to_checksummed();
+        let tree_ref = KDTreeRef::<f64>::try_new(&checksummed).unwrap();
+        assert_eq!(tree_ref.range(0.5, 0.5, 1.5, 1.5), vec![1]);
+        assert!(tree_ref.metadata.has_checksum());
+        assert!(tree_ref.verify_checksum().is_ok());
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let mut builder = KDTreeBuilder::<f64>::new(1);
+        builder.add(0., 0.);
+        let tree = builder.finish();
+
+        let mut checksummed = tree.to_checksummed();
+        let last = checksummed.len() - 1;
+        checksummed[last] ^= 0xff;
+
+        let tree_ref = KDTreeRef::<f64>::try_new(&checksummed).unwrap();
+        assert!(tree_ref.verify_checksum().is_err());
+        assert!(KDTreeRef::<f64>::try_new_checked(&checksummed).is_err());
+    }
+
+    #[test]
+    fn unchecksummed_buffer_verifies_as_a_no_op() {
+        let mut builder = KDTreeBuilder::<f64>::new(1);
+        builder.add(0., 0.);
+        let tree = builder.finish();
+
+        assert!(tree.verify_checksum().is_ok());
+    }
+}
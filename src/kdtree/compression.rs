@@ -0,0 +1,68 @@
+//! Optional xxh3 checksum and pluggable block compression for persisted [`KDTree`] buffers.
+//!
+//! Mirrors [`crate::rtree::compression`]; see its docs and [`crate::compression`] for the shared
+//! framing this builds on. Only the region after the 8-byte ABI header (indices and coords) is
+//! compressed; the header itself stays uncompressed and readable.
+
+use crate::compression::{read_compressed_stream, write_compressed_stream, CompressionType};
+use crate::error::Result;
+use crate::kdtree::index::{KDTree, KDTreeMetadata};
+use crate::r#type::IndexableNum;
+
+/// Compress and checksum an already-built `KDTree`'s buffer under the given codec.
+pub(crate) fn compress<N: IndexableNum, B: AsRef<[u8]>, const D: usize>(
+    tree: &KDTree<N, B, D>,
+    compression: CompressionType,
+) -> Vec<u8> {
+    let buffer = tree.as_ref();
+    let header: [u8; 8] = buffer[0..8].try_into().unwrap();
+    write_compressed_stream(&header, &buffer[8..], compression)
+}
+
+/// Reverse [`compress`], decompressing and checksum-verifying a stream back into a plain,
+/// zero-copy `KDTree` buffer.
+pub(crate) fn decompress<N: IndexableNum, const D: usize>(
+    data: &[u8],
+) -> Result<KDTree<N, Vec<u8>, D>> {
+    let buffer = read_compressed_stream(data)?;
+    let metadata = KDTreeMetadata::from_slice(&buffer)?;
+    Ok(KDTree { buffer, metadata })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kdtree::{KDTreeBuilder, KDTreeIndex};
+
+    #[test]
+    fn compresses_and_decompresses_round_trip() {
+        let mut builder = KDTreeBuilder::<f64>::new(3);
+        builder.add(0., 0.);
+        builder.add(1., 1.);
+        builder.add(2., 2.);
+        let tree = builder.finish();
+
+        for compression in [
+            CompressionType::None,
+            CompressionType::Lz4,
+            CompressionType::Miniz(6),
+        ] {
+            let compressed = compress(&tree, compression);
+            let restored = decompress::<f64, 2>(&compressed).unwrap();
+            assert_eq!(restored.range(0.5, 0.5, 1.5, 1.5), vec![1]);
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_stream() {
+        let mut builder = KDTreeBuilder::<f64>::new(1);
+        builder.add(0., 0.);
+        let tree = builder.finish();
+
+        let mut compressed = compress(&tree, CompressionType::Lz4);
+        let last = compressed.len() - 1;
+        compressed[last] ^= 0xff;
+
+        assert!(decompress::<f64, 2>(&compressed).is_err());
+    }
+}
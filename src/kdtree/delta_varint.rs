@@ -0,0 +1,205 @@
+//! Delta + LEB128 varint coordinate encoding, an alternate storage-optimized serialization for
+//! persisted [`KDTree`] buffers.
+//!
+//! [`KDTreeBuilder`][crate::kdtree::KDTreeBuilder] lays coordinates out via recursive median
+//! partitioning, so consecutive entries in each axis's coordinate stream tend to be spatially
+//! close together. This re-encodes each axis's stream as zig-zag deltas from the previous value,
+//! packed as LEB128 varints, which is usually far smaller than the fixed-width `coords_byte_size`
+//! region computed by [`KDTreeMetadata::new`]. Only the coordinate region is transformed; the
+//! header and indices are copied through unchanged, so [`decode`] can rematerialize the original
+//! fixed-width coordinate slice and hand the result to [`KDTreeMetadata::from_slice`] like any
+//! other buffer.
+
+use bytemuck::{cast_slice, pod_read_unaligned, Pod};
+
+use crate::error::{GeoIndexError, Result};
+use crate::kdtree::constants::KDBUSH_HEADER_SIZE;
+use crate::kdtree::index::{KDTree, KDTreeMetadata};
+use crate::r#type::IndexableNum;
+
+/// Bit set in the version nibble (the upper 4 bits of header byte 1) to mark a delta+varint
+/// encoded coordinate stream, so it can be told apart from the plain, fixed-width ABI layout
+/// without first decoding it.
+pub(crate) const DELTA_VARINT_FLAG: u8 = 0x40;
+
+/// Zig-zag encode a signed 64-bit delta so that small-magnitude values, positive or negative, map
+/// to small unsigned values, which is what makes varint encoding effective.
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Reverse [`zigzag_encode`].
+fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Append `value` to `out` as a LEB128 varint.
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Read a LEB128 varint from the front of `data`, returning the decoded value and the number of
+/// bytes consumed.
+fn read_varint(data: &[u8]) -> Result<(u64, usize)> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    for (i, &byte) in data.iter().enumerate() {
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+        shift += 7;
+    }
+    Err(GeoIndexError::TooShort {
+        expected: data.len() + 1,
+        actual: data.len(),
+    })
+}
+
+/// Read `value`'s raw in-memory bytes as a zero-extended `u64`. Sound for any `N` up to 8 bytes
+/// wide, which every [`IndexableNum`] is.
+fn to_bits<N: Pod>(value: N) -> u64 {
+    let mut buf = [0u8; 8];
+    let bytes = bytemuck::bytes_of(&value);
+    buf[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
+}
+
+/// Reverse [`to_bits`], reinterpreting the low `size_of::<N>()` bytes of `bits` back as `N`.
+fn from_bits<N: Pod>(bits: u64) -> N {
+    let buf = bits.to_le_bytes();
+    pod_read_unaligned(&buf[..std::mem::size_of::<N>()])
+}
+
+/// Encode an already-built `KDTree`'s coordinate region as per-axis zig-zag delta varints.
+pub(crate) fn encode<N: IndexableNum, B: AsRef<[u8]>, const D: usize>(
+    tree: &KDTree<N, B, D>,
+) -> Vec<u8> {
+    let metadata = tree.metadata();
+    let buffer = tree.as_ref();
+    let num_items = metadata.num_items() as usize;
+
+    let indices_start = KDBUSH_HEADER_SIZE;
+    let indices_end = indices_start + metadata.indices_byte_size + metadata.pad_coords_byte_size;
+    let coords = metadata.coords_slice(buffer);
+
+    let mut out = Vec::with_capacity(indices_end);
+    out.extend_from_slice(&buffer[0..KDBUSH_HEADER_SIZE]);
+    out[1] |= DELTA_VARINT_FLAG;
+    out.extend_from_slice(&buffer[indices_start..indices_end]);
+
+    for axis in 0..D {
+        let mut prev = 0i64;
+        for i in 0..num_items {
+            let bits = to_bits(coords[D * i + axis]) as i64;
+            write_varint(&mut out, zigzag_encode(bits.wrapping_sub(prev)));
+            prev = bits;
+        }
+    }
+
+    out
+}
+
+/// Reverse [`encode`], rematerializing the fixed-width coordinate slice and returning a plain,
+/// zero-copy `KDTree`.
+///
+/// ## Errors
+///
+/// Returns an error if `data` isn't flagged as a delta+varint stream, or if its varint stream
+/// runs out before every coordinate has been decoded.
+pub(crate) fn decode<N: IndexableNum, const D: usize>(data: &[u8]) -> Result<KDTree<N, Vec<u8>, D>> {
+    if data.len() < KDBUSH_HEADER_SIZE || data[1] & DELTA_VARINT_FLAG == 0 {
+        return Err(GeoIndexError::General(
+            "Data is not in delta+varint encoded format.".to_string(),
+        ));
+    }
+
+    let mut header = [0u8; KDBUSH_HEADER_SIZE];
+    header.copy_from_slice(&data[0..KDBUSH_HEADER_SIZE]);
+    header[1] &= !DELTA_VARINT_FLAG;
+
+    let node_size: u16 = cast_slice(&header[2..4])[0];
+    let num_items: u32 = cast_slice(&header[4..8])[0];
+    let metadata = KDTreeMetadata::<N, D>::new(num_items, node_size);
+    let num_items = num_items as usize;
+
+    let indices_start = KDBUSH_HEADER_SIZE;
+    let indices_end = indices_start + metadata.indices_byte_size + metadata.pad_coords_byte_size;
+    let indices_and_pad = data
+        .get(indices_start..indices_end)
+        .ok_or(GeoIndexError::TooShort {
+            expected: indices_end,
+            actual: data.len(),
+        })?;
+
+    let mut varint_data = &data[indices_end..];
+    let mut coords = vec![N::zero(); num_items * D];
+    for axis in 0..D {
+        let mut prev = 0i64;
+        for i in 0..num_items {
+            let (zigzag, consumed) = read_varint(varint_data)?;
+            varint_data = &varint_data[consumed..];
+            prev = prev.wrapping_add(zigzag_decode(zigzag));
+            coords[D * i + axis] = from_bits(prev as u64);
+        }
+    }
+
+    let mut buffer = Vec::with_capacity(metadata.data_buffer_length());
+    buffer.extend_from_slice(&header);
+    buffer.extend_from_slice(indices_and_pad);
+    buffer.extend_from_slice(cast_slice(&coords));
+
+    Ok(KDTree { buffer, metadata })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kdtree::{KDTreeBuilder, KDTreeIndex};
+
+    #[test]
+    fn encodes_and_decodes_round_trip() {
+        let mut builder = KDTreeBuilder::<f64>::new(5);
+        for (x, y) in [(0., 0.), (1., 1.), (2., 2.), (100., -50.), (3.5, 7.25)] {
+            builder.add(x, y);
+        }
+        let tree = builder.finish();
+
+        let encoded = encode(&tree);
+        assert!(encoded.len() < tree.as_ref().len());
+
+        let restored = decode::<f64, 2>(&encoded).unwrap();
+        assert_eq!(restored.range(1.5, 1.5, 100.5, 7.5), tree.range(1.5, 1.5, 100.5, 7.5));
+        assert_eq!(restored.as_ref(), tree.as_ref());
+    }
+
+    #[test]
+    fn rejects_a_plain_buffer() {
+        let mut builder = KDTreeBuilder::<f64>::new(1);
+        builder.add(0., 0.);
+        let tree = builder.finish();
+
+        assert!(decode::<f64, 2>(tree.as_ref()).is_err());
+    }
+
+    #[test]
+    fn round_trips_an_integer_type() {
+        let mut builder = KDTreeBuilder::<i32>::new(4);
+        for (x, y) in [(0, 0), (10, -10), (1000, 2000), (-5, 5)] {
+            builder.add(x, y);
+        }
+        let tree = builder.finish();
+
+        let encoded = encode(&tree);
+        let restored = decode::<i32, 2>(&encoded).unwrap();
+        assert_eq!(restored.as_ref(), tree.as_ref());
+    }
+}
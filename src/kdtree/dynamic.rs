@@ -0,0 +1,454 @@
+//! A dynamic, insert-capable wrapper around the immutable [`KDTree`].
+//!
+//! [`KDTreeBuilder`]/[`KDTreeIndex`] produce an immutable, bulk-loaded tree with no way to add
+//! points after [`finish`][KDTreeBuilder::finish]. [`DynamicKDTree`] restores incremental
+//! insertion by applying the same "logarithmic method" of dynamization that
+//! [`DynamicRTree`][crate::rtree::DynamicRTree] uses on top of the static builder: a small linear
+//! buffer absorbs new inserts, and once it fills its contents are merged with existing trees and
+//! rebuilt into a single new immutable tree, the way a binary counter carries.
+
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::kdtree::{KDTree, KDTreeBuilder, KDTreeIndex};
+use crate::r#type::IndexableNum;
+
+
+/// The number of bits of buffer capacity: the linear buffer holds up to `1 << BUFFER_BITS` items
+/// before it is flushed into a tree.
+const BUFFER_BITS: u32 = 6;
+
+/// Once a tree's live fraction (non-tombstoned items) drops below this threshold, it is rebuilt
+/// during the next compaction pass to reclaim space.
+const COMPACTION_THRESHOLD: f64 = 0.5;
+
+struct BufferedItem<N: IndexableNum, D> {
+    id: u64,
+    x: N,
+    y: N,
+    data: D,
+}
+
+/// One occupied slot of the dynamization forest: an immutable [`KDTree`] together with the
+/// global item id and user data for each of its local leaf positions.
+struct Slot<N: IndexableNum, D> {
+    tree: KDTree<N>,
+    ids: Vec<u64>,
+    data: Vec<D>,
+    /// Each item's `[x, y]`, in insertion order (the tree's own `coords()` holds them kd-sorted).
+    coords: Vec<N>,
+    live_count: usize,
+}
+
+/// A dynamic, insert-capable K-D tree.
+///
+/// This wraps [`KDTreeBuilder`]/[`KDTree`] with the classic dynamization scheme used to add
+/// incremental insertion to an otherwise-static structure: a small flat buffer absorbs new
+/// inserts and is searched linearly, while a vector of optional immutable trees holds
+/// geometrically-sized batches (slot `i`, when occupied, holds at most `2^(i + BUFFER_BITS)`
+/// items). When the buffer fills, its items plus every occupied consecutive low slot are merged
+/// and rebuilt into one new tree placed at the first empty slot, amortizing rebuild cost to
+/// `O(log n)` per insert.
+///
+/// Deletion is logical: a tombstone set is consulted at query time, and [`Self::compact`]
+/// rebuilds any slot whose live fraction has dropped below a threshold. Item ids are assigned
+/// sequentially across all inserts, so once returned from [`Self::insert`] an id remains stable
+/// for the lifetime of the tree.
+///
+/// ```
+/// use geo_index::kdtree::DynamicKDTree;
+///
+/// let mut tree = DynamicKDTree::<f64>::new();
+/// let id0 = tree.insert(0., 0., "a");
+/// let id1 = tree.insert(5., 5., "b");
+///
+/// let results = tree.within(0., 0., 1.);
+/// assert_eq!(results, vec![id0]);
+///
+/// tree.remove(id1);
+/// assert_eq!(tree.within(0., 0., 10.), vec![id0]);
+/// ```
+pub struct DynamicKDTree<N: IndexableNum, D = u64> {
+    buffer: Vec<BufferedItem<N, D>>,
+    slots: Vec<Option<Slot<N, D>>>,
+    tombstones: HashSet<u64>,
+    next_id: u64,
+}
+
+impl<N: IndexableNum, D: Clone> Default for DynamicKDTree<N, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: IndexableNum, D: Clone> DynamicKDTree<N, D> {
+    /// Create a new, empty dynamic K-D tree.
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::with_capacity(1 << BUFFER_BITS),
+            slots: Vec::new(),
+            tombstones: HashSet::new(),
+            next_id: 0,
+        }
+    }
+
+    /// The total number of live (non-deleted) items in this tree.
+    pub fn len(&self) -> usize {
+        self.buffer
+            .iter()
+            .filter(|item| !self.tombstones.contains(&item.id))
+            .count()
+            + self
+                .slots
+                .iter()
+                .filter_map(|slot| slot.as_ref().map(|s| s.live_count))
+                .sum::<usize>()
+    }
+
+    /// Returns `true` if this tree contains no live items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a new point with associated data, returning a stable id that can later be passed
+    /// to [`Self::remove`].
+    pub fn insert(&mut self, x: N, y: N, data: D) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.buffer.push(BufferedItem { id, x, y, data });
+
+        if self.buffer.len() >= 1 << BUFFER_BITS {
+            self.flush_buffer();
+        }
+
+        id
+    }
+
+    /// Logically delete an item by id. The item is skipped by future queries but its storage is
+    /// only reclaimed the next time its containing slot is compacted.
+    pub fn remove(&mut self, id: u64) {
+        if !self.tombstones.insert(id) {
+            return;
+        }
+        for slot in self.slots.iter_mut().flatten() {
+            if slot.ids.contains(&id) {
+                slot.live_count = slot.live_count.saturating_sub(1);
+                break;
+            }
+        }
+    }
+
+    /// Search for items within a given bounding box.
+    pub fn range(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Vec<u64> {
+        let mut results = Vec::new();
+
+        for item in &self.buffer {
+            if self.tombstones.contains(&item.id) {
+                continue;
+            }
+            if item.x >= min_x && item.x <= max_x && item.y >= min_y && item.y <= max_y {
+                results.push(item.id);
+            }
+        }
+
+        for slot in self.slots.iter().flatten() {
+            for local_index in slot.tree.range(min_x, min_y, max_x, max_y) {
+                let id = slot.ids[local_index as usize];
+                if !self.tombstones.contains(&id) {
+                    results.push(id);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Search for items within a given radius of a query point.
+    pub fn within(&self, qx: N, qy: N, r: N) -> Vec<u64> {
+        let mut results = Vec::new();
+        let r2 = r * r;
+
+        for item in &self.buffer {
+            if self.tombstones.contains(&item.id) {
+                continue;
+            }
+            let dx = item.x - qx;
+            let dy = item.y - qy;
+            if dx * dx + dy * dy <= r2 {
+                results.push(item.id);
+            }
+        }
+
+        for slot in self.slots.iter().flatten() {
+            for local_index in slot.tree.within(qx, qy, r) {
+                let id = slot.ids[local_index as usize];
+                if !self.tombstones.contains(&id) {
+                    results.push(id);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Find the `k` nearest neighbors to a query point, in ascending order of distance.
+    ///
+    /// Since each slot is its own independently-sorted tree, this queries the buffer and every
+    /// occupied tree for their own `k` nearest candidates and merges the per-component results
+    /// with a bounded max-heap, the same way [`KDTreeIndex::neighbors`] merges subtree results
+    /// internally.
+    pub fn neighbors(&self, qx: N, qy: N, k: usize) -> Vec<u64> {
+        if k == 0 {
+            return vec![];
+        }
+
+        let mut best: BinaryHeap<DynamicKnnCandidate> = BinaryHeap::with_capacity(k + 1);
+
+        for item in &self.buffer {
+            if self.tombstones.contains(&item.id) {
+                continue;
+            }
+            let dx = item.x - qx;
+            let dy = item.y - qy;
+            let dist = (dx * dx + dy * dy).to_f64().unwrap_or(f64::MAX);
+            offer(&mut best, k, DynamicKnnCandidate { dist, id: item.id });
+        }
+
+        for slot in self.slots.iter().flatten() {
+            // Ask each tree for up to `k` live candidates, over-fetching to account for
+            // tombstones that might fall within its own `k` nearest but not the live set's.
+            for local_index in slot.tree.neighbors(qx, qy, k + self.tombstones.len()) {
+                let id = slot.ids[local_index as usize];
+                if self.tombstones.contains(&id) {
+                    continue;
+                }
+                let x = slot.coords[2 * local_index as usize];
+                let y = slot.coords[2 * local_index as usize + 1];
+                let dx = x - qx;
+                let dy = y - qy;
+                let dist = (dx * dx + dy * dy).to_f64().unwrap_or(f64::MAX);
+                offer(&mut best, k, DynamicKnnCandidate { dist, id });
+            }
+        }
+
+        best.into_sorted_vec().into_iter().map(|c| c.id).collect()
+    }
+
+    /// Rebuild any slot whose live fraction has dropped below [`COMPACTION_THRESHOLD`], reclaiming
+    /// the space occupied by tombstoned items.
+    pub fn compact(&mut self) {
+        for slot_opt in self.slots.iter_mut() {
+            let needs_compaction = match slot_opt {
+                Some(slot) if slot.tree.num_items() > 0 => {
+                    (slot.live_count as f64) / (slot.tree.num_items() as f64) < COMPACTION_THRESHOLD
+                }
+                _ => false,
+            };
+            if !needs_compaction {
+                continue;
+            }
+
+            let slot = slot_opt.take().unwrap();
+            let live: Vec<_> = slot
+                .ids
+                .iter()
+                .zip(slot.data.iter())
+                .enumerate()
+                .filter(|(_, (id, _))| !self.tombstones.contains(id))
+                .map(|(local_index, (&id, data))| {
+                    let coords = &slot.coords;
+                    (
+                        id,
+                        coords[2 * local_index],
+                        coords[2 * local_index + 1],
+                        data.clone(),
+                    )
+                })
+                .collect();
+
+            if live.is_empty() {
+                *slot_opt = None;
+                continue;
+            }
+
+            *slot_opt = Some(build_slot(live));
+        }
+    }
+
+    fn flush_buffer(&mut self) {
+        let mut items: Vec<_> = self
+            .buffer
+            .drain(..)
+            .map(|item| (item.id, item.x, item.y, item.data))
+            .collect();
+
+        // Merge with every occupied consecutive low slot, like carrying a binary counter.
+        let mut slot_index = 0;
+        loop {
+            match self.slots.get_mut(slot_index) {
+                Some(slot @ Some(_)) => {
+                    let occupied = slot.take().unwrap();
+                    let coords = &occupied.coords;
+                    for (local_index, (id, data)) in
+                        occupied.ids.into_iter().zip(occupied.data).enumerate()
+                    {
+                        items.push((
+                            id,
+                            coords[2 * local_index],
+                            coords[2 * local_index + 1],
+                            data,
+                        ));
+                    }
+                    slot_index += 1;
+                }
+                Some(None) => break,
+                None => {
+                    self.slots.push(None);
+                    break;
+                }
+            }
+        }
+
+        let mut new_slot = build_slot(items);
+        new_slot.live_count = new_slot
+            .ids
+            .iter()
+            .filter(|id| !self.tombstones.contains(id))
+            .count();
+        self.slots[slot_index] = Some(new_slot);
+    }
+}
+
+/// A candidate `(distance, id)` pair in [`DynamicKDTree::neighbors`]'s bounded max-heap, ordered
+/// so that the farthest of the current best candidates sits at the top of the heap, ready to be
+/// evicted in `O(log k)` once a closer candidate turns up.
+struct DynamicKnnCandidate {
+    dist: f64,
+    id: u64,
+}
+
+impl PartialEq for DynamicKnnCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+
+impl Eq for DynamicKnnCandidate {}
+
+impl PartialOrd for DynamicKnnCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DynamicKnnCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist
+            .partial_cmp(&other.dist)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+#[inline]
+fn offer(best: &mut BinaryHeap<DynamicKnnCandidate>, k: usize, candidate: DynamicKnnCandidate) {
+    if best.len() < k {
+        best.push(candidate);
+    } else if let Some(worst) = best.peek() {
+        if candidate.dist < worst.dist {
+            best.pop();
+            best.push(candidate);
+        }
+    }
+}
+
+fn build_slot<N: IndexableNum, D>(items: Vec<(u64, N, N, D)>) -> Slot<N, D> {
+    let mut builder = KDTreeBuilder::<N>::new(items.len() as u32);
+    let mut ids = Vec::with_capacity(items.len());
+    let mut data = Vec::with_capacity(items.len());
+    let mut coords = Vec::with_capacity(items.len() * 2);
+    for (id, x, y, item_data) in items {
+        builder.add(x, y);
+        ids.push(id);
+        data.push(item_data);
+        coords.extend_from_slice(&[x, y]);
+    }
+    let tree = builder.finish();
+    let live_count = ids.len();
+    Slot {
+        tree,
+        ids,
+        data,
+        coords,
+        live_count,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_and_search_within_buffer() {
+        let mut tree = DynamicKDTree::<f64>::new();
+        let id0 = tree.insert(0., 0., 0u64);
+        let id1 = tree.insert(5., 5., 1u64);
+        assert_eq!(tree.within(0., 0., 2.), vec![id0]);
+        assert_eq!(tree.len(), 2);
+        let _ = id1;
+    }
+
+    #[test]
+    fn flushes_buffer_into_a_tree() {
+        let mut tree = DynamicKDTree::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..100 {
+            let x = i as f64;
+            ids.push(tree.insert(x, x, i));
+        }
+        assert_eq!(tree.len(), 100);
+        let results = tree.range(0., 0., 3., 3.);
+        assert!(results.contains(&ids[0]));
+        assert!(results.contains(&ids[1]));
+        assert!(results.contains(&ids[2]));
+    }
+
+    #[test]
+    fn remove_is_logical_and_hides_results() {
+        let mut tree = DynamicKDTree::<f64>::new();
+        let id0 = tree.insert(0., 0., "a");
+        tree.remove(id0);
+        assert_eq!(tree.within(0., 0., 1.), Vec::<u64>::new());
+        assert_eq!(tree.len(), 0);
+    }
+
+    #[test]
+    fn compact_reclaims_tombstoned_slots() {
+        let mut tree = DynamicKDTree::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..64 {
+            let x = i as f64;
+            ids.push(tree.insert(x, x, i));
+        }
+        for &id in &ids[..40] {
+            tree.remove(id);
+        }
+        tree.compact();
+        assert_eq!(tree.len(), 24);
+        for &id in &ids[40..] {
+            assert!(tree.range(0., 0., 100., 100.).contains(&id));
+        }
+    }
+
+    #[test]
+    fn neighbors_merges_across_buffer_and_trees() {
+        let mut tree = DynamicKDTree::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..70 {
+            let x = i as f64;
+            ids.push(tree.insert(x, 0., i));
+        }
+        // First 64 items flushed into a tree, remaining 6 still in the buffer.
+        let nearest = tree.neighbors(0., 0., 3);
+        assert_eq!(nearest, vec![ids[0], ids[1], ids[2]]);
+    }
+}
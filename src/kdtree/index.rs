@@ -7,27 +7,77 @@ use crate::indices::Indices;
 use crate::kdtree::constants::{KDBUSH_HEADER_SIZE, KDBUSH_MAGIC, KDBUSH_VERSION};
 use crate::r#type::IndexableNum;
 
+/// The version preceding the `u8` index tier, kept around so [`KDTreeMetadata::from_slice`] can
+/// still decode buffers written before it, where indices were always `u16` or `u32`.
+const PRE_U8_TIER_VERSION: u8 = KDBUSH_VERSION - 1;
+
+/// Bit set in the version nibble (the upper 4 bits of header byte 1) to mark that an 8-byte xxh3
+/// checksum footer follows the coordinate region, for [`KDTree::verify_checksum`]/
+/// [`KDTreeRef::verify_checksum`] to detect buffer corruption.
+///
+/// Unlike [`crate::compression::COMPRESSED_FLAG`]/[`crate::kdtree::delta_varint::DELTA_VARINT_FLAG`],
+/// which mark alternate framings that must be decoded back into a plain buffer before they're
+/// queryable, this bit is transparent to ordinary readers: [`KDTreeMetadata::from_header`] masks
+/// it out before interpreting the version, and [`KDTreeMetadata::data_buffer_length`] already
+/// accounts for the footer, so a checksummed buffer parses and queries normally for consumers who
+/// never call [`KDTree::verify_checksum`].
+pub(crate) const CHECKSUM_FLAG: u8 = 0x20;
+
+/// Size, in bytes, of the xxh3 checksum footer appended when [`CHECKSUM_FLAG`] is set.
+pub(crate) const CHECKSUM_FOOTER_SIZE: usize = 8;
+
+/// The number of bytes used per index element, given the total number of items in the tree.
+///
+/// Small trees (`num_items < 256`) fit their indices in a `u8`, shrinking the index section
+/// further than the `u16`/`u32` split alone, which matters for the many-small-trees case and for
+/// WASM memory footprint.
+fn indices_bytes_per_element(num_items: u32) -> usize {
+    if num_items < 256 {
+        1
+    } else if num_items < 65536 {
+        2
+    } else {
+        4
+    }
+}
+
+/// The pre-v{KDBUSH_VERSION} index width rule, where the `u8` tier didn't yet exist.
+fn legacy_indices_bytes_per_element(num_items: u32) -> usize {
+    if num_items < 65536 {
+        2
+    } else {
+        4
+    }
+}
+
 /// Common metadata to describe a KDTree
 ///
 /// You can use the metadata to infer the total byte size of a tree given the provided criteria.
 /// See [`data_buffer_length`][Self::data_buffer_length].
+///
+/// Generic over the number of dimensions `D` of the indexed points, defaulting to `2`. A
+/// [`KDTreeBuilder`]/[`KDTree`] of dimension `D` lays coordinates out as `D`-stride records
+/// (`[x0, y0, z0, ..., x1, y1, z1, ...]`), which is what lets [`KDTreeBuilder::add_n`] accept an
+/// `[N; D]` for any `D` while keeping the ordinary 2D `KDTree<N>` unchanged.
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct KDTreeMetadata<N: IndexableNum> {
+pub struct KDTreeMetadata<N: IndexableNum, const D: usize = 2> {
     node_size: u16,
     num_items: u32,
     phantom: PhantomData<N>,
     pub(crate) indices_byte_size: usize,
+    pub(crate) indices_bytes_per_element: usize,
     pub(crate) pad_coords_byte_size: usize,
     pub(crate) coords_byte_size: usize,
+    checksummed: bool,
 }
 
-impl<N: IndexableNum> KDTreeMetadata<N> {
+impl<N: IndexableNum, const D: usize> KDTreeMetadata<N, D> {
     /// Construct a new [`KDTreeMetadata`] from a number of items and node size.
     pub fn new(num_items: u32, node_size: u16) -> Self {
         assert!((2..=65535).contains(&node_size));
 
-        let coords_byte_size = (num_items as usize) * 2 * N::BYTES_PER_ELEMENT;
-        let indices_bytes_per_element = if num_items < 65536 { 2 } else { 4 };
+        let coords_byte_size = (num_items as usize) * D * N::BYTES_PER_ELEMENT;
+        let indices_bytes_per_element = indices_bytes_per_element(num_items);
         let indices_byte_size = (num_items as usize) * indices_bytes_per_element;
         let pad_coords_byte_size = (8 - (indices_byte_size % 8)) % 8;
 
@@ -36,23 +86,79 @@ impl<N: IndexableNum> KDTreeMetadata<N> {
             num_items,
             phantom: PhantomData,
             indices_byte_size,
+            indices_bytes_per_element,
             pad_coords_byte_size,
             coords_byte_size,
+            checksummed: false,
         }
     }
 
+    /// Construct metadata matching the [`PRE_U8_TIER_VERSION`] layout, where indices were always
+    /// `u16` or `u32`, for decoding buffers written before the `u8` tier was added.
+    fn new_legacy(num_items: u32, node_size: u16) -> Self {
+        assert!((2..=65535).contains(&node_size));
+
+        let coords_byte_size = (num_items as usize) * D * N::BYTES_PER_ELEMENT;
+        let indices_bytes_per_element = legacy_indices_bytes_per_element(num_items);
+        let indices_byte_size = (num_items as usize) * indices_bytes_per_element;
+        let pad_coords_byte_size = (8 - (indices_byte_size % 8)) % 8;
+
+        Self {
+            node_size,
+            num_items,
+            phantom: PhantomData,
+            indices_byte_size,
+            indices_bytes_per_element,
+            pad_coords_byte_size,
+            coords_byte_size,
+            checksummed: false,
+        }
+    }
+
+    /// Mark this metadata as describing a buffer with an appended checksum footer, adjusting
+    /// [`Self::data_buffer_length`] accordingly. Used by [`Self::from_header`] when parsing a
+    /// buffer written by [`KDTree::to_checksummed`].
+    pub(crate) fn with_checksum(mut self) -> Self {
+        self.checksummed = true;
+        self
+    }
+
+    /// Whether this metadata describes a buffer with an appended xxh3 checksum footer.
+    pub fn has_checksum(&self) -> bool {
+        self.checksummed
+    }
+
     /// Construct a new [`KDTreeMetadata`] from an existing byte slice conforming to the "kdbush
     /// ABI", such as what [`KDTreeBuilder`] generates.
     pub fn from_slice(data: &[u8]) -> Result<Self> {
-        if data[0] != KDBUSH_MAGIC {
+        let header: [u8; KDBUSH_HEADER_SIZE] = data
+            .get(0..KDBUSH_HEADER_SIZE)
+            .ok_or(GeoIndexError::TooShort {
+                expected: KDBUSH_HEADER_SIZE,
+                actual: data.len(),
+            })?
+            .try_into()
+            .unwrap();
+        Self::from_header(&header, data.len())
+    }
+
+    /// Parse a [`KDTreeMetadata`] from just the 8-byte "kdbush ABI" header, without requiring the
+    /// rest of the buffer to be resident.
+    ///
+    /// Used by [`Self::from_slice`], and by
+    /// [`Self::from_source`] to lay out a
+    /// [`LazyKDTree`][crate::kdtree::lazy::LazyKDTree] over a buffer that's paged in on demand.
+    fn from_header(header: &[u8; KDBUSH_HEADER_SIZE], total_len: usize) -> Result<Self> {
+        if header[0] != KDBUSH_MAGIC {
             return Err(GeoIndexError::General(
                 "Data not in Kdbush format.".to_string(),
             ));
         }
 
-        let version_and_type = data[1];
-        let version = version_and_type >> 4;
-        if version != KDBUSH_VERSION {
+        let version_and_type = header[1];
+        let checksummed = version_and_type & CHECKSUM_FLAG != 0;
+        let version = (version_and_type & !CHECKSUM_FLAG) >> 4;
+        if version != KDBUSH_VERSION && version != PRE_U8_TIER_VERSION {
             return Err(GeoIndexError::General(
                 format!("Got v{version} data when expected v{KDBUSH_VERSION}.").to_string(),
             ));
@@ -70,21 +176,46 @@ impl<N: IndexableNum> KDTreeMetadata<N> {
             ));
         }
 
-        let node_size: u16 = cast_slice(&data[2..4])[0];
-        let num_items: u32 = cast_slice(&data[4..8])[0];
+        let node_size: u16 = cast_slice(&header[2..4])[0];
+        let num_items: u32 = cast_slice(&header[4..8])[0];
 
-        let slf = Self::new(num_items, node_size);
-        if slf.data_buffer_length() != data.len() {
+        let slf = if version == PRE_U8_TIER_VERSION {
+            Self::new_legacy(num_items, node_size)
+        } else {
+            Self::new(num_items, node_size)
+        };
+        let slf = if checksummed { slf.with_checksum() } else { slf };
+        if slf.data_buffer_length() != total_len {
             return Err(GeoIndexError::General(format!(
                 "Expected {} bytes but received byte slice with {} bytes",
                 slf.data_buffer_length(),
-                data.len()
+                total_len
             )));
         }
 
         Ok(slf)
     }
 
+    /// Construct a [`KDTreeMetadata`] by reading only the 8-byte header out of `source`, without
+    /// pulling the rest of the (potentially huge) buffer into memory.
+    ///
+    /// Pair with [`LazyKDTree`][crate::kdtree::lazy::LazyKDTree] to query an index whose
+    /// coordinate and index regions are paged in lazily via
+    /// [`KDTreeSource::read_range`][crate::kdtree::lazy::KDTreeSource::read_range].
+    pub fn from_source<S: crate::kdtree::lazy::KDTreeSource>(source: &S) -> Result<Self> {
+        if source.len() < KDBUSH_HEADER_SIZE {
+            return Err(GeoIndexError::TooShort {
+                expected: KDBUSH_HEADER_SIZE,
+                actual: source.len(),
+            });
+        }
+        let header: [u8; KDBUSH_HEADER_SIZE] = source
+            .read_range(0, KDBUSH_HEADER_SIZE)
+            .try_into()
+            .unwrap();
+        Self::from_header(&header, source.len())
+    }
+
     /// The maximum number of items per node.
     pub fn node_size(&self) -> u16 {
         self.node_size
@@ -108,6 +239,11 @@ impl<N: IndexableNum> KDTreeMetadata<N> {
             + self.coords_byte_size
             + self.indices_byte_size
             + self.pad_coords_byte_size
+            + if self.checksummed {
+                CHECKSUM_FOOTER_SIZE
+            } else {
+                0
+            }
     }
 
     /// Access the slice of coordinates from the data buffer this metadata represents.
@@ -125,39 +261,130 @@ impl<N: IndexableNum> KDTreeMetadata<N> {
     pub fn indices_slice<'a>(&self, data: &'a [u8]) -> Indices<'a> {
         let indices_buf = &data[KDBUSH_HEADER_SIZE..KDBUSH_HEADER_SIZE + self.indices_byte_size];
 
-        if self.num_items < 65536 {
-            Indices::U16(cast_slice(indices_buf))
-        } else {
-            Indices::U32(cast_slice(indices_buf))
+        match self.indices_bytes_per_element {
+            1 => Indices::U8(indices_buf),
+            2 => Indices::U16(cast_slice(indices_buf)),
+            _ => Indices::U32(cast_slice(indices_buf)),
         }
     }
 }
 
-/// An owned KDTree buffer.
+/// A KDTree buffer, generic over its backing storage.
 ///
-/// Usually this will be created from scratch via [`KDTreeBuilder`][crate::kdtree::KDTreeBuilder].
+/// The default `B = Vec<u8>` (aliased as [`OwnedKDTree`]) heap-allocates its own buffer.
+/// [`KDTreeBuilder::from_metadata_in`][crate::kdtree::KDTreeBuilder::from_metadata_in] instead
+/// builds directly into a caller-provided `&mut [u8]` (backed by an `mmap`'d file or bump arena,
+/// say), avoiding a second full-size allocation when the index is ultimately persisted there.
 #[derive(Debug, Clone, PartialEq)]
-pub struct KDTree<N: IndexableNum> {
-    pub(crate) buffer: Vec<u8>,
-    pub(crate) metadata: KDTreeMetadata<N>,
+pub struct KDTree<N: IndexableNum, B: AsRef<[u8]> = Vec<u8>, const D: usize = 2> {
+    pub(crate) buffer: B,
+    pub(crate) metadata: KDTreeMetadata<N, D>,
 }
 
-impl<N: IndexableNum> KDTree<N> {
+/// A [`KDTree`] that owns a heap-allocated `Vec<u8>` buffer.
+///
+/// This is the ordinary, default way to build and hold a `KDTree`.
+pub type OwnedKDTree<N> = KDTree<N, Vec<u8>>;
+
+impl<N: IndexableNum, B: AsRef<[u8]>, const D: usize> KDTree<N, B, D> {
     /// Consume this KDTree, returning the underlying buffer.
-    pub fn into_inner(self) -> Vec<u8> {
+    pub fn into_inner(self) -> B {
         self.buffer
     }
+
+    /// Access the underlying [`KDTreeMetadata`] of this instance.
+    ///
+    /// Available for any `D`; see
+    /// [`KDTreeIndex`][crate::kdtree::KDTreeIndex]/[`KDTreeIndex::range_nd`]/
+    /// [`KDTreeIndex::within_nd`] for `D`-dimensional querying.
+    pub fn metadata(&self) -> &KDTreeMetadata<N, D> {
+        &self.metadata
+    }
+
+    /// Compress this tree's buffer under the given codec, for cheaper storage or transmission.
+    ///
+    /// The tree itself is never queried in compressed form: this only compresses the serialized
+    /// bytes, and only the region after the 8-byte header (indices and coords), leaving the
+    /// header itself uncompressed and readable. Pass the result to [`KDTree::from_compressed`]
+    /// to recover a normal, zero-copy tree.
+    #[cfg(feature = "compression")]
+    pub fn to_compressed(&self, compression: crate::compression::CompressionType) -> Vec<u8> {
+        crate::kdtree::compression::compress(self, compression)
+    }
+
+    /// Re-encode this tree's coordinate region as per-axis zig-zag delta LEB128 varints, for
+    /// cheaper storage than the fixed-width layout when items are spatially coherent (as they are
+    /// after [`KDTreeBuilder::finish`][crate::kdtree::KDTreeBuilder::finish]'s median-partitioning
+    /// sort).
+    ///
+    /// The indices region and the 8-byte ABI header are left untouched. Pass the result to
+    /// [`KDTree::from_delta_varint`] to recover a normal, zero-copy tree.
+    pub fn to_delta_varint(&self) -> Vec<u8> {
+        crate::kdtree::delta_varint::encode(self)
+    }
+
+    /// Append an 8-byte xxh3 checksum footer to this tree's buffer, returning a new buffer that
+    /// [`KDTree::verify_checksum`]/[`KDTreeRef::verify_checksum`] can use to detect corruption.
+    ///
+    /// The checksum covers the whole buffer (header, indices, and coordinates) with
+    /// [`CHECKSUM_FLAG`] already set, so flipping any bit anywhere is caught. Unlike
+    /// [`Self::to_compressed`]/[`Self::to_delta_varint`], the result is still a plain, directly
+    /// queryable buffer: [`KDTreeRef::try_new`] reads it the same as an unchecksummed one, since
+    /// [`KDTreeMetadata::data_buffer_length`] already accounts for the footer.
+    #[cfg(feature = "compression")]
+    pub fn to_checksummed(&self) -> Vec<u8> {
+        crate::kdtree::checksum::append(self.as_ref())
+    }
+
+    /// Verify this tree's checksum footer, detecting buffer corruption.
+    ///
+    /// A no-op returning `Ok(())` if this tree has no checksum footer, i.e. wasn't produced via
+    /// [`Self::to_checksummed`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the footer's xxh3 checksum doesn't match the rest of the buffer.
+    #[cfg(feature = "compression")]
+    pub fn verify_checksum(&self) -> Result<()> {
+        crate::kdtree::checksum::verify(self.as_ref(), &self.metadata)
+    }
 }
 
-impl<N: IndexableNum> AsRef<[u8]> for KDTree<N> {
+impl<N: IndexableNum, B: AsRef<[u8]>, const D: usize> AsRef<[u8]> for KDTree<N, B, D> {
     fn as_ref(&self) -> &[u8] {
-        &self.buffer
+        self.buffer.as_ref()
+    }
+}
+
+impl<N: IndexableNum, const D: usize> KDTree<N, Vec<u8>, D> {
+    /// Reverse [`KDTree::to_compressed`], decompressing and checksum-verifying a compressed byte
+    /// stream back into a plain, zero-copy `KDTree`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `data` isn't flagged as a compressed stream, if it fails to
+    /// decompress, or if the decompressed buffer fails its xxh3 checksum.
+    #[cfg(feature = "compression")]
+    pub fn from_compressed(data: &[u8]) -> Result<Self> {
+        crate::kdtree::compression::decompress(data)
+    }
+
+    /// Reverse [`KDTree::to_delta_varint`], rematerializing the fixed-width coordinate slice back
+    /// into a plain, zero-copy `KDTree`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `data` isn't flagged as a delta+varint encoded stream, or if its varint
+    /// stream runs out before every coordinate has been decoded.
+    pub fn from_delta_varint(data: &[u8]) -> Result<Self> {
+        crate::kdtree::delta_varint::decode(data)
     }
 }
 
 /// A reference on an external KDTree buffer.
 #[derive(Debug, Clone, PartialEq)]
 pub struct KDTreeRef<'a, N: IndexableNum> {
+    pub(crate) data: &'a [u8],
     pub(crate) coords: &'a [N],
     pub(crate) indices: Indices<'a>,
     pub(crate) metadata: KDTreeMetadata<N>,
@@ -177,12 +404,24 @@ impl<'a, N: IndexableNum> KDTreeRef<'a, N> {
         let indices = metadata.indices_slice(data);
 
         Ok(Self {
+            data,
             coords,
             indices,
             metadata,
         })
     }
 
+    /// Like [`Self::try_new`], but also verifies the checksum footer if this buffer has one,
+    /// rejecting corrupt data up front instead of silently returning wrong query results.
+    ///
+    /// A buffer with no checksum footer passes through unchanged, identically to [`Self::try_new`].
+    #[cfg(feature = "compression")]
+    pub fn try_new_checked<T: AsRef<[u8]>>(data: &'a T) -> Result<Self> {
+        let slf = Self::try_new(data)?;
+        slf.verify_checksum()?;
+        Ok(slf)
+    }
+
     /// Construct a new KDTreeRef without doing any validation
     ///
     /// # Safety
@@ -197,9 +436,23 @@ impl<'a, N: IndexableNum> KDTreeRef<'a, N> {
         let indices = metadata.indices_slice(data);
 
         Ok(Self {
+            data,
             coords,
             indices,
             metadata,
         })
     }
+
+    /// Verify this tree's checksum footer, detecting buffer corruption.
+    ///
+    /// A no-op returning `Ok(())` if this tree has no checksum footer, i.e. wasn't produced via
+    /// [`KDTree::to_checksummed`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the footer's xxh3 checksum doesn't match the rest of the buffer.
+    #[cfg(feature = "compression")]
+    pub fn verify_checksum(&self) -> Result<()> {
+        crate::kdtree::checksum::verify(self.data, &self.metadata)
+    }
 }
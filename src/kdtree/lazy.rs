@@ -0,0 +1,314 @@
+//! Paged access to a KDTree buffer too large to comfortably hold fully resident in memory.
+//!
+//! [`KDTreeRef::try_new`][crate::kdtree::KDTreeRef::try_new] and
+//! [`KDTreeMetadata::coords_slice`][crate::kdtree::KDTreeMetadata::coords_slice]/
+//! [`indices_slice`][crate::kdtree::KDTreeMetadata::indices_slice] assume the whole buffer is
+//! already a resident `&[u8]`, which forces a full read even for a query that only ever touches a
+//! handful of nodes. [`KDTreeSource`] abstracts "the bytes of a KDTree buffer" behind range reads
+//! instead, so [`KDTreeMetadata::from_source`][crate::kdtree::KDTreeMetadata::from_source] only
+//! has to touch the 8-byte header to parse an index's layout, and [`LazyKDTree`]'s search methods
+//! only page in the coordinate bytes of the nodes they actually visit, reusing the same
+//! byte-offset arithmetic [`KDTreeMetadata`] already computes for the fully-resident format.
+//!
+//! [`MmapKDTreeSource`] (behind the `mmap` feature) implements [`KDTreeSource`] over a
+//! memory-mapped file, so a multi-gigabyte index can be queried with a small resident footprint:
+//! the OS pages index data in as the search actually reads it, rather than the caller reading the
+//! whole file up front.
+
+use tinyvec::TinyVec;
+
+use crate::error::Result;
+use crate::kdtree::constants::KDBUSH_HEADER_SIZE;
+use crate::kdtree::index::KDTreeMetadata;
+use crate::r#type::IndexableNum;
+
+/// Squared Euclidean distance between a `D`-stride point and a `D`-dimensional query point.
+/// Duplicates [`crate::kdtree::r#trait::sq_dist_nd`], which is private to that module.
+#[inline]
+fn sq_dist_nd<N: IndexableNum, const D: usize>(point: &[N; D], query: [N; D]) -> N {
+    let mut total = N::zero();
+    for d in 0..D {
+        let diff = point[d] - query[d];
+        total = total + diff * diff;
+    }
+    total
+}
+
+/// A source of KDTree buffer bytes that need not be fully resident in memory.
+///
+/// Implemented for any in-memory buffer via the blanket `AsRef<[u8]>` impl (covering `Vec<u8>`
+/// and `&[u8]`), and for [`MmapKDTreeSource`] over a memory-mapped file.
+pub trait KDTreeSource {
+    /// Fetch the byte range `start..end` of the underlying buffer.
+    fn read_range(&self, start: usize, end: usize) -> Vec<u8>;
+
+    /// The total length, in bytes, of the underlying buffer.
+    fn len(&self) -> usize;
+
+    /// Whether the underlying buffer is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<B: AsRef<[u8]>> KDTreeSource for B {
+    fn read_range(&self, start: usize, end: usize) -> Vec<u8> {
+        self.as_ref()[start..end].to_vec()
+    }
+
+    fn len(&self) -> usize {
+        self.as_ref().len()
+    }
+}
+
+/// A [`KDTreeSource`] over a memory-mapped file, letting the OS page index data in on demand
+/// instead of reading the whole file up front.
+#[cfg(feature = "mmap")]
+pub struct MmapKDTreeSource(memmap2::Mmap);
+
+#[cfg(feature = "mmap")]
+impl MmapKDTreeSource {
+    /// Memory-map the file at `path` for use as a [`KDTreeSource`].
+    ///
+    /// # Safety
+    ///
+    /// Inherits the safety requirements of [`memmap2::Mmap::map`]: the mapped file must not be
+    /// concurrently modified or truncated by another process for the lifetime of the mapping.
+    pub unsafe fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Ok(Self(memmap2::Mmap::map(&file)?))
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl AsRef<[u8]> for MmapKDTreeSource {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A KDTree queried directly against a [`KDTreeSource`] rather than a fully-resident buffer.
+///
+/// Only [`KDTreeMetadata::from_source`] (the 8-byte header) is read eagerly; [`range_nd`][Self::range_nd]/
+/// [`within_nd`][Self::within_nd] page in just the coordinate bytes of the nodes the search
+/// actually visits, at the cost of one [`KDTreeSource::read_range`] call per node instead of a
+/// single upfront read of the whole buffer.
+pub struct LazyKDTree<N: IndexableNum, S: KDTreeSource, const D: usize = 2> {
+    source: S,
+    metadata: KDTreeMetadata<N, D>,
+}
+
+impl<N: IndexableNum, S: KDTreeSource, const D: usize> LazyKDTree<N, S, D> {
+    /// Construct a new [`LazyKDTree`], parsing only `source`'s 8-byte header up front.
+    pub fn try_new(source: S) -> Result<Self> {
+        let metadata = KDTreeMetadata::from_source(&source)?;
+        Ok(Self { source, metadata })
+    }
+
+    /// Access the underlying [`KDTreeMetadata`] of this instance.
+    pub fn metadata(&self) -> &KDTreeMetadata<N, D> {
+        &self.metadata
+    }
+
+    fn coords_byte_start(&self) -> usize {
+        KDBUSH_HEADER_SIZE + self.metadata.indices_byte_size + self.metadata.pad_coords_byte_size
+    }
+
+    /// Page in and decode the `D`-dimensional coordinate of the item at kd-sorted position `i`.
+    fn point(&self, i: usize) -> [N; D] {
+        let stride = D * N::BYTES_PER_ELEMENT;
+        let start = self.coords_byte_start() + i * stride;
+        let bytes = self.source.read_range(start, start + stride);
+
+        let mut out = [N::zero(); D];
+        for (d, slot) in out.iter_mut().enumerate() {
+            let elem_start = d * N::BYTES_PER_ELEMENT;
+            *slot = bytemuck::pod_read_unaligned(
+                &bytes[elem_start..elem_start + N::BYTES_PER_ELEMENT],
+            );
+        }
+        out
+    }
+
+    /// Page in and decode the original item id stored at kd-sorted position `i`.
+    fn item_id(&self, i: usize) -> u32 {
+        let width = self.metadata.indices_bytes_per_element;
+        let start = KDBUSH_HEADER_SIZE + i * width;
+        let bytes = self.source.read_range(start, start + width);
+        match width {
+            1 => bytes[0] as u32,
+            2 => u16::from_le_bytes(bytes.try_into().unwrap()) as u32,
+            _ => u32::from_le_bytes(bytes.try_into().unwrap()),
+        }
+    }
+
+    /// `D`-dimensional range search, generalizing [`Self::range`] beyond 2D.
+    ///
+    /// Mirrors the iterative recursion in
+    /// [`range_dyn`][crate::kdtree::r#trait::range_dyn]/[`within_dyn`][crate::kdtree::r#trait::within_dyn],
+    /// but fetches each visited node's coordinates via [`Self::point`] instead of indexing a
+    /// fully-resident slice.
+    pub fn range_nd(&self, min: [N; D], max: [N; D]) -> Vec<u32> {
+        let num_items = self.metadata.num_items() as usize;
+        if num_items == 0 {
+            return vec![];
+        }
+
+        let node_size = self.metadata.node_size() as usize;
+        let mut stack: TinyVec<[usize; 33]> = TinyVec::new();
+        stack.push(0);
+        stack.push(num_items - 1);
+        stack.push(0);
+
+        let mut result = vec![];
+        while !stack.is_empty() {
+            let axis = stack.pop().unwrap();
+            let right = stack.pop().unwrap();
+            let left = stack.pop().unwrap();
+
+            if right - left <= node_size {
+                for i in left..=right {
+                    let point = self.point(i);
+                    if (0..D).all(|d| point[d] >= min[d] && point[d] <= max[d]) {
+                        result.push(self.item_id(i));
+                    }
+                }
+                continue;
+            }
+
+            let m = (left + right) >> 1;
+            let point = self.point(m);
+            if (0..D).all(|d| point[d] >= min[d] && point[d] <= max[d]) {
+                result.push(self.item_id(m));
+            }
+
+            let split = point[axis];
+            let next_axis = (axis + 1) % D;
+
+            if min[axis] <= split {
+                stack.push(left);
+                stack.push(m - 1);
+                stack.push(next_axis);
+            }
+            if max[axis] >= split {
+                stack.push(m + 1);
+                stack.push(right);
+                stack.push(next_axis);
+            }
+        }
+
+        result
+    }
+
+    /// `D`-dimensional radius search, generalizing [`Self::within`] beyond 2D.
+    pub fn within_nd(&self, query: [N; D], r: N) -> Vec<u32> {
+        let num_items = self.metadata.num_items() as usize;
+        if num_items == 0 {
+            return vec![];
+        }
+
+        let node_size = self.metadata.node_size() as usize;
+        let r2 = r * r;
+        let mut stack: TinyVec<[usize; 33]> = TinyVec::new();
+        stack.push(0);
+        stack.push(num_items - 1);
+        stack.push(0);
+
+        let mut result = vec![];
+        while !stack.is_empty() {
+            let axis = stack.pop().unwrap();
+            let right = stack.pop().unwrap();
+            let left = stack.pop().unwrap();
+
+            if right - left <= node_size {
+                for i in left..=right {
+                    let point = self.point(i);
+                    if sq_dist_nd(&point, query) <= r2 {
+                        result.push(self.item_id(i));
+                    }
+                }
+                continue;
+            }
+
+            let m = (left + right) >> 1;
+            let point = self.point(m);
+            if sq_dist_nd(&point, query) <= r2 {
+                result.push(self.item_id(m));
+            }
+
+            let split = point[axis];
+            let next_axis = (axis + 1) % D;
+
+            if query[axis] - r <= split {
+                stack.push(left);
+                stack.push(m - 1);
+                stack.push(next_axis);
+            }
+            if query[axis] + r >= split {
+                stack.push(m + 1);
+                stack.push(right);
+                stack.push(next_axis);
+            }
+        }
+
+        result
+    }
+}
+
+impl<N: IndexableNum, S: KDTreeSource> LazyKDTree<N, S, 2> {
+    /// Find all items within the given bounding box, paging in only the nodes the search visits.
+    pub fn range(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Vec<u32> {
+        self.range_nd([min_x, min_y], [max_x, max_y])
+    }
+
+    /// Find all items within `r` of `(x, y)`, paging in only the nodes the search visits.
+    pub fn within(&self, x: N, y: N, r: N) -> Vec<u32> {
+        self.within_nd([x, y], r)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kdtree::KDTreeBuilder;
+
+    #[test]
+    fn range_search_matches_in_memory_search() {
+        use crate::kdtree::KDTreeIndex;
+
+        let mut builder = KDTreeBuilder::<f64>::new(50);
+        for i in 0..50 {
+            builder.add(i as f64, (i * 2) as f64);
+        }
+        let tree = builder.finish();
+
+        let lazy = LazyKDTree::<f64, _>::try_new(tree.as_ref().to_vec()).unwrap();
+        let mut lazy_result = lazy.range(10., 10., 30., 40.);
+        lazy_result.sort_unstable();
+
+        let mut eager_result = tree.range(10., 10., 30., 40.);
+        eager_result.sort_unstable();
+
+        assert_eq!(lazy_result, eager_result);
+    }
+
+    #[test]
+    fn within_search_matches_in_memory_search() {
+        use crate::kdtree::KDTreeIndex;
+
+        let mut builder = KDTreeBuilder::<f64>::new(50);
+        for i in 0..50 {
+            builder.add(i as f64, (i * 2) as f64);
+        }
+        let tree = builder.finish();
+
+        let lazy = LazyKDTree::<f64, _>::try_new(tree.as_ref().to_vec()).unwrap();
+        let mut lazy_result = lazy.within(15., 30., 12.);
+        lazy_result.sort_unstable();
+
+        let mut eager_result = tree.within(15., 30., 12.);
+        eager_result.sort_unstable();
+
+        assert_eq!(lazy_result, eager_result);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::r#type::IndexableNum;
+
+/// A pluggable distance metric for [`KDTreeIndex::nearest_neighbors`][crate::kdtree::KDTreeIndex::nearest_neighbors].
+///
+/// Implement this to plug in Manhattan distance, haversine distance over lon/lat, or any other
+/// metric beyond the built-in [`EuclideanMetric`]. The search only relies on two properties to
+/// stay correct: the true distance between two points, and a lower bound on the distance from a
+/// point to anything on the far side of an axis-aligned splitting plane.
+pub trait Metric<N: IndexableNum> {
+    /// The distance between point `a` and point `b`, given as raw per-axis coordinate slices.
+    fn distance(&self, a: &[N], b: &[N]) -> f64;
+
+    /// A lower bound on the distance from `point` to any point whose `axis` coordinate is on
+    /// the other side of `split_value`.
+    ///
+    /// This bound is what lets `nearest_neighbors` prune a subtree instead of descending into
+    /// it: if `axis_distance` is already no smaller than the current k-th best distance, nothing
+    /// beyond the splitting plane can improve on it. A bound that's too large (overestimates the
+    /// true distance) can cause real neighbors to be missed; a bound that's too small only costs
+    /// extra, otherwise-safe traversal.
+    fn axis_distance(&self, point: &[N], axis: usize, split_value: N) -> f64;
+}
+
+/// The ordinary straight-line (Euclidean / L2) distance metric.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EuclideanMetric;
+
+impl<N: IndexableNum> Metric<N> for EuclideanMetric {
+    fn distance(&self, a: &[N], b: &[N]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(&x, &y)| {
+                let d = x.to_f64().unwrap_or(0.0) - y.to_f64().unwrap_or(0.0);
+                d * d
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn axis_distance(&self, point: &[N], axis: usize, split_value: N) -> f64 {
+        (point[axis].to_f64().unwrap_or(0.0) - split_value.to_f64().unwrap_or(0.0)).abs()
+    }
+}
+
+/// The taxicab (Manhattan / L1) distance metric.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManhattanMetric;
+
+impl<N: IndexableNum> Metric<N> for ManhattanMetric {
+    fn distance(&self, a: &[N], b: &[N]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(&x, &y)| (x.to_f64().unwrap_or(0.0) - y.to_f64().unwrap_or(0.0)).abs())
+            .sum()
+    }
+
+    fn axis_distance(&self, point: &[N], axis: usize, split_value: N) -> f64 {
+        // Manhattan distance is separable across axes, so the bound is exact, not just a lower
+        // bound: moving only along `axis` to reach the splitting plane costs exactly this much.
+        (point[axis].to_f64().unwrap_or(0.0) - split_value.to_f64().unwrap_or(0.0)).abs()
+    }
+}
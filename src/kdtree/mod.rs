@@ -7,7 +7,27 @@
 //! ## Search
 //!
 //! Use [`KDTreeIndex::range`] to search a KDTree given a bounding box query. Use
-//! [`KDTreeIndex::within`] to search a KDTree given a point and radius.
+//! [`KDTreeIndex::within`] to search a KDTree given a point and radius. Use
+//! [`KDTreeIndex::neighbors`] to find the `k` nearest neighbors to a point by ordinary Euclidean
+//! distance, or [`KDTreeIndex::nearest_neighbors`] for the same search under a pluggable
+//! [`Metric`], such as [`ManhattanMetric`]. For geographic lon/lat points,
+//! [`KDTreeIndex::within_metric`] and [`KDTreeIndex::neighbors_metric`] accept the same
+//! [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric] implementations used by the
+//! RTree, such as `HaversineDistance`. For trees built with `D != 2` (see below),
+//! [`KDTreeIndex::range_nd`] and [`KDTreeIndex::within_nd`] generalize `range`/`within` to a
+//! `D`-dimensional box and ball, respectively.
+//!
+//! ## Dimensions
+//!
+//! [`KDTree`]/[`KDTreeBuilder`] are generic over a const `D`, the number of coordinates per
+//! point, which defaults to `2`. Use e.g. [`KDTreeBuilder::<f64, Vec<u8>, 3>::new`] to build a
+//! 3D tree over `(x, y, z)` triples, lon/lat/time, or higher-dimensional feature vectors.
+//!
+//! ## Dynamic insertion
+//!
+//! [`KDTreeBuilder`]/[`KDTree`] are bulk-built and immutable. If you need to insert points
+//! incrementally without paying a full rebuild on every insert, use [`DynamicKDTree`] instead,
+//! which wraps the same builder/tree pair in an amortized, insert-capable forest.
 //!
 //! ## Persisting
 //!
@@ -51,12 +71,25 @@
 
 mod builder;
 pub(crate) mod constants;
+#[cfg(feature = "compression")]
+mod checksum;
+#[cfg(feature = "compression")]
+mod compression;
+mod delta_varint;
+mod dynamic;
 mod index;
+pub mod lazy;
+mod metric;
 mod r#trait;
 
 pub use builder::{KDTreeBuilder, DEFAULT_KDTREE_NODE_SIZE};
-pub use index::{KDTreeMetadata, KDTreeRef, KDTree};
-pub use r#trait::KDTreeIndex;
+pub use dynamic::DynamicKDTree;
+pub use index::{KDTree, KDTreeMetadata, KDTreeRef, OwnedKDTree};
+#[cfg(feature = "mmap")]
+pub use lazy::MmapKDTreeSource;
+pub use lazy::{KDTreeSource, LazyKDTree};
+pub use metric::{EuclideanMetric, ManhattanMetric, Metric};
+pub use r#trait::{KDTreeIndex, KnnContainer, SearchParameters};
 
 #[cfg(test)]
 mod test;
@@ -1,6 +1,9 @@
 use crate::indices::Indices;
 use crate::kdtree::r#trait::sq_dist;
-use crate::kdtree::{KDTreeBuilder, KDTreeIndex, OwnedKDTree};
+use crate::kdtree::{
+    EuclideanMetric, KDTreeBuilder, KDTreeIndex, KDTreeMetadata, OwnedKDTree,
+    DEFAULT_KDTREE_NODE_SIZE,
+};
 
 fn points() -> Vec<(f64, f64)> {
     let coords: Vec<[i32; 2]> = vec![
@@ -236,6 +239,35 @@ fn radius_search() {
     // outside points not in range
 }
 
+#[test]
+fn nearest_neighbors_matches_brute_force() {
+    let owned_index = make_index();
+    let kdbush = owned_index.as_ref();
+
+    let [qx, qy] = [50., 50.];
+    let k = 5;
+
+    let result = kdbush.nearest_neighbors(&[qx, qy], k, &EuclideanMetric);
+    assert_eq!(result.len(), k);
+
+    let points = points();
+    let mut brute_force: Vec<(f64, usize)> = points
+        .iter()
+        .enumerate()
+        .map(|(id, &(x, y))| (sq_dist(x, y, qx, qy), id))
+        .collect();
+    brute_force.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let expected_ids: Vec<u32> = brute_force[..k]
+        .iter()
+        .map(|&(_, id)| id as u32)
+        .collect();
+
+    assert_eq!(
+        result, expected_ids,
+        "returns the k closest ids in ascending distance order"
+    );
+}
+
 // Even with should_panic, it still fails on the assertion
 // #[test]
 // #[should_panic]
@@ -243,3 +275,91 @@ fn radius_search() {
 //     let builder = KdbushBuilder::new(5);
 //     builder.finish();
 // }
+
+#[test]
+fn builds_a_3d_index() {
+    let points: Vec<[f64; 3]> = vec![
+        [54., 1., 10.],
+        [97., 21., 3.],
+        [65., 35., 7.],
+        [33., 54., 1.],
+        [95., 39., 8.],
+    ];
+
+    let mut builder = KDTreeBuilder::<f64, Vec<u8>, 3>::new(points.len() as u32);
+    for point in &points {
+        builder.add_n(*point);
+    }
+    let tree = builder.finish();
+
+    assert_eq!(tree.metadata().num_items(), points.len() as u32);
+    assert_eq!(
+        tree.as_ref().len(),
+        tree.metadata().data_buffer_length()
+    );
+}
+
+#[test]
+fn range_and_within_nd_search_a_3d_index() {
+    let points: Vec<[f64; 3]> = vec![
+        [0., 0., 0.],
+        [10., 10., 10.],
+        [1., 1., 1.],
+        [1., 0., 0.],
+        [20., 20., 20.],
+    ];
+
+    let mut builder = KDTreeBuilder::<f64, Vec<u8>, 3>::new(points.len() as u32);
+    for point in &points {
+        builder.add_n(*point);
+    }
+    let tree = builder.finish();
+
+    let mut in_box = tree.range_nd([0., 0., 0.], [1., 1., 1.]);
+    in_box.sort_unstable();
+    assert_eq!(in_box, vec![0, 2, 3]);
+
+    let mut in_radius = tree.within_nd([0., 0., 0.], 2.);
+    in_radius.sort_unstable();
+    assert_eq!(in_radius, vec![0, 2, 3]);
+}
+
+#[test]
+fn builds_into_caller_provided_buffer() {
+    let metadata = KDTreeMetadata::<f64>::new(5, DEFAULT_KDTREE_NODE_SIZE);
+    let mut buffer = vec![0u8; metadata.data_buffer_length()];
+    let mut builder =
+        KDTreeBuilder::<f64, _>::new_in(5, DEFAULT_KDTREE_NODE_SIZE, buffer.as_mut_slice());
+    for (x, y) in points().into_iter().take(5) {
+        builder.add(x, y);
+    }
+    let tree = builder.finish();
+
+    assert_eq!(tree.metadata().num_items(), 5);
+    let result = tree.range(0., 0., 100., 100.);
+    assert_eq!(result.len(), 5);
+}
+
+#[test]
+fn validate_passes_on_an_index_built_normally() {
+    let tree = make_index();
+    assert!(tree.validate().is_ok());
+}
+
+#[test]
+fn validate_catches_a_broken_median_partition() {
+    use crate::error::GeoIndexError;
+    use crate::kdtree::constants::KDBUSH_HEADER_SIZE;
+
+    let mut tree = make_index();
+    let metadata = *tree.metadata();
+    let coords_byte_start =
+        KDBUSH_HEADER_SIZE + metadata.indices_byte_size + metadata.pad_coords_byte_size;
+
+    // Overwrite the first item's x coordinate with something wildly out of range, breaking
+    // whichever median partition it falls under.
+    tree.buffer[coords_byte_start..coords_byte_start + 8].copy_from_slice(&1e9f64.to_le_bytes());
+
+    let err = tree.validate().unwrap_err();
+    assert!(matches!(err, GeoIndexError::Invalid { .. }));
+}
@@ -1,12 +1,25 @@
+use std::collections::BinaryHeap;
+
 use geo_traits::{CoordTrait, RectTrait};
 use tinyvec::TinyVec;
 
+use crate::error::{GeoIndexError, Result};
 use crate::indices::Indices;
+use crate::kdtree::metric::{EuclideanMetric, Metric};
 use crate::kdtree::{KDTree, KDTreeMetadata, KDTreeRef, Node};
 use crate::r#type::IndexableNum;
+use crate::rtree::SimpleDistanceMetric;
 
 /// A trait for searching and accessing data out of a KDTree.
-pub trait KDTreeIndex<N: IndexableNum>: Sized {
+///
+/// Generic over the number of dimensions `D` of the indexed points, matching
+/// [`KDTreeMetadata`]/[`KDTreeBuilder`][crate::kdtree::KDTreeBuilder]. [`range`][Self::range]/
+/// [`within`][Self::within]/[`range_rect`][Self::range_rect]/[`within_coord`][Self::within_coord]
+/// keep their original scalar, 2D-only signatures as thin wrappers over
+/// [`range_nd`][Self::range_nd]/[`within_nd`][Self::within_nd], which generalize to arbitrary
+/// `D`; the pluggable-metric search methods below (`within_metric`, `neighbors*`,
+/// `nearest_neighbors*`) are still 2D-only.
+pub trait KDTreeIndex<N: IndexableNum, const D: usize = 2>: Sized {
     /// The underlying raw coordinate buffer of this tree
     fn coords(&self) -> &[N];
 
@@ -14,7 +27,7 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
     fn indices(&self) -> Indices<'_>;
 
     /// Access the metadata describing this KDTree
-    fn metadata(&self) -> &KDTreeMetadata<N>;
+    fn metadata(&self) -> &KDTreeMetadata<N, D>;
 
     /// The number of items in this KDTree
     fn num_items(&self) -> u32 {
@@ -26,6 +39,23 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
         self.metadata().node_size()
     }
 
+    /// Search the index for items within a given `D`-dimensional axis-aligned box.
+    ///
+    /// Generalizes [`range`][Self::range] to arbitrary dimension: the splitting axis cycles
+    /// through all `D` dimensions in turn (`axis = (axis + 1) % D`) instead of alternating
+    /// between exactly two, and the box test checks every axis rather than just x/y.
+    ///
+    /// Returns indices of found items.
+    fn range_nd(&self, min: [N; D], max: [N; D]) -> Vec<u32> {
+        range_dyn(
+            self.coords(),
+            &self.indices(),
+            self.node_size() as usize,
+            min,
+            max,
+        )
+    }
+
     /// Search the index for items within a given bounding box.
     ///
     /// - min_x: bbox
@@ -35,6 +65,104 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
     ///
     /// Returns indices of found items
     fn range(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Vec<u32> {
+        assert_eq!(
+            D, 2,
+            "range(min_x, min_y, max_x, max_y) only supports 2D trees; use range_nd for D != 2"
+        );
+        range_dyn(
+            self.coords(),
+            &self.indices(),
+            self.node_size() as usize,
+            [min_x, min_y],
+            [max_x, max_y],
+        )
+    }
+
+    /// Search the index for items within a given bounding box.
+    ///
+    /// Returns indices of found items
+    fn range_rect(&self, rect: &impl RectTrait<T = N>) -> Vec<u32> {
+        self.range(
+            rect.min().x(),
+            rect.min().y(),
+            rect.max().x(),
+            rect.max().y(),
+        )
+    }
+
+    /// Search the index for items within a given radius of a `D`-dimensional query point.
+    ///
+    /// Generalizes [`within`][Self::within] to arbitrary dimension, using squared Euclidean
+    /// distance summed over all `D` components.
+    ///
+    /// Returns indices of found items.
+    fn within_nd(&self, query: [N; D], r: N) -> Vec<u32> {
+        within_dyn(
+            self.coords(),
+            &self.indices(),
+            self.node_size() as usize,
+            query,
+            r,
+        )
+    }
+
+    /// Search the index for items within a given radius.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - r: radius
+    ///
+    /// Returns indices of found items
+    fn within(&self, qx: N, qy: N, r: N) -> Vec<u32> {
+        assert_eq!(
+            D, 2,
+            "within(qx, qy, r) only supports 2D trees; use within_nd for D != 2"
+        );
+        within_dyn(
+            self.coords(),
+            &self.indices(),
+            self.node_size() as usize,
+            [qx, qy],
+            r,
+        )
+    }
+
+    /// Search the index for items within a given radius.
+    ///
+    /// - coord: coordinate of query point
+    /// - r: radius
+    ///
+    /// Returns indices of found items
+    fn within_coord(&self, coord: &impl CoordTrait<T = N>, r: N) -> Vec<u32> {
+        self.within(coord.x(), coord.y(), r)
+    }
+
+    /// Search the index for items within a given radius under a pluggable
+    /// [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric], such as
+    /// [`HaversineDistance`][crate::rtree::HaversineDistance] or
+    /// [`SpheroidDistance`][crate::rtree::SpheroidDistance].
+    ///
+    /// [`within`][Self::within] hardcodes squared Euclidean distance, which is the wrong notion
+    /// of distance for longitude/latitude points where "within N meters" needs a geodesic
+    /// metric. The kd descent still prunes correctly for a non-Euclidean metric: rather than
+    /// comparing `qx`/`qy` against the splitting coordinate directly, each subtree is only
+    /// skipped once `metric`'s distance from the query to the splitting plane itself already
+    /// exceeds `r` (the plane is the nearest any point past it can be, so this bound is always
+    /// safe to prune on). `metric.distance`'s output type `N` is compared to `r` directly, so
+    /// this works for any metric that reports its distance in the same units as `r`.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - r: radius
+    ///
+    /// Returns indices of found items
+    fn within_metric<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        qx: N,
+        qy: N,
+        r: N,
+        metric: &M,
+    ) -> Vec<u32> {
         let indices = self.indices();
         let coords = self.coords();
         let node_size = self.node_size();
@@ -47,7 +175,7 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
 
         let mut result: Vec<u32> = vec![];
 
-        // recursively search for items in range in the kd-sorted arrays
+        // recursively search for items within radius in the kd-sorted arrays
         while !stack.is_empty() {
             let axis = stack.pop().unwrap_or(0);
             let right = stack.pop().unwrap_or(0);
@@ -56,9 +184,7 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
             // if we reached "tree node", search linearly
             if right - left <= node_size as usize {
                 for i in left..right + 1 {
-                    let x = coords[2 * i];
-                    let y = coords[2 * i + 1];
-                    if x >= min_x && x <= max_x && y >= min_y && y <= max_y {
+                    if metric.distance(coords[2 * i], coords[2 * i + 1], qx, qy) <= r {
                         result.push(indices.get(i).try_into().unwrap());
                     }
                 }
@@ -71,51 +197,61 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
             // include the middle item if it's in range
             let x = coords[2 * m];
             let y = coords[2 * m + 1];
-            if x >= min_x && x <= max_x && y >= min_y && y <= max_y {
+            if metric.distance(x, y, qx, qy) <= r {
                 result.push(indices.get(m).try_into().unwrap());
             }
 
-            // queue search in halves that intersect the query
-            let lte = if axis == 0 { min_x <= x } else { min_y <= y };
-            if lte {
-                // Note: these are pushed in backwards order to what gets popped
+            let split = if axis == 0 { x } else { y };
+            let query_on_left = if axis == 0 { qx <= split } else { qy <= split };
+
+            // the half containing the query always needs to be searched
+            if query_on_left {
                 stack.push(left);
                 stack.push(m - 1);
                 stack.push(1 - axis);
-            }
-
-            let gte = if axis == 0 { max_x >= x } else { max_y >= y };
-            if gte {
-                // Note: these are pushed in backwards order to what gets popped
+            } else {
                 stack.push(m + 1);
                 stack.push(right);
                 stack.push(1 - axis);
             }
-        }
 
+            // the far half is only reachable if the splitting plane itself is within `r`
+            if axis_plane_distance(metric, qx, qy, axis, split) <= r {
+                if query_on_left {
+                    stack.push(m + 1);
+                    stack.push(right);
+                    stack.push(1 - axis);
+                } else {
+                    stack.push(left);
+                    stack.push(m - 1);
+                    stack.push(1 - axis);
+                }
+            }
+        }
         result
     }
 
-    /// Search the index for items within a given bounding box.
+    /// Search the index for items within a given radius, trading a bounded accuracy loss for
+    /// speed.
     ///
-    /// Returns indices of found items
-    fn range_rect(&self, rect: &impl RectTrait<T = N>) -> Vec<u32> {
-        self.range(
-            rect.min().x(),
-            rect.min().y(),
-            rect.max().x(),
-            rect.max().y(),
-        )
-    }
-
-    /// Search the index for items within a given radius.
+    /// This relaxes the splitting-plane test that [`within`][Self::within] uses to decide
+    /// whether to descend into the far side of a subtree: a far subtree is skipped once its
+    /// splitting-plane distance alone already exceeds `r / (1+epsilon)`, rather than `r`. Larger
+    /// `epsilon` prunes more subtrees and runs faster, but may omit items whose true distance lies
+    /// between `r / (1+epsilon)` and `r`. With `epsilon` of zero this is equivalent to `within`.
     ///
     /// - qx: x value of query point
     /// - qy: y value of query point
     /// - r: radius
+    /// - epsilon: approximation factor; must be non-negative
     ///
     /// Returns indices of found items
-    fn within(&self, qx: N, qy: N, r: N) -> Vec<u32> {
+    fn within_approx(&self, qx: N, qy: N, r: N, epsilon: N) -> Vec<u32> {
+        assert!(
+            epsilon >= N::zero(),
+            "epsilon must be non-negative, got {epsilon:?}"
+        );
+
         let indices = self.indices();
         let coords = self.coords();
         let node_size = self.node_size();
@@ -128,6 +264,7 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
 
         let mut result: Vec<u32> = vec![];
         let r2 = r * r;
+        let prune_r = r / (N::one() + epsilon);
 
         // recursively search for items within radius in the kd-sorted arrays
         while !stack.is_empty() {
@@ -155,15 +292,23 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
                 result.push(indices.get(m).try_into().unwrap());
             }
 
-            // queue search in halves that intersect the query
-            let lte = if axis == 0 { qx - r <= x } else { qy - r <= y };
+            // queue search in halves that intersect the relaxed radius
+            let lte = if axis == 0 {
+                qx - prune_r <= x
+            } else {
+                qy - prune_r <= y
+            };
             if lte {
                 stack.push(left);
                 stack.push(m - 1);
                 stack.push(1 - axis);
             }
 
-            let gte = if axis == 0 { qx + r >= x } else { qy + r >= y };
+            let gte = if axis == 0 {
+                qx + prune_r >= x
+            } else {
+                qy + prune_r >= y
+            };
             if gte {
                 stack.push(m + 1);
                 stack.push(right);
@@ -173,32 +318,574 @@ pub trait KDTreeIndex<N: IndexableNum>: Sized {
         result
     }
 
-    /// Search the index for items within a given radius.
+    /// Find the `k` nearest neighbors to `query` under the given [`Metric`], in ascending order
+    /// of distance.
+    ///
+    /// Unlike [`range`][Self::range]/[`within`][Self::within], which only ever need to compare
+    /// against an axis-aligned box or a radius, this takes a pluggable [`Metric`] so that
+    /// callers can search under something other than straight-line distance (see
+    /// [`EuclideanMetric`][crate::kdtree::EuclideanMetric] and
+    /// [`ManhattanMetric`][crate::kdtree::ManhattanMetric]).
+    ///
+    /// ```
+    /// use geo_index::kdtree::{EuclideanMetric, KDTreeBuilder, KDTreeIndex};
+    ///
+    /// let mut builder = KDTreeBuilder::<f64>::new(3);
+    /// builder.add(0., 0.);
+    /// builder.add(5., 5.);
+    /// builder.add(1., 1.);
+    /// let tree = builder.finish();
+    ///
+    /// assert_eq!(tree.nearest_neighbors(&[0., 0.], 2, &EuclideanMetric), vec![0, 2]);
+    /// ```
+    fn nearest_neighbors<M: Metric<N> + ?Sized>(
+        &self,
+        query: &[N],
+        k: usize,
+        metric: &M,
+    ) -> Vec<u32> {
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let node_size = self.node_size() as usize;
+
+        let mut best: BinaryHeap<KnnCandidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn(
+            coords,
+            &indices,
+            node_size,
+            0,
+            indices.len() - 1,
+            0,
+            query,
+            k,
+            metric,
+            1.0,
+            &mut best,
+        );
+
+        best.into_sorted_vec().into_iter().map(|c| c.id).collect()
+    }
+
+    /// Find the `k` nearest neighbors to `query` under the given [`Metric`], trading a bounded
+    /// accuracy loss for speed.
+    ///
+    /// This relaxes the splitting-plane test that [`nearest_neighbors`][Self::nearest_neighbors]
+    /// uses to decide whether to descend into the far side of a subtree: the far subtree is
+    /// skipped once `(1+epsilon)` times its splitting-plane distance already exceeds the current
+    /// k-th best distance, rather than the plane distance alone. Larger `epsilon` prunes more
+    /// subtrees and runs faster, but every returned distance is only guaranteed to be within a
+    /// factor of `(1+epsilon)` of the true k-th nearest distance. With `epsilon` of zero this is
+    /// equivalent to `nearest_neighbors`.
+    fn nearest_neighbors_approx<M: Metric<N> + ?Sized>(
+        &self,
+        query: &[N],
+        k: usize,
+        epsilon: N,
+        metric: &M,
+    ) -> Vec<u32> {
+        assert!(
+            epsilon >= N::zero(),
+            "epsilon must be non-negative, got {epsilon:?}"
+        );
+
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let node_size = self.node_size() as usize;
+        let epsilon_factor = 1.0 + epsilon.to_f64().unwrap_or(0.0);
+
+        let mut best: BinaryHeap<KnnCandidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn(
+            coords,
+            &indices,
+            node_size,
+            0,
+            indices.len() - 1,
+            0,
+            query,
+            k,
+            metric,
+            epsilon_factor,
+            &mut best,
+        );
+
+        best.into_sorted_vec().into_iter().map(|c| c.id).collect()
+    }
+
+    /// Find the `k` nearest neighbors to a query point, in ascending order of distance.
+    ///
+    /// This is a convenience wrapper around
+    /// [`nearest_neighbors`][Self::nearest_neighbors] using the ordinary
+    /// [`EuclideanMetric`][crate::kdtree::EuclideanMetric]. Reach for `nearest_neighbors`
+    /// directly if you need a different metric.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    ///
+    /// Returns indices of found items, ordered by ascending distance. If `k` is greater than or
+    /// equal to the number of items in the tree, all items are returned.
+    fn neighbors(&self, qx: N, qy: N, k: usize) -> Vec<u32> {
+        self.nearest_neighbors(&[qx, qy], k, &EuclideanMetric)
+    }
+
+    /// Find the `k` nearest neighbors to a query point, in ascending order of distance.
     ///
     /// - coord: coordinate of query point
+    /// - k: number of neighbors to find
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn neighbors_coord(&self, coord: &impl CoordTrait<T = N>, k: usize) -> Vec<u32> {
+        self.neighbors(coord.x(), coord.y(), k)
+    }
+
+    /// Find the `k` nearest items to a query point, in ascending order of distance.
+    ///
+    /// Alias for [`neighbors`][Self::neighbors], matching the `nearest` naming more commonly used
+    /// by other spatial index APIs for exact k-NN queries.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    ///
+    /// Returns indices of found items, ordered by ascending distance. If `k` is greater than or
+    /// equal to the number of items in the tree, all items are returned.
+    fn nearest(&self, qx: N, qy: N, k: usize) -> Vec<u32> {
+        self.neighbors(qx, qy, k)
+    }
+
+    /// Find the `k` nearest neighbors to a query point, exposing the choice of candidate
+    /// container and, optionally, how many leaf points were distance-tested.
+    ///
+    /// This is the same best-first Euclidean search as [`nearest`][Self::nearest], but tunable
+    /// for benchmarking [`node_size`][Self::node_size] and container choices against your own
+    /// data: `container` picks between [`KnnContainer::Heap`] (better asymptotics, the right
+    /// choice for large `k`) and [`KnnContainer::Linear`] (lower constant overhead, often faster
+    /// for small `k`). If `touch_count` is `Some`, it is incremented once per leaf point that was
+    /// distance-tested during the search; pass `&mut None` to skip that bookkeeping entirely.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - container: candidate container to use while narrowing down the `k` best
+    /// - touch_count: if `Some`, incremented once per leaf point tested against
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn knn_advanced(
+        &self,
+        qx: N,
+        qy: N,
+        k: usize,
+        container: KnnContainer,
+        touch_count: &mut Option<usize>,
+    ) -> Vec<u32> {
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let node_size = self.node_size() as usize;
+
+        let mut candidates = CandidateSet::new(container, k);
+        search_knn_advanced(
+            coords,
+            &indices,
+            node_size,
+            0,
+            indices.len() - 1,
+            0,
+            qx,
+            qy,
+            k,
+            1.0,
+            None,
+            &mut candidates,
+            touch_count,
+        );
+
+        candidates.into_sorted_ids()
+    }
+
+    /// Find the `k` nearest neighbors to a query point, combining every knob `knn_advanced`
+    /// exposes with [`SearchParameters`]' `epsilon`-approximate pruning, `max_radius` cutoff, and
+    /// `sort_results` toggle.
+    ///
+    /// This runs the same descent as [`knn_advanced`][Self::knn_advanced] — `container` still
+    /// picks [`KnnContainer::Heap`] or [`KnnContainer::Linear`], and `touch_count`, if `Some`, is
+    /// still incremented once per point tested — but the far-subtree prune is relaxed by
+    /// `(1+params.epsilon)` as in [`nearest_neighbors_approx`][Self::nearest_neighbors_approx],
+    /// candidates beyond `params.max_radius` (if set) are discarded outright, and
+    /// `params.sort_results` controls whether the result comes back sorted by ascending distance
+    /// or in whatever order the container produced it, skipping the sort entirely when the caller
+    /// doesn't need it.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - container: candidate container to use while narrowing down the `k` best
+    /// - params: approximation, radius, and ordering knobs
+    /// - touch_count: if `Some`, incremented once per leaf point tested against
+    ///
+    /// Returns indices of found items.
+    fn nearest_advanced(
+        &self,
+        qx: N,
+        qy: N,
+        k: usize,
+        container: KnnContainer,
+        params: &SearchParameters<N>,
+        touch_count: &mut Option<usize>,
+    ) -> Vec<u32> {
+        assert!(
+            params.epsilon >= N::zero(),
+            "epsilon must be non-negative, got {:?}",
+            params.epsilon
+        );
+
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let node_size = self.node_size() as usize;
+        let epsilon_factor = 1.0 + params.epsilon.to_f64().unwrap_or(0.0);
+        let max_radius_sq = params.max_radius.map(|r| {
+            let r = r.to_f64().unwrap_or(f64::MAX);
+            r * r
+        });
+
+        let mut candidates = CandidateSet::new(container, k);
+        search_knn_advanced(
+            coords,
+            &indices,
+            node_size,
+            0,
+            indices.len() - 1,
+            0,
+            qx,
+            qy,
+            k,
+            epsilon_factor,
+            max_radius_sq,
+            &mut candidates,
+            touch_count,
+        );
+
+        if params.sort_results {
+            candidates.into_sorted_ids()
+        } else {
+            candidates.into_ids()
+        }
+    }
+
+    /// Find the `k` nearest neighbors to a query point, trading a bounded accuracy loss for
+    /// speed.
+    ///
+    /// This is a convenience wrapper around
+    /// [`nearest_neighbors_approx`][Self::nearest_neighbors_approx] using the ordinary
+    /// [`EuclideanMetric`][crate::kdtree::EuclideanMetric]. See that method for what `epsilon`
+    /// controls.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - epsilon: approximation factor; must be non-negative
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn neighbors_approx(&self, qx: N, qy: N, k: usize, epsilon: N) -> Vec<u32> {
+        self.nearest_neighbors_approx(&[qx, qy], k, epsilon, &EuclideanMetric)
+    }
+
+    /// Find the `k` nearest items to a query point, trading a bounded accuracy loss for speed.
+    ///
+    /// Alias for [`neighbors_approx`][Self::neighbors_approx], matching the `nearest`/
+    /// `nearest_metric` naming. Every returned neighbor is within `(1 + epsilon)` times the true
+    /// distance of the exact `k`-th nearest neighbor; see [`neighbors_approx`][Self::neighbors_approx]
+    /// for how `epsilon` prunes the descent.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - epsilon: approximation factor; must be non-negative
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn nearest_approx(&self, qx: N, qy: N, k: usize, epsilon: N) -> Vec<u32> {
+        self.neighbors_approx(qx, qy, k, epsilon)
+    }
+
+    /// Find the `k` nearest neighbors to a query point under a pluggable
+    /// [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric], such as
+    /// [`HaversineDistance`][crate::rtree::HaversineDistance] or
+    /// [`SpheroidDistance`][crate::rtree::SpheroidDistance].
+    ///
+    /// This is the same best-first traversal as [`neighbors`][Self::neighbors], except the
+    /// splitting-plane pruning bound is computed via `metric`'s `distance_to_bbox` (against a
+    /// degenerate bbox collapsed onto the splitting plane) instead of assuming Euclidean
+    /// geometry, so geographic metrics prune correctly too.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn neighbors_metric<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        qx: N,
+        qy: N,
+        k: usize,
+        metric: &M,
+    ) -> Vec<u32> {
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let node_size = self.node_size() as usize;
+
+        let mut best: BinaryHeap<KnnCandidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn_simple(
+            coords,
+            &indices,
+            node_size,
+            0,
+            indices.len() - 1,
+            0,
+            qx,
+            qy,
+            k,
+            metric,
+            &mut best,
+        );
+
+        best.into_sorted_vec().into_iter().map(|c| c.id).collect()
+    }
+
+    /// Find the `k` nearest items to a query point under a pluggable
+    /// [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric], such as
+    /// [`HaversineDistance`][crate::rtree::HaversineDistance] or
+    /// [`SpheroidDistance`][crate::rtree::SpheroidDistance].
+    ///
+    /// Alias for [`neighbors_metric`][Self::neighbors_metric], matching the `nearest_metric`
+    /// naming used alongside [`nearest`][Self::nearest] and [`within_metric`][Self::within_metric]
+    /// so the planar API (`neighbors`/`within`) stays unbroken while metric-aware callers get
+    /// consistent names.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn nearest_metric<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        qx: N,
+        qy: N,
+        k: usize,
+        metric: &M,
+    ) -> Vec<u32> {
+        self.neighbors_metric(qx, qy, k, metric)
+    }
+
+    /// Search the index for items within a given bounding box on a periodic (wrap-around) domain,
+    /// such as global longitude wrapping at the antimeridian or a simulation box with periodic
+    /// boundaries.
+    ///
+    /// `period` gives the wrap period of the `x`/`y` axes (e.g. `Some([360., 180.])` for
+    /// longitude/latitude in degrees), or `None` for the ordinary non-periodic behavior of
+    /// [`range`][Self::range]. Coordinates on a periodic axis are assumed to lie in `[0, period)`;
+    /// a query bound that strays outside that range on a periodic axis (e.g. `max_x` past
+    /// `period`, meaning the window wraps back around through `0`) is decomposed into the at most
+    /// two sub-windows that don't straddle the seam, each searched with the ordinary box test,
+    /// and their results unioned.
+    ///
+    /// Returns indices of found items.
+    fn range_periodic(
+        &self,
+        min_x: N,
+        min_y: N,
+        max_x: N,
+        max_y: N,
+        period: Option<[N; 2]>,
+    ) -> Vec<u32> {
+        assert_eq!(
+            D, 2,
+            "range_periodic(min_x, min_y, max_x, max_y, period) only supports 2D trees"
+        );
+
+        let Some([period_x, period_y]) = period else {
+            return self.range(min_x, min_y, max_x, max_y);
+        };
+
+        let coords = self.coords();
+        let indices = self.indices();
+        let node_size = self.node_size() as usize;
+
+        let mut result: Vec<u32> = vec![];
+        for (x_min, x_max) in wrap_axis_range(min_x, max_x, period_x) {
+            for (y_min, y_max) in wrap_axis_range(min_y, max_y, period_y) {
+                result.extend(range_dyn(
+                    coords,
+                    &indices,
+                    node_size,
+                    [x_min, y_min],
+                    [x_max, y_max],
+                ));
+            }
+        }
+        // A seam-straddling query decomposes into up to 4 sub-windows (2 per axis); an item
+        // whose box falls in more than one of them would otherwise be yielded more than once.
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    /// Search the index for items within a given radius of a query point on a periodic
+    /// (wrap-around) domain.
+    ///
+    /// `period` gives the wrap period of the `x`/`y` axes, or `None` for the ordinary
+    /// non-periodic behavior of [`within`][Self::within]. A candidate's distance uses the 1-D gap
+    /// `min(|a - b|, period - |a - b|)` on each periodic axis instead of the plain coordinate
+    /// difference, and a subtree on the far side of a split is only skipped once both the direct
+    /// gap to the split and the gap through the seam to that subtree's side of the domain exceed
+    /// `r`, so a query circle near the seam correctly reaches items on the far side of it
+    /// without the caller needing to search a wrapped copy of the point themselves.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
     /// - r: radius
+    /// - period: wrap period of the `x`/`y` axes, or `None` for no wrapping
     ///
-    /// Returns indices of found items
-    fn within_coord(&self, coord: &impl CoordTrait<T = N>, r: N) -> Vec<u32> {
-        self.within(coord.x(), coord.y(), r)
+    /// Returns indices of found items.
+    fn within_periodic(&self, qx: N, qy: N, r: N, period: Option<[N; 2]>) -> Vec<u32> {
+        assert_eq!(
+            D, 2,
+            "within_periodic(qx, qy, r, period) only supports 2D trees"
+        );
+
+        let Some(period) = period else {
+            return self.within(qx, qy, r);
+        };
+
+        within_periodic_dyn(
+            self.coords(),
+            &self.indices(),
+            self.node_size() as usize,
+            qx,
+            qy,
+            r,
+            period,
+        )
+    }
+
+    /// Find the `k` nearest items to a query point on a periodic (wrap-around) domain, in
+    /// ascending order of distance.
+    ///
+    /// `period` gives the wrap period of the `x`/`y` axes, or `None` for the ordinary
+    /// non-periodic behavior of [`neighbors`][Self::neighbors]. See
+    /// [`within_periodic`][Self::within_periodic] for how `period` changes the distance used
+    /// during the descent.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - period: wrap period of the `x`/`y` axes, or `None` for no wrapping
+    ///
+    /// Returns indices of found items, ordered by ascending distance.
+    fn nearest_periodic(&self, qx: N, qy: N, k: usize, period: Option<[N; 2]>) -> Vec<u32> {
+        let Some(period) = period else {
+            return self.neighbors(qx, qy, k);
+        };
+
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let node_size = self.node_size() as usize;
+
+        let mut best: BinaryHeap<KnnCandidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn_periodic(
+            coords,
+            &indices,
+            node_size,
+            0,
+            indices.len() - 1,
+            0,
+            qx,
+            qy,
+            k,
+            period,
+            &mut best,
+        );
+
+        best.into_sorted_vec().into_iter().map(|c| c.id).collect()
     }
 
     /// Access the root node of the KDTree for manual traversal.
-    fn root(&self) -> Node<'_, N, Self> {
+    ///
+    /// Only available for 2D trees; [`Node`]'s `RectTrait`/`GeometryTrait` impls assume a 2D
+    /// bounding box.
+    fn root(&self) -> Node<'_, N, Self>
+    where
+        Self: KDTreeIndex<N>,
+    {
         Node::from_root(self)
     }
+
+    /// Validate this tree's internal structural integrity: that the recursive median
+    /// partitioning invariant [`KDTreeBuilder`][crate::kdtree::KDTreeBuilder] relies on for
+    /// correct search still holds, on every axis, at every node.
+    ///
+    /// `KDTreeMetadata::from_slice`/`KDTreeRef::try_new` only check the header (magic, version,
+    /// type, and total length), so a buffer that's the right *size* but internally corrupt —
+    /// swapped coordinates, a point left on the wrong side of a split — passes that validation
+    /// and silently produces wrong `range`/`within`/`neighbors` results later. This walks the
+    /// tree from the root down exactly as `sort` built it, to catch that kind of damage directly.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GeoIndexError::Invalid`] on the first violation found, carrying the full
+    /// `(level, node-offset)` path from the root down to the offending node, where `level` is
+    /// the recursion depth (0 at the root) and `node-offset` is the median index that failed.
+    fn validate(&self) -> Result<()> {
+        let num_items = self.indices().len();
+        if num_items == 0 {
+            return Ok(());
+        }
+
+        let mut path = vec![];
+        validate_node::<N, D>(
+            self.coords(),
+            self.node_size() as usize,
+            0,
+            num_items - 1,
+            0,
+            0,
+            &mut path,
+        )
+    }
 }
 
-impl<N: IndexableNum> KDTreeIndex<N> for KDTree<N> {
+impl<N: IndexableNum, B: AsRef<[u8]>, const D: usize> KDTreeIndex<N, D> for KDTree<N, B, D> {
     fn coords(&self) -> &[N] {
-        self.metadata.coords_slice(&self.buffer)
+        self.metadata.coords_slice(self.buffer.as_ref())
     }
 
     fn indices(&self) -> Indices<'_> {
-        self.metadata.indices_slice(&self.buffer)
+        self.metadata.indices_slice(self.buffer.as_ref())
     }
 
-    fn metadata(&self) -> &KDTreeMetadata<N> {
+    fn metadata(&self) -> &KDTreeMetadata<N, D> {
         &self.metadata
     }
 }
@@ -223,3 +910,937 @@ pub(crate) fn sq_dist<N: IndexableNum>(ax: N, ay: N, bx: N, by: N) -> N {
     let dy = ay - by;
     dx * dx + dy * dy
 }
+
+/// Squared Euclidean distance between a `D`-stride point slice and a `D`-dimensional query
+/// point, summed over all `D` components. Generalizes [`sq_dist`] beyond 2D.
+#[inline]
+fn sq_dist_nd<N: IndexableNum, const D: usize>(point: &[N], query: [N; D]) -> N {
+    let mut total = N::zero();
+    for d in 0..D {
+        let diff = point[d] - query[d];
+        total = total + diff * diff;
+    }
+    total
+}
+
+/// Recursively validate a single KD-node's median-partitioning invariant and its descendants,
+/// mirroring the same `left`/`right`/`axis` recursion the builder's `sort` function used to
+/// build the tree. Mutates `path` to track the nodes visited so far: the one push made here is
+/// undone before returning, so a sibling subtree never inherits a dangling entry, and on error
+/// `path` reflects exactly the route from the root down to the offending node.
+fn validate_node<N: IndexableNum, const D: usize>(
+    coords: &[N],
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    level: usize,
+    path: &mut Vec<(usize, usize)>,
+) -> Result<()> {
+    if right - left <= node_size {
+        return Ok(());
+    }
+
+    let m = (left + right) >> 1;
+    path.push((level, m));
+    let split = coords[D * m + axis];
+
+    for i in left..m {
+        if coords[D * i + axis] > split {
+            return Err(GeoIndexError::Invalid {
+                reason: format!(
+                    "item at position {i} has axis-{axis} coordinate {:?} greater than the median {:?}, violating the left-partition invariant",
+                    coords[D * i + axis],
+                    split
+                ),
+                path: path.clone(),
+            });
+        }
+    }
+    for i in (m + 1)..=right {
+        if coords[D * i + axis] < split {
+            return Err(GeoIndexError::Invalid {
+                reason: format!(
+                    "item at position {i} has axis-{axis} coordinate {:?} smaller than the median {:?}, violating the right-partition invariant",
+                    coords[D * i + axis],
+                    split
+                ),
+                path: path.clone(),
+            });
+        }
+    }
+
+    let next_axis = (axis + 1) % D;
+    if m > left {
+        validate_node::<N, D>(coords, node_size, left, m - 1, next_axis, level + 1, path)?;
+    }
+    validate_node::<N, D>(coords, node_size, m + 1, right, next_axis, level + 1, path)?;
+    path.pop();
+
+    Ok(())
+}
+
+/// Iterative range search over a `D`-stride kd-sorted `coords`/`indices` pair, generalizing
+/// [`KDTreeIndex::range`] beyond 2D: the splitting axis cycles through all `D` dimensions in
+/// turn (`axis = (axis + 1) % D`) rather than alternating between exactly two.
+fn range_dyn<N: IndexableNum, const D: usize>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    node_size: usize,
+    min: [N; D],
+    max: [N; D],
+) -> Vec<u32> {
+    // Use TinyVec to avoid heap allocations
+    let mut stack: TinyVec<[usize; 33]> = TinyVec::new();
+    stack.push(0);
+    stack.push(indices.len() - 1);
+    stack.push(0);
+
+    let mut result: Vec<u32> = vec![];
+
+    // recursively search for items in range in the kd-sorted arrays
+    while !stack.is_empty() {
+        let axis = stack.pop().unwrap_or(0);
+        let right = stack.pop().unwrap_or(0);
+        let left = stack.pop().unwrap_or(0);
+
+        // if we reached "tree node", search linearly
+        if right - left <= node_size {
+            for i in left..right + 1 {
+                let point = &coords[D * i..D * i + D];
+                if (0..D).all(|d| point[d] >= min[d] && point[d] <= max[d]) {
+                    result.push(indices.get(i).try_into().unwrap());
+                }
+            }
+            continue;
+        }
+
+        // otherwise find the middle index
+        let m = (left + right) >> 1;
+
+        // include the middle item if it's in range
+        let point = &coords[D * m..D * m + D];
+        if (0..D).all(|d| point[d] >= min[d] && point[d] <= max[d]) {
+            result.push(indices.get(m).try_into().unwrap());
+        }
+
+        let split = point[axis];
+        let next_axis = (axis + 1) % D;
+
+        // queue search in halves that intersect the query
+        if min[axis] <= split {
+            // Note: these are pushed in backwards order to what gets popped
+            stack.push(left);
+            stack.push(m - 1);
+            stack.push(next_axis);
+        }
+
+        if max[axis] >= split {
+            // Note: these are pushed in backwards order to what gets popped
+            stack.push(m + 1);
+            stack.push(right);
+            stack.push(next_axis);
+        }
+    }
+
+    result
+}
+
+/// Iterative radius search over a `D`-stride kd-sorted `coords`/`indices` pair, generalizing
+/// [`KDTreeIndex::within`] beyond 2D via [`sq_dist_nd`].
+fn within_dyn<N: IndexableNum, const D: usize>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    node_size: usize,
+    query: [N; D],
+    r: N,
+) -> Vec<u32> {
+    // Use TinyVec to avoid heap allocations
+    let mut stack: TinyVec<[usize; 33]> = TinyVec::new();
+    stack.push(0);
+    stack.push(indices.len() - 1);
+    stack.push(0);
+
+    let mut result: Vec<u32> = vec![];
+    let r2 = r * r;
+
+    // recursively search for items within radius in the kd-sorted arrays
+    while !stack.is_empty() {
+        let axis = stack.pop().unwrap_or(0);
+        let right = stack.pop().unwrap_or(0);
+        let left = stack.pop().unwrap_or(0);
+
+        // if we reached "tree node", search linearly
+        if right - left <= node_size {
+            for i in left..right + 1 {
+                if sq_dist_nd(&coords[D * i..D * i + D], query) <= r2 {
+                    result.push(indices.get(i).try_into().unwrap());
+                }
+            }
+            continue;
+        }
+
+        // otherwise find the middle index
+        let m = (left + right) >> 1;
+
+        // include the middle item if it's in range
+        let point = &coords[D * m..D * m + D];
+        if sq_dist_nd(point, query) <= r2 {
+            result.push(indices.get(m).try_into().unwrap());
+        }
+
+        let split = point[axis];
+        let next_axis = (axis + 1) % D;
+
+        // queue search in halves that intersect the query
+        if query[axis] - r <= split {
+            stack.push(left);
+            stack.push(m - 1);
+            stack.push(next_axis);
+        }
+
+        if query[axis] + r >= split {
+            stack.push(m + 1);
+            stack.push(right);
+            stack.push(next_axis);
+        }
+    }
+    result
+}
+
+/// The periodic (wrap-around) 1-D gap between two coordinates on an axis with the given `period`,
+/// e.g. `min(|a - b|, period - |a - b|)` for longitude wrapping at the antimeridian. `a` and `b`
+/// are assumed to already lie in `[0, period)`.
+#[inline]
+fn periodic_axis_gap<N: IndexableNum>(a: N, b: N, period: N) -> N {
+    let diff = if a > b { a - b } else { b - a };
+    let wrapped = period - diff;
+    if wrapped < diff {
+        wrapped
+    } else {
+        diff
+    }
+}
+
+/// Squared periodic distance between `(ax, ay)` and `(bx, by)`, using [`periodic_axis_gap`] on
+/// each axis instead of a plain coordinate difference.
+#[inline]
+fn sq_dist_periodic<N: IndexableNum>(ax: N, ay: N, bx: N, by: N, period: [N; 2]) -> N {
+    let dx = periodic_axis_gap(ax, bx, period[0]);
+    let dy = periodic_axis_gap(ay, by, period[1]);
+    dx * dx + dy * dy
+}
+
+/// Split a 1-D query range against an axis with the given wrap `period`, decomposing a range that
+/// straddles the `[0, period)` boundary into the (at most two) sub-ranges that don't.
+///
+/// Mirrors a longitude range crossing the antimeridian: a caller passing `max > period` (e.g.
+/// `max_x = 370` when `period = 360`, meaning the window wraps back around to `max - period`)
+/// gets `[min, period]` and `[0, max - period]` back; a caller passing `min < 0` gets the
+/// symmetric split. A range that already stays within `[0, period)` is returned unchanged.
+fn wrap_axis_range<N: IndexableNum>(min: N, max: N, period: N) -> Vec<(N, N)> {
+    let mut out = Vec::with_capacity(2);
+    if max > period {
+        out.push((min, period));
+        out.push((N::zero(), max - period));
+    } else if min < N::zero() {
+        out.push((N::zero(), max));
+        out.push((period + min, period));
+    } else {
+        out.push((min, max));
+    }
+    out
+}
+
+/// Iterative radius search over a 2D kd-sorted `coords`/`indices` pair on a periodic
+/// (wrap-around) domain, generalizing [`within_dyn`] via [`periodic_axis_gap`]/
+/// [`sq_dist_periodic`]: the leaf distance test uses the wrapped gap, and the decision to descend
+/// into a subtree takes the smaller of the direct gap to the split and the gap through the seam,
+/// so a query circle near the seam naturally reaches across it without the caller having to
+/// search a translated copy of the point.
+fn within_periodic_dyn<N: IndexableNum>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    node_size: usize,
+    qx: N,
+    qy: N,
+    r: N,
+    period: [N; 2],
+) -> Vec<u32> {
+    // Use TinyVec to avoid heap allocations
+    let mut stack: TinyVec<[usize; 33]> = TinyVec::new();
+    stack.push(0);
+    stack.push(indices.len() - 1);
+    stack.push(0);
+
+    let mut result: Vec<u32> = vec![];
+    let r2 = r * r;
+
+    while !stack.is_empty() {
+        let axis = stack.pop().unwrap_or(0);
+        let right = stack.pop().unwrap_or(0);
+        let left = stack.pop().unwrap_or(0);
+
+        if right - left <= node_size {
+            for i in left..right + 1 {
+                if sq_dist_periodic(coords[2 * i], coords[2 * i + 1], qx, qy, period) <= r2 {
+                    result.push(indices.get(i).try_into().unwrap());
+                }
+            }
+            continue;
+        }
+
+        let m = (left + right) >> 1;
+        let x = coords[2 * m];
+        let y = coords[2 * m + 1];
+        if sq_dist_periodic(x, y, qx, qy, period) <= r2 {
+            result.push(indices.get(m).try_into().unwrap());
+        }
+
+        let split = if axis == 0 { x } else { y };
+        let query_coord = if axis == 0 { qx } else { qy };
+        let next_axis = 1 - axis;
+
+        // The half containing the query coordinate directly always needs to be searched; the
+        // other half is reachable either directly (if the query is already past the split) or,
+        // on a periodic axis, by wrapping around through the domain's seam.
+        // Coordinates lie in `[0, period)`, so through the seam the left half (`<= split`) is
+        // `period - query_coord` away and the right half (`>= split`) is `query_coord` away.
+        let dist_to_left = if query_coord <= split {
+            N::zero()
+        } else {
+            let direct = query_coord - split;
+            let wrapped = period[axis] - query_coord;
+            if wrapped < direct { wrapped } else { direct }
+        };
+        let dist_to_right = if query_coord > split {
+            N::zero()
+        } else {
+            let direct = split - query_coord;
+            if query_coord < direct { query_coord } else { direct }
+        };
+
+        if dist_to_left <= r {
+            stack.push(left);
+            stack.push(m - 1);
+            stack.push(next_axis);
+        }
+        if dist_to_right <= r {
+            stack.push(m + 1);
+            stack.push(right);
+            stack.push(next_axis);
+        }
+    }
+    result
+}
+
+/// A lower bound, under `metric`, on the distance from `(qx, qy)` to anything on the far side of
+/// the splitting plane at `split` along `axis`.
+///
+/// Computed via `metric`'s own `distance_to_bbox` against a bbox collapsed to zero width along
+/// `axis` (and left unconstrained along the other axis via `N`'s min/max), i.e. the plane itself
+/// — the closest any far-side point could possibly be. This is the same clamped-closest-point
+/// technique [`HaversineDistance`][crate::rtree::HaversineDistance] and
+/// [`SpheroidDistance`][crate::rtree::SpheroidDistance] already use for their own
+/// `distance_to_bbox`, just applied to a degenerate, one-dimensional bbox.
+#[inline]
+fn axis_plane_distance<N: IndexableNum, M: SimpleDistanceMetric<N> + ?Sized>(
+    metric: &M,
+    qx: N,
+    qy: N,
+    axis: usize,
+    split: N,
+) -> N {
+    if axis == 0 {
+        metric.distance_to_bbox(qx, qy, split, N::min_value(), split, N::max_value())
+    } else {
+        metric.distance_to_bbox(qx, qy, N::min_value(), split, N::max_value(), split)
+    }
+}
+
+/// A candidate `(distance, id)` pair in [`KDTreeIndex::nearest_neighbors`]'s bounded max-heap.
+///
+/// Ordered by distance so that the *farthest* of the current best candidates sits at the top of
+/// the heap, ready to be evicted in `O(log k)` once a closer candidate turns up.
+struct KnnCandidate {
+    dist: f64,
+    id: u32,
+}
+
+impl PartialEq for KnnCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+
+impl Eq for KnnCandidate {}
+
+impl PartialOrd for KnnCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KnnCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist
+            .partial_cmp(&other.dist)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+#[inline]
+fn offer_candidate(best: &mut BinaryHeap<KnnCandidate>, k: usize, candidate: KnnCandidate) {
+    if best.len() < k {
+        best.push(candidate);
+    } else if let Some(worst) = best.peek() {
+        if candidate.dist < worst.dist {
+            best.pop();
+            best.push(candidate);
+        }
+    }
+}
+
+/// The candidate container used by [`KDTreeIndex::knn_advanced`] to track the current `k` best
+/// candidates during the search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KnnContainer {
+    /// A binary max-heap, giving `O(log k)` insertion and eviction of the current worst
+    /// candidate. The better asymptotic choice as `k` grows.
+    #[default]
+    Heap,
+    /// An unsorted buffer of up to `k` candidates; inserting evicts the current worst by a
+    /// linear `O(k)` scan. Lower constant overhead than `Heap`, so often faster for small `k`.
+    Linear,
+}
+
+/// Tuning parameters shared by the `*_advanced` query entry points across
+/// [`KDTreeIndex::nearest_advanced`], [`KdbushIndex::nearest_advanced`][crate::kdbush::KdbushIndex::nearest_advanced],
+/// and [`FlatbushIndex::neighbors_advanced`][crate::flatbush::FlatbushIndex::neighbors_advanced].
+///
+/// The default (`epsilon` zero, `max_radius` unbounded, `sort_results` true) performs an exact,
+/// fully sorted search, equivalent to not passing `SearchParameters` at all.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchParameters<N: IndexableNum> {
+    /// Approximation factor for the far-subtree prune; must be non-negative. `0.0` is exact;
+    /// larger values prune more subtrees at the cost of only guaranteeing results within a factor
+    /// of `(1+epsilon)` of the true distance.
+    pub epsilon: N,
+    /// If `Some`, discard candidates beyond this distance and skip subtrees that start beyond it
+    /// entirely.
+    pub max_radius: Option<N>,
+    /// Whether the returned indices are sorted by ascending distance. Set to `false` to skip the
+    /// final sort when the caller doesn't care about order.
+    pub sort_results: bool,
+}
+
+impl<N: IndexableNum> Default for SearchParameters<N> {
+    fn default() -> Self {
+        Self {
+            epsilon: N::zero(),
+            max_radius: None,
+            sort_results: true,
+        }
+    }
+}
+
+/// The backing storage behind a [`KDTreeIndex::knn_advanced`] search, dispatching to either a
+/// [`BinaryHeap`] or a linear-scan buffer depending on the requested [`KnnContainer`].
+enum CandidateSet {
+    Heap(BinaryHeap<KnnCandidate>),
+    Linear(Vec<KnnCandidate>),
+}
+
+impl CandidateSet {
+    fn new(container: KnnContainer, k: usize) -> Self {
+        match container {
+            KnnContainer::Heap => Self::Heap(BinaryHeap::with_capacity(k + 1)),
+            KnnContainer::Linear => Self::Linear(Vec::with_capacity(k)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Heap(heap) => heap.len(),
+            Self::Linear(buf) => buf.len(),
+        }
+    }
+
+    fn worst_dist(&self) -> Option<f64> {
+        match self {
+            Self::Heap(heap) => heap.peek().map(|c| c.dist),
+            Self::Linear(buf) => buf
+                .iter()
+                .map(|c| c.dist)
+                .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)),
+        }
+    }
+
+    fn offer(&mut self, k: usize, candidate: KnnCandidate) {
+        match self {
+            Self::Heap(heap) => offer_candidate(heap, k, candidate),
+            Self::Linear(buf) => {
+                if buf.len() < k {
+                    buf.push(candidate);
+                    return;
+                }
+                let worst_idx = buf
+                    .iter()
+                    .enumerate()
+                    .max_by(|(_, a), (_, b)| {
+                        a.dist.partial_cmp(&b.dist).unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .map(|(idx, _)| idx);
+                if let Some(worst_idx) = worst_idx {
+                    if candidate.dist < buf[worst_idx].dist {
+                        buf[worst_idx] = candidate;
+                    }
+                }
+            }
+        }
+    }
+
+    fn into_sorted_ids(self) -> Vec<u32> {
+        match self {
+            Self::Heap(heap) => heap.into_sorted_vec().into_iter().map(|c| c.id).collect(),
+            Self::Linear(mut buf) => {
+                buf.sort_unstable_by(|a, b| {
+                    a.dist.partial_cmp(&b.dist).unwrap_or(std::cmp::Ordering::Equal)
+                });
+                buf.into_iter().map(|c| c.id).collect()
+            }
+        }
+    }
+
+    /// Like [`into_sorted_ids`][Self::into_sorted_ids], but skips the final sort: callers that
+    /// don't care about result order (e.g. to then compute their own aggregate over the set) can
+    /// avoid the `O(k log k)` cost.
+    fn into_ids(self) -> Vec<u32> {
+        match self {
+            Self::Heap(heap) => heap.into_vec().into_iter().map(|c| c.id).collect(),
+            Self::Linear(buf) => buf.into_iter().map(|c| c.id).collect(),
+        }
+    }
+}
+
+/// Recursive best-first k-nearest-neighbor search over a 2D kd-sorted `coords`/`indices` pair.
+///
+/// Visits the child on the query's side of the splitting plane first; after it returns, only
+/// descends the far child if `metric`'s lower bound on the distance past the splitting plane,
+/// scaled by `epsilon_factor`, is still smaller than the current k-th best distance (otherwise
+/// the far subtree is pruned). `epsilon_factor` of `1.0` (i.e. `epsilon` of zero) performs an
+/// exact search; values greater than `1.0` prune more aggressively at the cost of only
+/// guaranteeing results within that factor of the true distance.
+#[allow(clippy::too_many_arguments)]
+fn search_knn<N: IndexableNum, M: Metric<N> + ?Sized>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    query: &[N],
+    k: usize,
+    metric: &M,
+    epsilon_factor: f64,
+    best: &mut BinaryHeap<KnnCandidate>,
+) {
+    if right - left <= node_size {
+        for i in left..=right {
+            let point = &coords[2 * i..2 * i + 2];
+            let dist = metric.distance(query, point);
+            offer_candidate(
+                best,
+                k,
+                KnnCandidate {
+                    dist,
+                    id: indices.get(i).try_into().unwrap(),
+                },
+            );
+        }
+        return;
+    }
+
+    let m = (left + right) >> 1;
+    let point = &coords[2 * m..2 * m + 2];
+    offer_candidate(
+        best,
+        k,
+        KnnCandidate {
+            dist: metric.distance(query, point),
+            id: indices.get(m).try_into().unwrap(),
+        },
+    );
+
+    let split_value = coords[2 * m + axis];
+    let next_axis = 1 - axis;
+    let query_on_left = query[axis] <= split_value;
+
+    let left_half_nonempty = m > left;
+    let right_half_nonempty = m < right;
+
+    let (near_half, far_half) = if query_on_left {
+        (left_half_nonempty, right_half_nonempty)
+    } else {
+        (right_half_nonempty, left_half_nonempty)
+    };
+
+    if near_half {
+        if query_on_left {
+            search_knn(
+                coords,
+                indices,
+                node_size,
+                left,
+                m - 1,
+                next_axis,
+                query,
+                k,
+                metric,
+                epsilon_factor,
+                best,
+            );
+        } else {
+            search_knn(
+                coords,
+                indices,
+                node_size,
+                m + 1,
+                right,
+                next_axis,
+                query,
+                k,
+                metric,
+                epsilon_factor,
+                best,
+            );
+        }
+    }
+
+    let axis_dist = metric.axis_distance(query, axis, split_value) * epsilon_factor;
+    if far_half && (best.len() < k || axis_dist < best.peek().unwrap().dist) {
+        if query_on_left {
+            search_knn(
+                coords,
+                indices,
+                node_size,
+                m + 1,
+                right,
+                next_axis,
+                query,
+                k,
+                metric,
+                epsilon_factor,
+                best,
+            );
+        } else {
+            search_knn(
+                coords,
+                indices,
+                node_size,
+                left,
+                m - 1,
+                next_axis,
+                query,
+                k,
+                metric,
+                epsilon_factor,
+                best,
+            );
+        }
+    }
+}
+
+/// Like [`search_knn`], but for an ordinary 2D Euclidean point query backing
+/// [`KDTreeIndex::knn_advanced`]/[`KDTreeIndex::nearest_advanced`]: the candidate set is a
+/// [`CandidateSet`] rather than a bare [`BinaryHeap`] (so either container can be plugged in), and
+/// `touch_count`, if `Some`, is incremented once per leaf point tested against.
+///
+/// `epsilon_factor` relaxes the far-subtree prune exactly as in [`search_knn`] (`1.0` is exact,
+/// larger values prune more aggressively); `max_radius_sq`, if `Some`, additionally discards
+/// candidates beyond that squared distance and skips subtrees that start beyond it entirely.
+#[allow(clippy::too_many_arguments)]
+fn search_knn_advanced<N: IndexableNum>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    qx: N,
+    qy: N,
+    k: usize,
+    epsilon_factor: f64,
+    max_radius_sq: Option<f64>,
+    candidates: &mut CandidateSet,
+    touch_count: &mut Option<usize>,
+) {
+    // `dist` is a plain Euclidean distance, so square it before comparing to `max_radius_sq`.
+    let within_radius = |dist: f64| max_radius_sq.map_or(true, |max| dist * dist <= max);
+
+    if right - left <= node_size {
+        for i in left..=right {
+            if let Some(count) = touch_count.as_mut() {
+                *count += 1;
+            }
+            let point = &coords[2 * i..2 * i + 2];
+            let dist = EuclideanMetric.distance(&[qx, qy], point);
+            if within_radius(dist) {
+                candidates.offer(
+                    k,
+                    KnnCandidate {
+                        dist,
+                        id: indices.get(i).try_into().unwrap(),
+                    },
+                );
+            }
+        }
+        return;
+    }
+
+    let m = (left + right) >> 1;
+    if let Some(count) = touch_count.as_mut() {
+        *count += 1;
+    }
+    let point = &coords[2 * m..2 * m + 2];
+    let mid_dist = EuclideanMetric.distance(&[qx, qy], point);
+    if within_radius(mid_dist) {
+        candidates.offer(
+            k,
+            KnnCandidate {
+                dist: mid_dist,
+                id: indices.get(m).try_into().unwrap(),
+            },
+        );
+    }
+
+    let split_value = coords[2 * m + axis];
+    let next_axis = 1 - axis;
+    let query_coord = if axis == 0 { qx } else { qy };
+    let query_on_left = query_coord <= split_value;
+
+    let left_half_nonempty = m > left;
+    let right_half_nonempty = m < right;
+
+    let (near_half, far_half) = if query_on_left {
+        (left_half_nonempty, right_half_nonempty)
+    } else {
+        (right_half_nonempty, left_half_nonempty)
+    };
+
+    if near_half {
+        if query_on_left {
+            search_knn_advanced(
+                coords, indices, node_size, left, m - 1, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, candidates, touch_count,
+            );
+        } else {
+            search_knn_advanced(
+                coords, indices, node_size, m + 1, right, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, candidates, touch_count,
+            );
+        }
+    }
+
+    let axis_dist = EuclideanMetric.axis_distance(&[qx, qy], axis, split_value);
+    let scaled_axis_dist = axis_dist * epsilon_factor;
+    let within_best = candidates.len() < k || scaled_axis_dist < candidates.worst_dist().unwrap();
+    if far_half && within_best && within_radius(scaled_axis_dist) {
+        if query_on_left {
+            search_knn_advanced(
+                coords, indices, node_size, m + 1, right, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, candidates, touch_count,
+            );
+        } else {
+            search_knn_advanced(
+                coords, indices, node_size, left, m - 1, next_axis, qx, qy, k, epsilon_factor,
+                max_radius_sq, candidates, touch_count,
+            );
+        }
+    }
+}
+
+/// Like [`search_knn`], but for a 2D point query under a
+/// [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric] rather than the generic
+/// slice-based [`Metric`]: the far subtree is pruned using [`axis_plane_distance`] instead of
+/// `Metric::axis_distance`.
+#[allow(clippy::too_many_arguments)]
+fn search_knn_simple<N: IndexableNum, M: SimpleDistanceMetric<N> + ?Sized>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    qx: N,
+    qy: N,
+    k: usize,
+    metric: &M,
+    best: &mut BinaryHeap<KnnCandidate>,
+) {
+    if right - left <= node_size {
+        for i in left..=right {
+            let dist = metric.distance(coords[2 * i], coords[2 * i + 1], qx, qy);
+            offer_candidate(
+                best,
+                k,
+                KnnCandidate {
+                    dist: dist.to_f64().unwrap_or(f64::MAX),
+                    id: indices.get(i).try_into().unwrap(),
+                },
+            );
+        }
+        return;
+    }
+
+    let m = (left + right) >> 1;
+    let x = coords[2 * m];
+    let y = coords[2 * m + 1];
+    offer_candidate(
+        best,
+        k,
+        KnnCandidate {
+            dist: metric.distance(x, y, qx, qy).to_f64().unwrap_or(f64::MAX),
+            id: indices.get(m).try_into().unwrap(),
+        },
+    );
+
+    let split = if axis == 0 { x } else { y };
+    let next_axis = 1 - axis;
+    let query_on_left = if axis == 0 { qx <= split } else { qy <= split };
+
+    let left_half_nonempty = m > left;
+    let right_half_nonempty = m < right;
+
+    let (near_half, far_half) = if query_on_left {
+        (left_half_nonempty, right_half_nonempty)
+    } else {
+        (right_half_nonempty, left_half_nonempty)
+    };
+
+    if near_half {
+        if query_on_left {
+            search_knn_simple(
+                coords, indices, node_size, left, m - 1, next_axis, qx, qy, k, metric, best,
+            );
+        } else {
+            search_knn_simple(
+                coords, indices, node_size, m + 1, right, next_axis, qx, qy, k, metric, best,
+            );
+        }
+    }
+
+    let axis_dist = axis_plane_distance(metric, qx, qy, axis, split)
+        .to_f64()
+        .unwrap_or(f64::MAX);
+    if far_half && (best.len() < k || axis_dist < best.peek().unwrap().dist) {
+        if query_on_left {
+            search_knn_simple(
+                coords, indices, node_size, m + 1, right, next_axis, qx, qy, k, metric, best,
+            );
+        } else {
+            search_knn_simple(
+                coords, indices, node_size, left, m - 1, next_axis, qx, qy, k, metric, best,
+            );
+        }
+    }
+}
+
+/// Recursive best-first k-nearest-neighbor search over a 2D kd-sorted `coords`/`indices` pair on
+/// a periodic (wrap-around) domain, mirroring [`search_knn_simple`] but using
+/// [`sq_dist_periodic`] for the leaf distance test and, for the far-subtree pruning bound, the
+/// smaller of the direct gap to the split and the gap through the seam to the far half, so a
+/// query near the domain's seam correctly reaches across it.
+#[allow(clippy::too_many_arguments)]
+fn search_knn_periodic<N: IndexableNum>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    node_size: usize,
+    left: usize,
+    right: usize,
+    axis: usize,
+    qx: N,
+    qy: N,
+    k: usize,
+    period: [N; 2],
+    best: &mut BinaryHeap<KnnCandidate>,
+) {
+    if right - left <= node_size {
+        for i in left..=right {
+            let dist = sq_dist_periodic(coords[2 * i], coords[2 * i + 1], qx, qy, period)
+                .sqrt()
+                .and_then(|d| d.to_f64())
+                .unwrap_or(f64::MAX);
+            offer_candidate(
+                best,
+                k,
+                KnnCandidate {
+                    dist,
+                    id: indices.get(i).try_into().unwrap(),
+                },
+            );
+        }
+        return;
+    }
+
+    let m = (left + right) >> 1;
+    let x = coords[2 * m];
+    let y = coords[2 * m + 1];
+    let dist = sq_dist_periodic(x, y, qx, qy, period)
+        .sqrt()
+        .and_then(|d| d.to_f64())
+        .unwrap_or(f64::MAX);
+    offer_candidate(
+        best,
+        k,
+        KnnCandidate {
+            dist,
+            id: indices.get(m).try_into().unwrap(),
+        },
+    );
+
+    let split = if axis == 0 { x } else { y };
+    let query_coord = if axis == 0 { qx } else { qy };
+    let next_axis = 1 - axis;
+    let query_on_left = query_coord <= split;
+
+    let left_half_nonempty = m > left;
+    let right_half_nonempty = m < right;
+
+    let (near_half, far_half) = if query_on_left {
+        (left_half_nonempty, right_half_nonempty)
+    } else {
+        (right_half_nonempty, left_half_nonempty)
+    };
+
+    if near_half {
+        if query_on_left {
+            search_knn_periodic(
+                coords, indices, node_size, left, m - 1, next_axis, qx, qy, k, period, best,
+            );
+        } else {
+            search_knn_periodic(
+                coords, indices, node_size, m + 1, right, next_axis, qx, qy, k, period, best,
+            );
+        }
+    }
+
+    let (direct, wrapped) = if query_on_left {
+        (split - query_coord, query_coord)
+    } else {
+        (query_coord - split, period[axis] - query_coord)
+    };
+    let far_gap = if wrapped < direct { wrapped } else { direct };
+    let axis_dist = far_gap.to_f64().unwrap_or(f64::MAX);
+    if far_half && (best.len() < k || axis_dist < best.peek().unwrap().dist) {
+        if query_on_left {
+            search_knn_periodic(
+                coords, indices, node_size, m + 1, right, next_axis, qx, qy, k, period, best,
+            );
+        } else {
+            search_knn_periodic(
+                coords, indices, node_size, left, m - 1, next_axis, qx, qy, k, period, best,
+            );
+        }
+    }
+}
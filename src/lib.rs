@@ -1,9 +1,14 @@
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "compression")]
+pub mod compression;
+#[cfg(feature = "use-geo_0_31")]
+pub mod hnsw;
 pub mod indices;
 pub mod kdtree;
 pub mod rtree;
 pub mod r#type;
+pub mod vptree;
 
 pub use kdtree::{KdbushBuilder, KdbushIndex, KdbushRef, OwnedKdbush};
 pub use rtree::{OwnedRTree, RTreeBuilder, RTreeIndex, RTreeRef};
@@ -22,9 +22,14 @@ pub const DEFAULT_RTREE_NODE_SIZE: u16 = 16;
 /// builder.add(2., 2., 4., 4.);
 /// let tree = builder.finish::<HilbertSort>();
 /// ```
-pub struct RTreeBuilder<N: IndexableNum> {
+///
+/// Generic over its backing storage `B`. The default `B = Vec<u8>` heap-allocates its own
+/// buffer; use [`Self::from_metadata_in`]/[`Self::new_in`] to instead build directly into a
+/// caller-owned `&mut [u8]` (an `mmap`'d file or bump arena, say), avoiding a second full-size
+/// allocation when the index is ultimately persisted there.
+pub struct RTreeBuilder<N: IndexableNum, B: AsMut<[u8]> + AsRef<[u8]> = Vec<u8>> {
     /// data buffer
-    data: Vec<u8>,
+    data: B,
     metadata: RTreeMetadata<N>,
     pos: usize,
     min_x: N,
@@ -33,7 +38,7 @@ pub struct RTreeBuilder<N: IndexableNum> {
     max_y: N,
 }
 
-impl<N: IndexableNum> RTreeBuilder<N> {
+impl<N: IndexableNum> RTreeBuilder<N, Vec<u8>> {
     /// Create a new builder with the provided number of items and the default node size.
     pub fn new(num_items: u32) -> Self {
         Self::new_with_node_size(num_items, DEFAULT_RTREE_NODE_SIZE)
@@ -45,10 +50,25 @@ impl<N: IndexableNum> RTreeBuilder<N> {
         Self::from_metadata(metadata)
     }
 
-    /// Create a new builder with the provided metadata
+    /// Create a new builder with the provided metadata, heap-allocating its own buffer.
     pub fn from_metadata(metadata: RTreeMetadata<N>) -> Self {
-        let mut data = vec![0; metadata.data_buffer_length()];
+        let data = vec![0; metadata.data_buffer_length()];
+        Self::from_metadata_in(metadata, data)
+    }
+}
 
+impl<N: IndexableNum, B: AsMut<[u8]> + AsRef<[u8]>> RTreeBuilder<N, B> {
+    /// Create a new builder with the provided metadata, writing into a caller-provided buffer.
+    ///
+    /// `buffer` must be exactly `metadata.data_buffer_length()` bytes long.
+    pub fn from_metadata_in(metadata: RTreeMetadata<N>, mut buffer: B) -> Self {
+        debug_assert_eq!(
+            buffer.as_ref().len(),
+            metadata.data_buffer_length(),
+            "buffer must be exactly `metadata.data_buffer_length()` bytes long"
+        );
+
+        let data = buffer.as_mut();
         // Set data header
         data[0] = 0xfb;
         data[1] = (VERSION << 4) + N::TYPE_INDEX;
@@ -56,7 +76,7 @@ impl<N: IndexableNum> RTreeBuilder<N> {
         cast_slice_mut(&mut data[4..8])[0] = metadata.num_items();
 
         Self {
-            data,
+            data: buffer,
             metadata,
             pos: 0,
             min_x: N::max_value(),
@@ -66,6 +86,16 @@ impl<N: IndexableNum> RTreeBuilder<N> {
         }
     }
 
+    /// Create a new builder with the provided number of items and node size, writing into a
+    /// caller-provided buffer.
+    ///
+    /// `buffer` must be exactly as long as
+    /// `RTreeMetadata::new(num_items, node_size).data_buffer_length()`.
+    pub fn new_in(num_items: u32, node_size: u16, buffer: B) -> Self {
+        let metadata = RTreeMetadata::new(num_items, node_size);
+        Self::from_metadata_in(metadata, buffer)
+    }
+
     /// Access the underlying [RTreeMetadata] of this instance.
     pub fn metadata(&self) -> &RTreeMetadata<N> {
         &self.metadata
@@ -80,7 +110,7 @@ impl<N: IndexableNum> RTreeBuilder<N> {
     #[inline]
     pub fn add(&mut self, min_x: N, min_y: N, max_x: N, max_y: N) -> u32 {
         let index = self.pos >> 2;
-        let (boxes, mut indices) = split_data_borrow(&mut self.data, &self.metadata);
+        let (boxes, mut indices) = split_data_borrow(self.data.as_mut(), &self.metadata);
 
         indices.set(index, index);
         boxes[self.pos] = min_x;
@@ -122,7 +152,7 @@ impl<N: IndexableNum> RTreeBuilder<N> {
         mut max_x: impl ExactSizeIterator<Item = N>,
         mut max_y: impl ExactSizeIterator<Item = N>,
     ) -> Vec<u32> {
-        let (boxes, mut indices) = split_data_borrow(&mut self.data, &self.metadata);
+        let (boxes, mut indices) = split_data_borrow(self.data.as_mut(), &self.metadata);
         assert_eq!(min_x.len(), min_y.len());
         assert_eq!(min_x.len(), max_x.len());
         assert_eq!(min_x.len(), max_y.len());
@@ -183,12 +213,14 @@ impl<N: IndexableNum> RTreeBuilder<N> {
 
     /// Consume this builder, perfoming the sort and generating an RTree ready for queries.
     ///
-    /// [`HilbertSort`] and [`STRSort`] both implement [`Sort`], allowing you to choose the method
-    /// used.
+    /// [`HilbertSort`], [`STRSort`], [`ZOrderSort`], and [`HilbertSort32`] all implement [`Sort`],
+    /// allowing you to choose the method used.
     ///
     /// [`HilbertSort`]: crate::rtree::sort::HilbertSort
     /// [`STRSort`]: crate::rtree::sort::STRSort
-    pub fn finish<S: Sort<N>>(mut self) -> RTree<N> {
+    /// [`ZOrderSort`]: crate::rtree::sort::ZOrderSort
+    /// [`HilbertSort32`]: crate::rtree::sort::HilbertSort32
+    pub fn finish<S: Sort<N>>(mut self) -> RTree<N, B> {
         assert_eq!(
             self.pos >> 2,
             self.metadata.num_items() as usize,
@@ -197,7 +229,7 @@ impl<N: IndexableNum> RTreeBuilder<N> {
             self.metadata.num_items()
         );
 
-        let (boxes, mut indices) = split_data_borrow(&mut self.data, &self.metadata);
+        let (boxes, mut indices) = split_data_borrow(self.data.as_mut(), &self.metadata);
 
         if self.metadata.num_items() == 1 {
             // Only one item, we don't even have a root node to fill
@@ -293,6 +325,140 @@ impl<N: IndexableNum> RTreeBuilder<N> {
             metadata: self.metadata,
         }
     }
+
+    /// Like [`Self::finish`], but sorts using multiple threads via `rayon` once a subrange of
+    /// the build exceeds an internal threshold, falling back to sequential recursion below it to
+    /// avoid task overhead on small inputs.
+    ///
+    /// Requires the `rayon` feature. [`Self::finish`] always sorts single-threaded and is
+    /// unaffected by whether this feature is enabled. [`HilbertSort`], [`ZOrderSort`],
+    /// [`HilbertSort32`], and [`STRSort`] all override [`Sort::sort_parallel`]; other strategies
+    /// just fall back to their ordinary [`Sort::sort`].
+    ///
+    /// [`HilbertSort`]: crate::rtree::sort::HilbertSort
+    /// [`STRSort`]: crate::rtree::sort::STRSort
+    /// [`ZOrderSort`]: crate::rtree::sort::ZOrderSort
+    /// [`HilbertSort32`]: crate::rtree::sort::HilbertSort32
+    #[cfg(feature = "rayon")]
+    pub fn finish_parallel<S: Sort<N>>(mut self) -> RTree<N, B> {
+        assert_eq!(
+            self.pos >> 2,
+            self.metadata.num_items() as usize,
+            "Added {} items when expected {}.",
+            self.pos >> 2,
+            self.metadata.num_items()
+        );
+
+        let (boxes, mut indices) = split_data_borrow(self.data.as_mut(), &self.metadata);
+
+        if self.metadata.num_items() == 1 {
+            // Only one item, we don't even have a root node to fill
+            return RTree {
+                buffer: self.data,
+                metadata: self.metadata,
+            };
+        }
+
+        if self.metadata.num_items() as usize <= self.metadata.node_size() as usize {
+            // only one node, skip sorting and just fill the root box
+            boxes[self.pos] = self.min_x;
+            self.pos += 1;
+            boxes[self.pos] = self.min_y;
+            self.pos += 1;
+            boxes[self.pos] = self.max_x;
+            self.pos += 1;
+            boxes[self.pos] = self.max_y;
+            self.pos += 1;
+
+            return RTree {
+                buffer: self.data,
+                metadata: self.metadata,
+            };
+        }
+
+        let mut sort_params = SortParams {
+            num_items: self.metadata.num_items() as usize,
+            node_size: self.metadata.node_size() as usize,
+            min_x: self.min_x,
+            min_y: self.min_y,
+            max_x: self.max_x,
+            max_y: self.max_y,
+        };
+        S::sort_parallel(&mut sort_params, boxes, &mut indices);
+
+        {
+            // generate nodes at each tree level, bottom-up
+            let mut pos = 0;
+            for end in self.metadata.level_bounds()[..self.metadata.level_bounds().len() - 1].iter()
+            {
+                while pos < *end {
+                    let node_index = pos;
+
+                    // calculate bbox for the new node
+                    let mut node_min_x = boxes[pos];
+                    pos += 1;
+                    let mut node_min_y = boxes[pos];
+                    pos += 1;
+                    let mut node_max_x = boxes[pos];
+                    pos += 1;
+                    let mut node_max_y = boxes[pos];
+                    pos += 1;
+                    for _ in 1..self.metadata.node_size() {
+                        if pos >= *end {
+                            break;
+                        }
+
+                        if boxes[pos] < node_min_x {
+                            node_min_x = boxes[pos];
+                        }
+                        pos += 1;
+                        if boxes[pos] < node_min_y {
+                            node_min_y = boxes[pos];
+                        }
+                        pos += 1;
+                        if boxes[pos] > node_max_x {
+                            node_max_x = boxes[pos]
+                        }
+                        pos += 1;
+                        if boxes[pos] > node_max_y {
+                            node_max_y = boxes[pos]
+                        }
+                        pos += 1;
+                    }
+
+                    // add the new node to the tree data
+                    indices.set(self.pos >> 2, node_index);
+                    boxes[self.pos] = node_min_x;
+                    self.pos += 1;
+                    boxes[self.pos] = node_min_y;
+                    self.pos += 1;
+                    boxes[self.pos] = node_max_x;
+                    self.pos += 1;
+                    boxes[self.pos] = node_max_y;
+                    self.pos += 1;
+                }
+            }
+        }
+
+        RTree {
+            buffer: self.data,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Consume this builder like [`Self::finish`], but return a checksummed, compressed byte
+    /// stream instead of a ready-to-query `RTree`.
+    ///
+    /// The tree itself is never queried in compressed form: this only compresses the
+    /// serialized bytes, for cheaper storage or transmission. Pass the result to
+    /// [`RTree::from_compressed`] to recover a normal, zero-copy tree.
+    #[cfg(feature = "compression")]
+    pub fn finish_compressed<S: Sort<N>>(
+        self,
+        compression: crate::compression::CompressionType,
+    ) -> Vec<u8> {
+        self.finish::<S>().to_compressed(compression)
+    }
 }
 
 /// Mutable borrow of boxes and indices
@@ -305,13 +471,17 @@ fn split_data_borrow<'a, N: IndexableNum>(
     debug_assert_eq!(indices_buf.len(), metadata.indices_byte_length);
 
     let boxes = cast_slice_mut(boxes_buf);
-    let indices = MutableIndices::new(indices_buf, metadata.num_nodes());
+    let indices = match metadata.indices_bytes_per_element {
+        1 => MutableIndices::U8(indices_buf),
+        2 => MutableIndices::U16(cast_slice_mut(indices_buf)),
+        _ => MutableIndices::U32(cast_slice_mut(indices_buf)),
+    };
     (boxes, indices)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::rtree::sort::HilbertSort;
+    use crate::rtree::sort::{HilbertSort, HilbertSort32, ZOrderSort};
     use crate::rtree::RTreeIndex;
 
     use super::*;
@@ -324,4 +494,40 @@ mod test {
         let result = tree.search(0., 0., 0., 0.);
         assert_eq!(result, vec![0]);
     }
+
+    #[test]
+    fn z_order_sort_builds_a_queryable_tree() {
+        let mut builder = RTreeBuilder::<f64>::new(3);
+        builder.add(0., 0., 2., 2.);
+        builder.add(1., 1., 3., 3.);
+        builder.add(2., 2., 4., 4.);
+        let tree = builder.finish::<ZOrderSort>();
+        assert_eq!(tree.search(0.5, 0.5, 1.5, 1.5), vec![0, 1]);
+    }
+
+    #[test]
+    fn hilbert_sort_32_builds_a_queryable_tree() {
+        let mut builder = RTreeBuilder::<f64>::new(3);
+        builder.add(0., 0., 2., 2.);
+        builder.add(1., 1., 3., 3.);
+        builder.add(2., 2., 4., 4.);
+        let tree = builder.finish::<HilbertSort32>();
+        assert_eq!(tree.search(0.5, 0.5, 1.5, 1.5), vec![0, 1]);
+    }
+
+    #[test]
+    fn builds_into_caller_provided_buffer() {
+        let metadata = RTreeMetadata::<f64>::new(3, DEFAULT_RTREE_NODE_SIZE);
+        let mut buffer = vec![0u8; metadata.data_buffer_length()];
+        let mut builder = RTreeBuilder::<f64, _>::new_in(
+            3,
+            DEFAULT_RTREE_NODE_SIZE,
+            buffer.as_mut_slice(),
+        );
+        builder.add(0., 0., 2., 2.);
+        builder.add(1., 1., 3., 3.);
+        builder.add(2., 2., 4., 4.);
+        let tree = builder.finish::<HilbertSort>();
+        assert_eq!(tree.search(0.5, 0.5, 1.5, 1.5), vec![0, 1]);
+    }
 }
@@ -0,0 +1,92 @@
+//! Optional xxh3 checksum footer for persisted [`RTree`] buffers.
+//!
+//! Mirrors [`crate::kdtree::checksum`]. Unlike [`crate::rtree::compression`], which wraps a buffer
+//! in a separate framing that must be decoded before it's queryable again, a checksummed buffer
+//! stays in the ordinary flatbush ABI: [`append`] only sets [`CHECKSUM_FLAG`] and appends an
+//! 8-byte hash, and [`RTreeMetadata::data_buffer_length`][crate::rtree::RTreeMetadata::data_buffer_length]
+//! already accounts for the footer, so [`RTreeRef::try_new`][crate::rtree::RTreeRef::try_new]
+//! reads it exactly like an unchecksummed buffer. [`verify`] is only called when a caller opts in
+//! via [`RTree::verify_checksum`][crate::rtree::RTree::verify_checksum]/
+//! [`RTreeRef::verify_checksum`][crate::rtree::RTreeRef::verify_checksum]/
+//! [`RTreeRef::try_new_checked`][crate::rtree::RTreeRef::try_new_checked].
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::error::{GeoIndexError, Result};
+use crate::r#type::IndexableNum;
+use crate::rtree::index::{RTreeMetadata, CHECKSUM_FLAG, CHECKSUM_FOOTER_SIZE};
+
+/// Set [`CHECKSUM_FLAG`] on a copy of `data` and append an 8-byte xxh3 checksum of the result.
+pub(crate) fn append(data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    out[1] |= CHECKSUM_FLAG;
+    let checksum = xxh3_64(&out);
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+/// Verify `data`'s checksum footer against `metadata`, if it has one.
+///
+/// A no-op if `metadata` indicates no footer is present.
+pub(crate) fn verify<N: IndexableNum>(data: &[u8], metadata: &RTreeMetadata<N>) -> Result<()> {
+    if !metadata.has_checksum() {
+        return Ok(());
+    }
+
+    let footer_start = data.len() - CHECKSUM_FOOTER_SIZE;
+    let expected = u64::from_le_bytes(data[footer_start..].try_into().unwrap());
+    let actual = xxh3_64(&data[..footer_start]);
+    if actual != expected {
+        return Err(GeoIndexError::General(
+            "Checksum mismatch: buffer is corrupt.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rtree::sort::HilbertSort;
+    use crate::rtree::{RTreeBuilder, RTreeIndex, RTreeRef};
+
+    #[test]
+    fn checksummed_buffer_still_queries_normally() {
+        let mut builder = RTreeBuilder::<f64>::new(3);
+        builder.add(0., 0., 2., 2.);
+        builder.add(1., 1., 3., 3.);
+        builder.add(2., 2., 4., 4.);
+        let tree = builder.finish::<HilbertSort>();
+
+        let checksummed = tree.to_checksummed();
+        let tree_ref = RTreeRef::<f64>::try_new(&checksummed).unwrap();
+        assert_eq!(tree_ref.search(0.5, 0.5, 1.5, 1.5), vec![0, 1]);
+        assert!(tree_ref.metadata.has_checksum());
+        assert!(tree_ref.verify_checksum().is_ok());
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let mut builder = RTreeBuilder::<f64>::new(1);
+        builder.add(0., 0., 1., 1.);
+        let tree = builder.finish::<HilbertSort>();
+
+        let mut checksummed = tree.to_checksummed();
+        let last = checksummed.len() - 1;
+        checksummed[last] ^= 0xff;
+
+        let tree_ref = RTreeRef::<f64>::try_new(&checksummed).unwrap();
+        assert!(tree_ref.verify_checksum().is_err());
+        assert!(RTreeRef::<f64>::try_new_checked(&checksummed).is_err());
+    }
+
+    #[test]
+    fn unchecksummed_buffer_verifies_as_a_no_op() {
+        let mut builder = RTreeBuilder::<f64>::new(1);
+        builder.add(0., 0., 1., 1.);
+        let tree = builder.finish::<HilbertSort>();
+
+        assert!(tree.verify_checksum().is_ok());
+    }
+}
@@ -0,0 +1,71 @@
+//! Optional xxh3 checksum and pluggable block compression for persisted [`RTree`] buffers.
+//!
+//! The in-memory [`RTree`] itself always stays a plain, zero-copy buffer over `coords`/`boxes`
+//! and indices; compression only applies to the serialized byte stream produced by
+//! [`RTree::to_compressed`][crate::rtree::OwnedRTree::to_compressed] and consumed by
+//! [`RTree::from_compressed`][crate::rtree::OwnedRTree::from_compressed], so querying a tree
+//! never pays a decompression cost. Only the region after the 8-byte ABI header (boxes and
+//! indices) is compressed; the header itself stays uncompressed and readable. See
+//! [`crate::compression`] for the shared framing this builds on.
+
+use crate::compression::{read_compressed_stream, write_compressed_stream, CompressionType};
+use crate::error::Result;
+use crate::r#type::IndexableNum;
+use crate::rtree::index::{RTree, RTreeMetadata};
+
+/// Compress and checksum an already-built `RTree`'s buffer under the given codec.
+pub(crate) fn compress<N: IndexableNum, B: AsRef<[u8]>>(
+    tree: &RTree<N, B>,
+    compression: CompressionType,
+) -> Vec<u8> {
+    let buffer = tree.as_ref();
+    let header: [u8; 8] = buffer[0..8].try_into().unwrap();
+    write_compressed_stream(&header, &buffer[8..], compression)
+}
+
+/// Reverse [`compress`], decompressing and checksum-verifying a stream back into a plain,
+/// zero-copy `RTree` buffer.
+pub(crate) fn decompress<N: IndexableNum>(data: &[u8]) -> Result<RTree<N, Vec<u8>>> {
+    let buffer = read_compressed_stream(data)?;
+    let metadata = RTreeMetadata::from_slice(&buffer)?;
+    Ok(RTree { buffer, metadata })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rtree::sort::HilbertSort;
+    use crate::rtree::{RTreeBuilder, RTreeIndex};
+
+    #[test]
+    fn compresses_and_decompresses_round_trip() {
+        let mut builder = RTreeBuilder::<f64>::new(3);
+        builder.add(0., 0., 2., 2.);
+        builder.add(1., 1., 3., 3.);
+        builder.add(2., 2., 4., 4.);
+        let tree = builder.finish::<HilbertSort>();
+
+        for compression in [
+            CompressionType::None,
+            CompressionType::Lz4,
+            CompressionType::Miniz(6),
+        ] {
+            let compressed = compress(&tree, compression);
+            let restored = decompress::<f64>(&compressed).unwrap();
+            assert_eq!(restored.search(0.5, 0.5, 1.5, 1.5), vec![0, 1]);
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_stream() {
+        let mut builder = RTreeBuilder::<f64>::new(1);
+        builder.add(0., 0., 1., 1.);
+        let tree = builder.finish::<HilbertSort>();
+
+        let mut compressed = compress(&tree, CompressionType::Lz4);
+        let last = compressed.len() - 1;
+        compressed[last] ^= 0xff;
+
+        assert!(decompress::<f64>(&compressed).is_err());
+    }
+}
@@ -3,10 +3,18 @@
 //! This module provides different distance calculation methods for spatial queries,
 //! including Euclidean, Haversine, and Spheroid distance calculations.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use crate::r#type::IndexableNum;
 use crate::rtree::r#trait::{axis_dist, SimpleDistanceMetric};
-use geo_0_31::algorithm::{Distance, Euclidean, Geodesic, Haversine};
-use geo_0_31::{Geometry, Point};
+use geo_0_31::algorithm::line_measures::Bearing;
+use geo_0_31::algorithm::{Contains, Distance, Euclidean, Geodesic, Haversine, Rhumb};
+use geo_0_31::{Geometry, LineString, Point};
+use geo_traits::{
+    CoordTrait, GeometryTrait, GeometryType, LineStringTrait, MultiLineStringTrait,
+    MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+};
 
 pub use crate::rtree::r#trait::GeometryAccessor;
 
@@ -15,13 +23,101 @@ pub use crate::rtree::r#trait::GeometryAccessor;
 /// This trait extends `SimpleDistanceMetric` to add geometry-to-geometry distance calculations.
 pub trait DistanceMetric<N: IndexableNum>: SimpleDistanceMetric<N> {
     /// Calculate the distance between two geometries.
-    /// This method is used by geometry-based neighbor searches to compute the actual
-    /// distance between a query geometry and an item geometry.
     ///
-    /// TODO: Consider changing to accept `&impl GeometryTrait<T = f64>` instead of concrete
-    /// `Geometry<f64>` type for better flexibility and integration with geo-traits.
-    /// This would be a non-breaking change since Geometry implements GeometryTrait.
-    fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N;
+    /// This method is used by geometry-based neighbor searches to compute the actual
+    /// distance between a query geometry and an item geometry. Accepting `&impl
+    /// GeometryTrait<T = f64>` (rather than the concrete `geo_0_31::Geometry<f64>`) lets callers
+    /// feed geometries straight from GeoArrow/WKB columnar buffers without first materializing
+    /// `geo_types` structs. Since `Geometry` itself implements `GeometryTrait`, this is
+    /// non-breaking for existing callers.
+    fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+        &self,
+        geom1: &G1,
+        geom2: &G2,
+    ) -> N;
+}
+
+/// Convert any `geo-traits` geometry into an owned `geo_types::Geometry<f64>`.
+///
+/// This lets [`DistanceMetric`] implementations keep using `geo`'s algorithms internally while
+/// accepting geometries from any source (GeoArrow arrays, WKB, or plain `geo_types`) at the
+/// trait boundary. `Rect`/`Line`/`Triangle` are expanded to their equivalent `Polygon`/
+/// `LineString` representation, and a `GeometryCollection` is flattened by taking the first
+/// member (good enough for the distance approximations used here, which already fall back to
+/// centroids for exotic inputs).
+pub(crate) fn to_owned_geometry<G: GeometryTrait<T = f64>>(geom: &G) -> Geometry<f64> {
+    match geom.as_type() {
+        GeometryType::Point(p) => Geometry::Point(to_point(p)),
+        GeometryType::LineString(ls) => Geometry::LineString(to_line_string(ls)),
+        GeometryType::Polygon(poly) => Geometry::Polygon(to_polygon(poly)),
+        GeometryType::MultiPoint(mp) => Geometry::MultiPoint(geo_0_31::MultiPoint::new(
+            mp.points().map(to_point).collect(),
+        )),
+        GeometryType::MultiLineString(mls) => Geometry::MultiLineString(
+            geo_0_31::MultiLineString::new(mls.line_strings().map(to_line_string).collect()),
+        ),
+        GeometryType::MultiPolygon(mpoly) => Geometry::MultiPolygon(geo_0_31::MultiPolygon::new(
+            mpoly.polygons().map(to_polygon).collect(),
+        )),
+        GeometryType::Rect(rect) => {
+            let min = rect.min();
+            let max = rect.max();
+            Geometry::Polygon(geo_0_31::Polygon::new(
+                LineString::from(vec![
+                    (min.x(), min.y()),
+                    (max.x(), min.y()),
+                    (max.x(), max.y()),
+                    (min.x(), max.y()),
+                    (min.x(), min.y()),
+                ]),
+                vec![],
+            ))
+        }
+        GeometryType::Line(line) => {
+            let start = line.start();
+            let end = line.end();
+            Geometry::LineString(LineString::from(vec![
+                (start.x(), start.y()),
+                (end.x(), end.y()),
+            ]))
+        }
+        GeometryType::Triangle(tri) => {
+            let (a, b, c) = (tri.first(), tri.second(), tri.third());
+            Geometry::Polygon(geo_0_31::Polygon::new(
+                LineString::from(vec![
+                    (a.x(), a.y()),
+                    (b.x(), b.y()),
+                    (c.x(), c.y()),
+                    (a.x(), a.y()),
+                ]),
+                vec![],
+            ))
+        }
+        GeometryType::GeometryCollection(gc) => gc
+            .geometries()
+            .next()
+            .map(|g| to_owned_geometry(&g))
+            .unwrap_or(Geometry::Point(Point::new(0.0, 0.0))),
+    }
+}
+
+fn to_point(p: impl PointTrait<T = f64>) -> Point<f64> {
+    p.coord()
+        .map(|c| Point::new(c.x(), c.y()))
+        .unwrap_or(Point::new(0.0, 0.0))
+}
+
+fn to_line_string(ls: impl LineStringTrait<T = f64>) -> LineString<f64> {
+    LineString::from(ls.coords().map(|c| (c.x(), c.y())).collect::<Vec<_>>())
+}
+
+fn to_polygon(poly: impl PolygonTrait<T = f64>) -> geo_0_31::Polygon<f64> {
+    let exterior = poly
+        .exterior()
+        .map(to_line_string)
+        .unwrap_or(LineString::new(vec![]));
+    let interiors = poly.interiors().map(to_line_string).collect();
+    geo_0_31::Polygon::new(exterior, interiors)
 }
 
 /// Euclidean distance metric.
@@ -46,11 +142,38 @@ impl<N: IndexableNum> SimpleDistanceMetric<N> for EuclideanDistance {
         let dy = axis_dist(y, min_y, max_y);
         (dx * dx + dy * dy).sqrt().unwrap_or(N::max_value())
     }
+
+    // The squared distance is monotonic with the exact (square-rooted) distance above, so a
+    // neighbor search's priority queue can order by it directly and skip the `sqrt` entirely.
+    #[inline]
+    fn cmp_distance(&self, x1: N, y1: N, x2: N, y2: N) -> N {
+        let dx = x2 - x1;
+        let dy = y2 - y1;
+        dx * dx + dy * dy
+    }
+
+    #[inline]
+    fn cmp_distance_to_bbox(&self, x: N, y: N, min_x: N, min_y: N, max_x: N, max_y: N) -> N {
+        let dx = axis_dist(x, min_x, max_x);
+        let dy = axis_dist(y, min_y, max_y);
+        dx * dx + dy * dy
+    }
+
+    #[inline]
+    fn cmp_max_distance(&self, max_distance: N) -> N {
+        max_distance * max_distance
+    }
 }
 
 impl<N: IndexableNum> DistanceMetric<N> for EuclideanDistance {
-    fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-        N::from_f64(Euclidean.distance(geom1, geom2)).unwrap_or(N::max_value())
+    fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+        &self,
+        geom1: &G1,
+        geom2: &G2,
+    ) -> N {
+        let geom1 = to_owned_geometry(geom1);
+        let geom2 = to_owned_geometry(geom2);
+        N::from_f64(Euclidean.distance(&geom1, &geom2)).unwrap_or(N::max_value())
     }
 }
 
@@ -69,7 +192,8 @@ pub struct HaversineDistance {
 impl Default for HaversineDistance {
     fn default() -> Self {
         Self {
-            earth_radius: 6378137.0, // WGS84 equatorial radius in meters
+            // IUGG mean Earth radius in meters
+            earth_radius: 6371008.8,
         }
     }
 }
@@ -81,6 +205,29 @@ impl HaversineDistance {
     }
 }
 
+/// Clamp a longitude value into a bbox's lon-range, accounting for antimeridian wraparound (where
+/// `min_lon > max_lon` because the box crosses +/-180 degrees).
+#[inline]
+fn clamp_lon(lon: f64, min_lon: f64, max_lon: f64) -> f64 {
+    if min_lon <= max_lon {
+        return lon.clamp(min_lon, max_lon);
+    }
+
+    // The box wraps the antimeridian: the "inside" range is [min_lon, 180] union [-180, max_lon].
+    if lon >= min_lon || lon <= max_lon {
+        lon
+    } else {
+        // Outside the box on both sides; pick whichever edge is angularly closer.
+        let dist_to_min = (lon - min_lon + 540.0) % 360.0 - 180.0;
+        let dist_to_max = (lon - max_lon + 540.0) % 360.0 - 180.0;
+        if dist_to_min.abs() <= dist_to_max.abs() {
+            min_lon
+        } else {
+            max_lon
+        }
+    }
+}
+
 impl<N: IndexableNum> SimpleDistanceMetric<N> for HaversineDistance {
     fn distance(&self, lon1: N, lat1: N, lon2: N, lat2: N) -> N {
         let p1 = Point::new(lon1.to_f64().unwrap_or(0.0), lat1.to_f64().unwrap_or(0.0));
@@ -97,7 +244,8 @@ impl<N: IndexableNum> SimpleDistanceMetric<N> for HaversineDistance {
         max_lon: N,
         max_lat: N,
     ) -> N {
-        // For geographic distance to bbox, find the closest point on the bbox
+        // Find the closest point on the bbox, clamping longitude with antimeridian wraparound so
+        // this lower bound never exceeds the true distance to any item inside the box.
         let lon_f = lon.to_f64().unwrap_or(0.0);
         let lat_f = lat.to_f64().unwrap_or(0.0);
         let min_lon_f = min_lon.to_f64().unwrap_or(0.0);
@@ -105,7 +253,7 @@ impl<N: IndexableNum> SimpleDistanceMetric<N> for HaversineDistance {
         let max_lon_f = max_lon.to_f64().unwrap_or(0.0);
         let max_lat_f = max_lat.to_f64().unwrap_or(0.0);
 
-        let closest_lon = lon_f.clamp(min_lon_f, max_lon_f);
+        let closest_lon = clamp_lon(lon_f, min_lon_f, max_lon_f);
         let closest_lat = lat_f.clamp(min_lat_f, max_lat_f);
 
         let point = Point::new(lon_f, lat_f);
@@ -115,36 +263,400 @@ impl<N: IndexableNum> SimpleDistanceMetric<N> for HaversineDistance {
 }
 
 impl<N: IndexableNum> DistanceMetric<N> for HaversineDistance {
-    fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-        // For Haversine, use centroid-to-centroid distance as approximation
-        use geo_0_31::algorithm::Centroid;
-        let c1 = geom1.centroid().unwrap_or(Point::new(0.0, 0.0));
-        let c2 = geom2.centroid().unwrap_or(Point::new(0.0, 0.0));
-        N::from_f64(Haversine.distance(c1, c2)).unwrap_or(N::max_value())
+    fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+        &self,
+        geom1: &G1,
+        geom2: &G2,
+    ) -> N {
+        let geom1 = to_owned_geometry(geom1);
+        let geom2 = to_owned_geometry(geom2);
+        N::from_f64(great_circle_distance_to_geometry(
+            Haversine,
+            &geom1,
+            &geom2,
+            self.earth_radius,
+        ))
+        .unwrap_or(N::max_value())
     }
 }
 
-/// Spheroid distance metric (using Geodesic/Vincenty's formula).
+/// Spheroid distance metric, computed via Vincenty's iterative inverse formula.
 ///
-/// This calculates the shortest distance between two points on the surface
-/// of a spheroid (ellipsoid), providing a more accurate Earth model than
-/// a simple sphere. The input coordinates should be in longitude/latitude
-/// (degrees), and the output distance is in meters.
-#[derive(Debug, Clone, Copy, Default)]
-pub struct SpheroidDistance;
+/// This calculates the shortest distance between two points on the surface of a configurable
+/// reference ellipsoid, providing a more accurate Earth model than a simple sphere. The input
+/// coordinates should be in longitude/latitude (degrees), and the output distance is in meters.
+///
+/// geo's own `Geodesic` type is hard-wired to WGS84, so real datasets on a different ellipsoid
+/// (Bessel 1841, Clarke 1866, ...) need their own solver; [`Self::with_ellipsoid`] and the named
+/// presets below configure the semi-major axis `a` and flattening `f` that Vincenty's formula
+/// runs against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpheroidDistance {
+    /// Semi-major axis of the reference ellipsoid, in meters.
+    pub a: f64,
+    /// Flattening of the reference ellipsoid.
+    pub f: f64,
+}
+
+impl Default for SpheroidDistance {
+    fn default() -> Self {
+        Self::wgs84()
+    }
+}
 
 impl SpheroidDistance {
-    /// Create a new Spheroid distance metric for GRS80 ellipsoid.
+    /// Create a Spheroid distance metric for an arbitrary reference ellipsoid, given its
+    /// semi-major axis `a` (in meters) and flattening `f`.
+    pub fn with_ellipsoid(a: f64, f: f64) -> Self {
+        Self { a, f }
+    }
+
+    /// The WGS84 ellipsoid (`a` = 6378137.0 m, `1/f` = 298.257223563).
+    pub fn wgs84() -> Self {
+        Self::with_ellipsoid(6378137.0, 1.0 / 298.257223563)
+    }
+
+    /// The GRS80 ellipsoid (`a` = 6378137.0 m, `1/f` = 298.257222101).
     pub fn grs80() -> Self {
-        Self
+        Self::with_ellipsoid(6378137.0, 1.0 / 298.257222101)
+    }
+
+    /// The Bessel 1841 ellipsoid (`a` = 6377397.155 m, `1/f` = 299.1528128).
+    pub fn bessel1841() -> Self {
+        Self::with_ellipsoid(6377397.155, 1.0 / 299.1528128)
+    }
+
+    /// The Clarke 1866 ellipsoid (`a` = 6378206.4 m, `1/f` = 294.9786982).
+    pub fn clarke1866() -> Self {
+        Self::with_ellipsoid(6378206.4, 1.0 / 294.9786982)
+    }
+
+    /// Vincenty's iterative inverse solution for the geodesic distance between two lon/lat
+    /// points (in degrees) on this ellipsoid, in meters.
+    ///
+    /// Falls back to a great-circle approximation (a sphere of radius `a`) if the iteration
+    /// fails to converge within 200 steps, which can happen for near-antipodal points.
+    fn vincenty_distance(&self, lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+        let a = self.a;
+        let f = self.f;
+        let b = a * (1.0 - f);
+
+        let u1 = ((1.0 - f) * lat1.to_radians().tan()).atan();
+        let u2 = ((1.0 - f) * lat2.to_radians().tan()).atan();
+        let l = (lon2 - lon1).to_radians();
+
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        let mut remaining_iters = 200;
+        let (mut sin_sigma, mut cos_sigma, mut sigma, mut cos_sq_alpha, mut cos2_sigma_m) =
+            (0.0, 0.0, 0.0, 0.0, 0.0);
+
+        loop {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                return 0.0; // Coincident points.
+            }
+            cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+            cos2_sigma_m = if cos_sq_alpha == 0.0 {
+                0.0 // Equatorial line.
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            };
+            let c = f / 16.0 * cos_sq_alpha * (4.0 + f * (4.0 - 3.0 * cos_sq_alpha));
+            let lambda_prev = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * f
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos2_sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos2_sigma_m * cos2_sigma_m)));
+            remaining_iters -= 1;
+            if (lambda - lambda_prev).abs() < 1e-12 || remaining_iters == 0 {
+                break;
+            }
+        }
+
+        if remaining_iters == 0 {
+            // Near-antipodal points failed to converge; fall back to a spherical approximation
+            // using this ellipsoid's semi-major axis as the sphere radius.
+            let p1 = Point::new(lon1, lat1);
+            let p2 = Point::new(lon2, lat2);
+            return Haversine.distance(p1, p2) * (a / 6371008.8);
+        }
+
+        let u_sq = cos_sq_alpha * (a * a - b * b) / (b * b);
+        let cap_a =
+            1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+        let cap_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+        let delta_sigma = cap_b
+            * sin_sigma
+            * (cos2_sigma_m
+                + cap_b / 4.0
+                    * (cos_sigma * (-1.0 + 2.0 * cos2_sigma_m * cos2_sigma_m)
+                        - cap_b / 6.0
+                            * cos2_sigma_m
+                            * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                            * (-3.0 + 4.0 * cos2_sigma_m * cos2_sigma_m)));
+
+        b * cap_a * (sigma - delta_sigma)
     }
 }
 
 impl<N: IndexableNum> SimpleDistanceMetric<N> for SpheroidDistance {
+    fn distance(&self, lon1: N, lat1: N, lon2: N, lat2: N) -> N {
+        let d = self.vincenty_distance(
+            lon1.to_f64().unwrap_or(0.0),
+            lat1.to_f64().unwrap_or(0.0),
+            lon2.to_f64().unwrap_or(0.0),
+            lat2.to_f64().unwrap_or(0.0),
+        );
+        N::from_f64(d).unwrap_or(N::max_value())
+    }
+
+    fn distance_to_bbox(
+        &self,
+        lon: N,
+        lat: N,
+        min_lon: N,
+        min_lat: N,
+        max_lon: N,
+        max_lat: N,
+    ) -> N {
+        // Similar to haversine, approximate using closest point on bbox
+        let lon_f = lon.to_f64().unwrap_or(0.0);
+        let lat_f = lat.to_f64().unwrap_or(0.0);
+        let min_lon_f = min_lon.to_f64().unwrap_or(0.0);
+        let min_lat_f = min_lat.to_f64().unwrap_or(0.0);
+        let max_lon_f = max_lon.to_f64().unwrap_or(0.0);
+        let max_lat_f = max_lat.to_f64().unwrap_or(0.0);
+
+        let closest_lon = clamp_lon(lon_f, min_lon_f, max_lon_f);
+        let closest_lat = lat_f.clamp(min_lat_f, max_lat_f);
+
+        let d = self.vincenty_distance(lon_f, lat_f, closest_lon, closest_lat);
+        N::from_f64(d).unwrap_or(N::max_value())
+    }
+}
+
+impl<N: IndexableNum> DistanceMetric<N> for SpheroidDistance {
+    fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+        &self,
+        geom1: &G1,
+        geom2: &G2,
+    ) -> N {
+        // Point-to-geometry cross-track/along-track trigonometry needs a `Bearing` impl, which
+        // geo only provides for its built-in (WGS84) `Geodesic` space; a custom `self.a`/`self.f`
+        // ellipsoid is therefore only honored by `distance`/`distance_to_bbox` above, not here.
+        let geom1 = to_owned_geometry(geom1);
+        let geom2 = to_owned_geometry(geom2);
+        N::from_f64(great_circle_distance_to_geometry(
+            Geodesic,
+            &geom1,
+            &geom2,
+            self.a,
+        ))
+        .unwrap_or(N::max_value())
+    }
+}
+
+/// Compute the true point-to-geometry great-circle distance between `geom1` and `geom2`, rather
+/// than collapsing both to centroids.
+///
+/// If `geom1` is a single point, this walks every segment of `geom2`'s boundary (or its exterior
+/// and interior rings, for polygons) and finds the true closest point using the cross-track /
+/// along-track distance formulas from Ed Williams' Aviation Formulary:
+/// `d13 = dist(A,P)/R`, `dxt = asin(sin(d13)*sin(bearing(A,P) - bearing(A,B))) * R`, and
+/// `dat = acos(cos(d13)/cos(dxt/R)) * R`. If `dat` falls within the segment, the segment distance
+/// is `|dxt|`; otherwise it's the smaller of the distances to the segment's endpoints. A point
+/// inside a polygon has distance 0. For non-point query geometries, this instead samples `geom1`'s
+/// vertices and takes the minimum distance as a conservative bound.
+fn great_circle_distance_to_geometry<S>(
+    space: S,
+    geom1: &Geometry<f64>,
+    geom2: &Geometry<f64>,
+    radius_meters: f64,
+) -> f64
+where
+    S: Distance<f64, Point<f64>, Point<f64>> + Bearing<f64> + Copy,
+{
+    if let Geometry::Point(p) = geom1 {
+        return point_to_geometry_distance(space, *p, geom2, radius_meters);
+    }
+
+    // Conservative bound for non-point query geometries: sample vertices.
+    use geo_0_31::algorithm::CoordsIter;
+    geom1
+        .coords_iter()
+        .map(|c| point_to_geometry_distance(space, Point::from(c), geom2, radius_meters))
+        .fold(f64::MAX, f64::min)
+}
+
+fn point_to_geometry_distance<S>(
+    space: S,
+    point: Point<f64>,
+    geom: &Geometry<f64>,
+    radius_meters: f64,
+) -> f64
+where
+    S: Distance<f64, Point<f64>, Point<f64>> + Bearing<f64> + Copy,
+{
+    match geom {
+        Geometry::Point(p) => space.distance(point, *p),
+        Geometry::MultiPoint(mp) => mp
+            .iter()
+            .map(|p| space.distance(point, *p))
+            .fold(f64::MAX, f64::min),
+        Geometry::LineString(ls) => line_string_point_distance(space, point, ls, radius_meters),
+        Geometry::MultiLineString(mls) => mls
+            .iter()
+            .map(|ls| line_string_point_distance(space, point, ls, radius_meters))
+            .fold(f64::MAX, f64::min),
+        Geometry::Polygon(poly) => {
+            if poly.contains(&point) {
+                0.0
+            } else {
+                polygon_point_distance(space, point, poly, radius_meters)
+            }
+        }
+        Geometry::MultiPolygon(mpoly) => mpoly
+            .iter()
+            .map(|poly| {
+                if poly.contains(&point) {
+                    0.0
+                } else {
+                    polygon_point_distance(space, point, poly, radius_meters)
+                }
+            })
+            .fold(f64::MAX, f64::min),
+        _ => {
+            // Other/mixed geometry collections: fall back to centroid distance.
+            use geo_0_31::algorithm::Centroid;
+            let c = geom.centroid().unwrap_or(point);
+            space.distance(point, c)
+        }
+    }
+}
+
+fn polygon_point_distance<S>(
+    space: S,
+    point: Point<f64>,
+    poly: &geo_0_31::Polygon<f64>,
+    radius_meters: f64,
+) -> f64
+where
+    S: Distance<f64, Point<f64>, Point<f64>> + Bearing<f64> + Copy,
+{
+    let mut min_d = line_string_point_distance(space, point, poly.exterior(), radius_meters);
+    for interior in poly.interiors() {
+        min_d = min_d.min(line_string_point_distance(space, point, interior, radius_meters));
+    }
+    min_d
+}
+
+fn line_string_point_distance<S>(
+    space: S,
+    point: Point<f64>,
+    line: &LineString<f64>,
+    radius_meters: f64,
+) -> f64
+where
+    S: Distance<f64, Point<f64>, Point<f64>> + Bearing<f64> + Copy,
+{
+    let mut min_d = f64::MAX;
+    let mut any_segment = false;
+    for seg in line.lines() {
+        any_segment = true;
+        let a = Point::from(seg.start);
+        let b = Point::from(seg.end);
+        let d = segment_point_distance(space, point, a, b, radius_meters);
+        if d < min_d {
+            min_d = d;
+        }
+    }
+    if !any_segment {
+        // Degenerate (zero- or one-point) linestring.
+        if let Some(p) = line.points().next() {
+            return space.distance(point, p);
+        }
+    }
+    min_d
+}
+
+/// Closest distance from `point` to the great-circle segment `a`-`b`, via cross-track / along-track
+/// distance. See [`great_circle_distance_to_geometry`] for the formula.
+fn segment_point_distance<S>(
+    space: S,
+    point: Point<f64>,
+    a: Point<f64>,
+    b: Point<f64>,
+    radius_meters: f64,
+) -> f64
+where
+    S: Distance<f64, Point<f64>, Point<f64>> + Bearing<f64> + Copy,
+{
+    let dist_ap = space.distance(a, point);
+    if dist_ap == 0.0 {
+        return 0.0;
+    }
+
+    let seg_len = space.distance(a, b);
+    if seg_len == 0.0 {
+        return dist_ap;
+    }
+
+    let d13 = dist_ap / radius_meters;
+    let theta13 = space.bearing(a, point).to_radians();
+    let theta12 = space.bearing(a, b).to_radians();
+
+    let dxt = (d13.sin() * (theta13 - theta12).sin())
+        .clamp(-1.0, 1.0)
+        .asin()
+        * radius_meters;
+
+    let cos_arg = (d13.cos() / (dxt / radius_meters).cos()).clamp(-1.0, 1.0);
+    let dat = cos_arg.acos() * radius_meters;
+
+    if dat <= seg_len {
+        dxt.abs()
+    } else {
+        let dist_bp = space.distance(b, point);
+        dist_ap.min(dist_bp)
+    }
+}
+
+/// Geodesic distance metric.
+///
+/// This is an alias for [`SpheroidDistance`]: the shortest distance between two points on the
+/// surface of a reference ellipsoid (WGS84 by default; see [`SpheroidDistance::with_ellipsoid`]
+/// for others), via Vincenty's inverse formula. The input coordinates should be in
+/// longitude/latitude (degrees), and the output distance is in meters.
+pub type GeodesicDistance = SpheroidDistance;
+
+/// Rhumb-line (loxodrome) distance metric.
+///
+/// This calculates the distance along a line of constant bearing, as used for shipping and
+/// aviation track lengths. Unlike [`HaversineDistance`] or [`SpheroidDistance`], which find the
+/// shortest great-circle path, a rhumb line crosses every meridian at the same angle, which can
+/// be a meaningfully longer path. The input coordinates should be in longitude/latitude
+/// (degrees), and the output distance is in meters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RhumbDistance;
+
+impl<N: IndexableNum> SimpleDistanceMetric<N> for RhumbDistance {
     fn distance(&self, lon1: N, lat1: N, lon2: N, lat2: N) -> N {
         let p1 = Point::new(lon1.to_f64().unwrap_or(0.0), lat1.to_f64().unwrap_or(0.0));
         let p2 = Point::new(lon2.to_f64().unwrap_or(0.0), lat2.to_f64().unwrap_or(0.0));
-        N::from_f64(Geodesic.distance(p1, p2)).unwrap_or(N::max_value())
+        N::from_f64(Rhumb.distance(p1, p2)).unwrap_or(N::max_value())
     }
 
     fn distance_to_bbox(
@@ -156,7 +668,7 @@ impl<N: IndexableNum> SimpleDistanceMetric<N> for SpheroidDistance {
         max_lon: N,
         max_lat: N,
     ) -> N {
-        // Similar to haversine, approximate using closest point on bbox
+        // Same closest-point-on-bbox clamping used by `HaversineDistance`.
         let lon_f = lon.to_f64().unwrap_or(0.0);
         let lat_f = lat.to_f64().unwrap_or(0.0);
         let min_lon_f = min_lon.to_f64().unwrap_or(0.0);
@@ -164,22 +676,126 @@ impl<N: IndexableNum> SimpleDistanceMetric<N> for SpheroidDistance {
         let max_lon_f = max_lon.to_f64().unwrap_or(0.0);
         let max_lat_f = max_lat.to_f64().unwrap_or(0.0);
 
-        let closest_lon = lon_f.clamp(min_lon_f, max_lon_f);
+        let closest_lon = clamp_lon(lon_f, min_lon_f, max_lon_f);
         let closest_lat = lat_f.clamp(min_lat_f, max_lat_f);
 
         let point = Point::new(lon_f, lat_f);
         let closest_point = Point::new(closest_lon, closest_lat);
-        N::from_f64(Geodesic.distance(point, closest_point)).unwrap_or(N::max_value())
+        N::from_f64(Rhumb.distance(point, closest_point)).unwrap_or(N::max_value())
     }
 }
 
-impl<N: IndexableNum> DistanceMetric<N> for SpheroidDistance {
-    fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-        // For Geodesic, use centroid-to-centroid distance as approximation
+impl<N: IndexableNum> DistanceMetric<N> for RhumbDistance {
+    fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+        &self,
+        geom1: &G1,
+        geom2: &G2,
+    ) -> N {
+        // For Rhumb, use centroid-to-centroid distance as approximation
         use geo_0_31::algorithm::Centroid;
+        let geom1 = to_owned_geometry(geom1);
+        let geom2 = to_owned_geometry(geom2);
         let c1 = geom1.centroid().unwrap_or(Point::new(0.0, 0.0));
         let c2 = geom2.centroid().unwrap_or(Point::new(0.0, 0.0));
-        N::from_f64(Geodesic.distance(c1, c2)).unwrap_or(N::max_value())
+        N::from_f64(Rhumb.distance(c1, c2)).unwrap_or(N::max_value())
+    }
+}
+
+/// Extract a representative [`LineString`] from an arbitrary geometry, for algorithms (like
+/// [`FrechetDistance`] below) that only operate on line strings.
+///
+/// Points become a degenerate single-vertex line, polygons use their exterior ring, and
+/// multi-geometries use their first member; this is a best-effort shape, not a precise one.
+fn to_representative_line_string(geom: &Geometry<f64>) -> LineString<f64> {
+    match geom {
+        Geometry::LineString(ls) => ls.clone(),
+        Geometry::Point(p) => LineString::from(vec![p.0, p.0]),
+        Geometry::Polygon(poly) => poly.exterior().clone(),
+        Geometry::MultiPoint(mp) => LineString::from(mp.iter().map(|p| p.0).collect::<Vec<_>>()),
+        Geometry::MultiLineString(mls) => mls
+            .0
+            .first()
+            .cloned()
+            .unwrap_or_else(|| LineString::from(vec![])),
+        Geometry::MultiPolygon(mpoly) => mpoly
+            .0
+            .first()
+            .map(|poly| poly.exterior().clone())
+            .unwrap_or_else(|| LineString::from(vec![])),
+        _ => LineString::from(vec![]),
+    }
+}
+
+/// Fréchet distance metric, for comparing the similarity of curves such as GPS trajectories.
+///
+/// Unlike [`EuclideanDistance`], which measures the distance between two shapes, Fréchet
+/// distance measures how similar two curves are taking into account the order of their points
+/// along the path (informally, the minimum leash length needed for a person and a dog walking
+/// along each curve, without backtracking, to stay connected). Delegates to geo's
+/// `FrechetDistance` algorithm, which operates on line strings; other geometry types are reduced
+/// to a representative line string first. The input coordinates should be in planar (not
+/// lon/lat) units for the result to be meaningful.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrechetDistance;
+
+impl<N: IndexableNum> SimpleDistanceMetric<N> for FrechetDistance {
+    fn distance(&self, x1: N, y1: N, x2: N, y2: N) -> N {
+        EuclideanDistance.distance(x1, y1, x2, y2)
+    }
+
+    fn distance_to_bbox(&self, x: N, y: N, min_x: N, min_y: N, max_x: N, max_y: N) -> N {
+        // An ordinary Euclidean bbox bound is an admissible lower bound for Fréchet distance,
+        // since Fréchet distance is never smaller than the Euclidean distance between the
+        // closest pair of points on the two curves.
+        EuclideanDistance.distance_to_bbox(x, y, min_x, min_y, max_x, max_y)
+    }
+}
+
+impl<N: IndexableNum> DistanceMetric<N> for FrechetDistance {
+    fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+        &self,
+        geom1: &G1,
+        geom2: &G2,
+    ) -> N {
+        use geo_0_31::algorithm::FrechetDistance as GeoFrechetDistance;
+        let geom1 = to_representative_line_string(&to_owned_geometry(geom1));
+        let geom2 = to_representative_line_string(&to_owned_geometry(geom2));
+        N::from_f64(geom1.frechet_distance(&geom2)).unwrap_or(N::max_value())
+    }
+}
+
+/// Hausdorff distance metric, for comparing the similarity of shapes regardless of point order.
+///
+/// Hausdorff distance is the greatest of all distances from a point in one geometry to the
+/// closest point in the other, measured in both directions. Delegates to geo's
+/// `HausdorffDistance` algorithm. The input coordinates should be in planar (not lon/lat) units
+/// for the result to be meaningful.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HausdorffDistance;
+
+impl<N: IndexableNum> SimpleDistanceMetric<N> for HausdorffDistance {
+    fn distance(&self, x1: N, y1: N, x2: N, y2: N) -> N {
+        EuclideanDistance.distance(x1, y1, x2, y2)
+    }
+
+    fn distance_to_bbox(&self, x: N, y: N, min_x: N, min_y: N, max_x: N, max_y: N) -> N {
+        // As with FrechetDistance, the ordinary Euclidean bbox bound is an admissible lower
+        // bound: Hausdorff distance is never smaller than the Euclidean distance between the
+        // closest pair of points on the two geometries.
+        EuclideanDistance.distance_to_bbox(x, y, min_x, min_y, max_x, max_y)
+    }
+}
+
+impl<N: IndexableNum> DistanceMetric<N> for HausdorffDistance {
+    fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+        &self,
+        geom1: &G1,
+        geom2: &G2,
+    ) -> N {
+        use geo_0_31::algorithm::HausdorffDistance as GeoHausdorffDistance;
+        let geom1 = to_owned_geometry(geom1);
+        let geom2 = to_owned_geometry(geom2);
+        N::from_f64(geom1.hausdorff_distance(&geom2)).unwrap_or(N::max_value())
     }
 }
 
@@ -218,6 +834,96 @@ impl<'a> GeometryAccessor for SliceGeometryAccessor<'a> {
     }
 }
 
+/// A geometry accessor over WKB-encoded geometries packed into a single contiguous buffer.
+///
+/// This is the zero-copy path from an Arrow/Parquet WKB geometry column into
+/// [`neighbors_geometry`][crate::rtree::RTreeIndex::neighbors_geometry]: rather than decoding
+/// every geometry up front into a `Vec<Geometry<f64>>`, `WkbGeometryAccessor` borrows the packed
+/// `data` buffer and a GeoArrow-style `offsets` array (`offsets[i]..offsets[i + 1]` is the WKB
+/// slice for item `i`, so `offsets.len()` is the number of geometries plus one) and decodes each
+/// geometry lazily,
+/// the first time it's requested. Decoded geometries are cached so that repeated neighbor
+/// comparisons against the same feature don't re-parse WKB.
+///
+/// # Example
+/// ```
+/// use geo_index::rtree::distance::{EuclideanDistance, GeometryAccessor, WkbGeometryAccessor};
+/// use geo_0_31::{Geometry, Point};
+/// use geozero::{CoordDimensions, ToWkb};
+///
+/// let point = Geometry::Point(Point::new(1.0, 1.0));
+/// let wkb = point.to_wkb(CoordDimensions::default()).unwrap();
+/// let offsets = vec![0u32, wkb.len() as u32];
+///
+/// let accessor = WkbGeometryAccessor::new(&wkb, &offsets);
+/// assert_eq!(accessor.get_geometry(0), Some(&point));
+/// ```
+pub struct WkbGeometryAccessor<'a> {
+    data: &'a [u8],
+    offsets: &'a [u32],
+    cache: RefCell<HashMap<usize, Box<Geometry<f64>>>>,
+}
+
+impl<'a> WkbGeometryAccessor<'a> {
+    /// Create a new accessor over a packed WKB buffer and its GeoArrow-style offsets.
+    ///
+    /// `offsets` must have `data`'s item count plus one entries, with `offsets[i]..offsets[i +
+    /// 1]` giving the byte range of the `i`th geometry's WKB encoding within `data`.
+    pub fn new(data: &'a [u8], offsets: &'a [u32]) -> Self {
+        Self {
+            data,
+            offsets,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// The number of geometries in this accessor.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Returns `true` if this accessor holds no geometries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn decode(&self, item_index: usize) -> Option<Geometry<f64>> {
+        use geozero::geo_types::GeoWriter;
+        use geozero::wkb::Wkb;
+        use geozero::GeozeroGeometry;
+
+        let start = *self.offsets.get(item_index)? as usize;
+        let end = *self.offsets.get(item_index + 1)? as usize;
+        let mut geo_writer = GeoWriter::new();
+        Wkb(self.data.get(start..end)?)
+            .process_geom(&mut geo_writer)
+            .ok()?;
+        geo_writer.take_geometry()
+    }
+}
+
+impl<'a> GeometryAccessor for WkbGeometryAccessor<'a> {
+    fn get_geometry(&self, item_index: usize) -> Option<&Geometry<f64>> {
+        if !self.cache.borrow().contains_key(&item_index) {
+            let geometry = self.decode(item_index)?;
+            self.cache
+                .borrow_mut()
+                .insert(item_index, Box::new(geometry));
+        }
+
+        let cache = self.cache.borrow();
+        let boxed: &Box<Geometry<f64>> = cache.get(&item_index)?;
+        let ptr: *const Geometry<f64> = boxed.as_ref();
+        // Safety: `boxed` is heap-allocated, so its target's address is stable even if the
+        // `HashMap` reallocates on a later insert, and cache entries are never removed or
+        // replaced once inserted.
+        // Justification: `GeometryAccessor::get_geometry` returns `&Geometry<f64>`, which
+        // requires handing out a reference tied to `&self` rather than to the `RefCell`'s
+        // short-lived `Ref` guard.
+        unsafe { Some(&*ptr) }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,13 +947,113 @@ mod tests {
 
     #[test]
     fn test_spheroid_distance() {
-        let metric = SpheroidDistance;
+        let metric = SpheroidDistance::default();
         // Distance between New York and London (approximately)
         let distance = metric.distance(-74.0f64, 40.7f64, -0.1f64, 51.5f64);
         // Should be approximately 5585 km (slightly different from Haversine)
         assert!((distance - 5585000.0f64).abs() < 50000.0f64);
     }
 
+    #[test]
+    fn test_spheroid_distance_custom_ellipsoid() {
+        // Distance between New York and London on a few named presets should all land in the
+        // same ballpark, but not be bit-identical, since each ellipsoid has different a/f.
+        let wgs84 = SpheroidDistance::wgs84().distance(-74.0f64, 40.7f64, -0.1f64, 51.5f64);
+        let grs80 = SpheroidDistance::grs80().distance(-74.0f64, 40.7f64, -0.1f64, 51.5f64);
+        let clarke1866 =
+            SpheroidDistance::clarke1866().distance(-74.0f64, 40.7f64, -0.1f64, 51.5f64);
+
+        assert!((wgs84 - 5585000.0f64).abs() < 50000.0f64);
+        assert!((grs80 - wgs84).abs() < 1.0, "wgs84 and grs80 are nearly identical");
+        assert!((clarke1866 - wgs84).abs() > 1.0, "differing ellipsoids should differ");
+    }
+
+    #[test]
+    fn test_rhumb_distance() {
+        let metric = RhumbDistance;
+        // Distance between New York and London (approximately)
+        let distance = metric.distance(-74.0f64, 40.7f64, -0.1f64, 51.5f64);
+        // Rhumb-line distance (about 5797 km) is longer than the great-circle distance (about
+        // 5573 km).
+        assert!((distance - 5797000.0f64).abs() < 100000.0f64);
+    }
+
+    #[test]
+    fn test_frechet_distance_identical_lines_is_zero() {
+        let line = Geometry::LineString(LineString::from(vec![
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 0.0),
+        ]));
+        let metric = FrechetDistance;
+        let distance: f64 = metric.distance_to_geometry(&line, &line);
+        assert_eq!(distance, 0.0);
+    }
+
+    #[test]
+    fn test_frechet_distance_parallel_lines() {
+        let line1 = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 0.0)]));
+        let line2 = Geometry::LineString(LineString::from(vec![(0.0, 1.0), (1.0, 1.0)]));
+        let metric = FrechetDistance;
+        let distance: f64 = metric.distance_to_geometry(&line1, &line2);
+        assert_eq!(distance, 1.0);
+    }
+
+    #[test]
+    fn test_hausdorff_distance_identical_geometries_is_zero() {
+        let line = Geometry::LineString(LineString::from(vec![
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 0.0),
+        ]));
+        let metric = HausdorffDistance;
+        let distance: f64 = metric.distance_to_geometry(&line, &line);
+        assert_eq!(distance, 0.0);
+    }
+
+    #[test]
+    fn test_hausdorff_distance_parallel_lines() {
+        let line1 = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 0.0)]));
+        let line2 = Geometry::LineString(LineString::from(vec![(0.0, 1.0), (1.0, 1.0)]));
+        let metric = HausdorffDistance;
+        let distance: f64 = metric.distance_to_geometry(&line1, &line2);
+        assert_eq!(distance, 1.0);
+    }
+
+    #[test]
+    fn test_haversine_geometry_distance_point_on_long_linestring() {
+        // A point sitting on a long "coastline" LineString should report ~0 distance, not the
+        // large centroid-to-centroid distance a naive approximation would give.
+        let coastline = Geometry::LineString(LineString::from(vec![
+            (-74.0, 40.0),
+            (-73.0, 41.0),
+            (10.0, 50.0),
+        ]));
+        let query = Geometry::Point(Point::new(-74.0, 40.0));
+
+        let metric = HaversineDistance::default();
+        let distance: f64 = metric.distance_to_geometry(&query, &coastline);
+        assert!(distance < 1.0, "expected near-zero distance, got {distance}");
+    }
+
+    #[test]
+    fn test_haversine_geometry_distance_point_inside_polygon() {
+        let square = Geometry::Polygon(geo_0_31::Polygon::new(
+            LineString::from(vec![
+                (0.0, 0.0),
+                (2.0, 0.0),
+                (2.0, 2.0),
+                (0.0, 2.0),
+                (0.0, 0.0),
+            ]),
+            vec![],
+        ));
+        let query = Geometry::Point(Point::new(1.0, 1.0));
+
+        let metric = HaversineDistance::default();
+        assert_eq!(metric.distance_to_geometry(&query, &square), 0.0);
+    }
+
     #[test]
     fn test_euclidean_geometry_distance() {
         // Test Euclidean distance between geometries
@@ -312,8 +1118,14 @@ mod tests {
         }
 
         impl<'a, N: IndexableNum> DistanceMetric<N> for WkbDistanceMetric<'a> {
-            fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-                N::from_f64(Euclidean.distance(geom1, geom2)).unwrap_or(N::max_value())
+            fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+                &self,
+                geom1: &G1,
+                geom2: &G2,
+            ) -> N {
+                let geom1 = to_owned_geometry(geom1);
+                let geom2 = to_owned_geometry(geom2);
+                N::from_f64(Euclidean.distance(&geom1, &geom2)).unwrap_or(N::max_value())
             }
         }
 
@@ -408,8 +1220,14 @@ mod tests {
         }
 
         impl<'a, N: IndexableNum> DistanceMetric<N> for CachedDistanceMetric<'a> {
-            fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-                N::from_f64(Euclidean.distance(geom1, geom2)).unwrap_or(N::max_value())
+            fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+                &self,
+                geom1: &G1,
+                geom2: &G2,
+            ) -> N {
+                let geom1 = to_owned_geometry(geom1);
+                let geom2 = to_owned_geometry(geom2);
+                N::from_f64(Euclidean.distance(&geom1, &geom2)).unwrap_or(N::max_value())
             }
         }
 
@@ -455,4 +1273,34 @@ mod tests {
         assert_eq!(hits_after_second, 2); // 2 cache hits
         assert_eq!(misses_after_second, 3); // Still 3 misses total
     }
+
+    #[test]
+    fn test_wkb_geometry_accessor_decodes_and_caches() {
+        use geozero::{CoordDimensions, ToWkb};
+
+        let points = [
+            Geometry::Point(Point::new(0.0, 0.0)),
+            Geometry::Point(Point::new(3.0, 4.0)),
+        ];
+        let wkb: Vec<Vec<u8>> = points
+            .iter()
+            .map(|g| g.to_wkb(CoordDimensions::default()).unwrap())
+            .collect();
+
+        let mut data = Vec::new();
+        let mut offsets = vec![0u32];
+        for bytes in &wkb {
+            data.extend_from_slice(bytes);
+            offsets.push(data.len() as u32);
+        }
+
+        let accessor = WkbGeometryAccessor::new(&data, &offsets);
+        assert_eq!(accessor.len(), 2);
+
+        // Decoding is lazy: the first call parses the WKB, later calls reuse the cached value.
+        assert_eq!(accessor.get_geometry(0), Some(&points[0]));
+        assert_eq!(accessor.get_geometry(0), Some(&points[0]));
+        assert_eq!(accessor.get_geometry(1), Some(&points[1]));
+        assert_eq!(accessor.get_geometry(2), None);
+    }
 }
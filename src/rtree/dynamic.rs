@@ -0,0 +1,384 @@
+//! A dynamic, insert-capable wrapper around the immutable [`RTree`].
+//!
+//! [`RTreeBuilder`]/[`RTreeIndex`][crate::rtree::RTreeIndex] produce an immutable, bulk-loaded
+//! tree with no way to add items after [`finish`][RTreeBuilder::finish]. [`DynamicRTree`]
+//! restores incremental insertion by applying the classic "logarithmic method" of dynamization on
+//! top of the static builder: a small linear buffer absorbs new inserts, and once it fills its
+//! contents are merged with existing trees and rebuilt into a single new immutable tree, the way
+//! a binary counter carries.
+
+use std::collections::HashSet;
+
+use crate::r#type::IndexableNum;
+use crate::rtree::sort::HilbertSort;
+use crate::rtree::{RTree, RTreeBuilder, RTreeIndex};
+
+/// The number of bits of buffer capacity: the linear buffer holds up to `1 << BUFFER_BITS` items
+/// before it is flushed into a tree.
+const BUFFER_BITS: u32 = 6;
+
+/// Once a tree's live fraction (non-tombstoned items) drops below this threshold, it is rebuilt
+/// during the next compaction pass to reclaim space.
+const COMPACTION_THRESHOLD: f64 = 0.5;
+
+struct BufferedItem<N: IndexableNum, D> {
+    id: u64,
+    min_x: N,
+    min_y: N,
+    max_x: N,
+    max_y: N,
+    data: D,
+}
+
+/// One occupied slot of the dynamization forest: an immutable [`RTree`] together with the global
+/// item id, user data and box of each of its items, in insertion order.
+struct Slot<N: IndexableNum, D> {
+    tree: RTree<N>,
+    ids: Vec<u64>,
+    data: Vec<D>,
+    /// Each item's `[min_x, min_y, max_x, max_y]` in insertion order; the tree's own boxes are
+    /// Hilbert-sorted, so they can't be read back by insertion index.
+    boxes: Vec<N>,
+    live_count: usize,
+}
+
+/// A dynamic, insert-capable R-tree.
+///
+/// This wraps [`RTreeBuilder`]/[`RTree`] with the classic dynamization scheme used to add
+/// incremental insertion to an otherwise-static structure: a small flat buffer absorbs new
+/// inserts and is searched linearly, while a vector of optional immutable trees holds
+/// geometrically-sized batches (slot `i`, when occupied, holds at most `2^(i + BUFFER_BITS)`
+/// items). When the buffer fills, its items plus every occupied consecutive low slot are merged
+/// and rebuilt into one new tree placed at the first empty slot, amortizing rebuild cost to
+/// `O(log n)` per insert.
+///
+/// Deletion is logical: a tombstone set is consulted at query time, and [`Self::compact`] rebuilds
+/// any slot whose live fraction has dropped below a threshold.
+///
+/// ```
+/// use geo_index::rtree::DynamicRTree;
+///
+/// let mut tree = DynamicRTree::<f64>::new();
+/// let id0 = tree.insert(0., 0., 1., 1., "a");
+/// let id1 = tree.insert(5., 5., 6., 6., "b");
+///
+/// let mut results = tree.search(0., 0., 2., 2.);
+/// assert_eq!(results, vec![id0]);
+///
+/// tree.remove(id1);
+/// results = tree.search(0., 0., 10., 10.);
+/// assert_eq!(results, vec![id0]);
+/// ```
+pub struct DynamicRTree<N: IndexableNum, D = u64> {
+    buffer: Vec<BufferedItem<N, D>>,
+    slots: Vec<Option<Slot<N, D>>>,
+    tombstones: HashSet<u64>,
+    next_id: u64,
+}
+
+impl<N: IndexableNum, D: Clone> Default for DynamicRTree<N, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: IndexableNum, D: Clone> DynamicRTree<N, D> {
+    /// Create a new, empty dynamic R-tree.
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::with_capacity(1 << BUFFER_BITS),
+            slots: Vec::new(),
+            tombstones: HashSet::new(),
+            next_id: 0,
+        }
+    }
+
+    /// The total number of live (non-deleted) items in this tree.
+    pub fn len(&self) -> usize {
+        self.buffer
+            .iter()
+            .filter(|item| !self.tombstones.contains(&item.id))
+            .count()
+            + self
+                .slots
+                .iter()
+                .filter_map(|slot| slot.as_ref().map(|s| s.live_count))
+                .sum::<usize>()
+    }
+
+    /// Returns `true` if this tree contains no live items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a new rectangle with associated data, returning a stable id that can later be
+    /// passed to [`Self::remove`].
+    pub fn insert(&mut self, min_x: N, min_y: N, max_x: N, max_y: N, data: D) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.buffer.push(BufferedItem {
+            id,
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            data,
+        });
+
+        if self.buffer.len() >= 1 << BUFFER_BITS {
+            self.flush_buffer();
+        }
+
+        id
+    }
+
+    /// Logically delete an item by id. The item is skipped by future queries but its storage is
+    /// only reclaimed the next time its containing slot is compacted.
+    pub fn remove(&mut self, id: u64) {
+        if !self.tombstones.insert(id) {
+            return;
+        }
+        for slot in self.slots.iter_mut().flatten() {
+            if slot.ids.contains(&id) {
+                slot.live_count = slot.live_count.saturating_sub(1);
+                break;
+            }
+        }
+    }
+
+    /// Search for items whose bounding box intersects the query rectangle.
+    pub fn search(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Vec<u64> {
+        let mut results = Vec::new();
+
+        for item in &self.buffer {
+            if self.tombstones.contains(&item.id) {
+                continue;
+            }
+            if item.max_x < min_x || item.min_x > max_x || item.max_y < min_y || item.min_y > max_y
+            {
+                continue;
+            }
+            results.push(item.id);
+        }
+
+        for slot in self.slots.iter().flatten() {
+            for local_index in slot.tree.search(min_x, min_y, max_x, max_y) {
+                let id = slot.ids[local_index as usize];
+                if !self.tombstones.contains(&id) {
+                    results.push(id);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Returns pairs of intersecting item ids between this tree and another dynamic tree.
+    pub fn intersection_candidates_with_other_tree(&self, other: &Self) -> Vec<(u64, u64)> {
+        let mut results = Vec::new();
+
+        // Every live item of this tree, buffered or in a slot, is compared against every live item
+        // of `other` by brute force; the per-slot trees are not used for this join.
+        let self_items = self.all_boxes();
+        let other_items = other.all_boxes();
+        for &(id1, min_x1, min_y1, max_x1, max_y1) in &self_items {
+            for &(id2, min_x2, min_y2, max_x2, max_y2) in &other_items {
+                if max_x1 >= min_x2 && min_x1 <= max_x2 && max_y1 >= min_y2 && min_y1 <= max_y2 {
+                    results.push((id1, id2));
+                }
+            }
+        }
+
+        results
+    }
+
+    fn all_boxes(&self) -> Vec<(u64, N, N, N, N)> {
+        let mut out = Vec::with_capacity(self.len());
+        for item in &self.buffer {
+            if !self.tombstones.contains(&item.id) {
+                out.push((item.id, item.min_x, item.min_y, item.max_x, item.max_y));
+            }
+        }
+        for slot in self.slots.iter().flatten() {
+            let boxes = &slot.boxes;
+            for (local_index, &id) in slot.ids.iter().enumerate() {
+                if self.tombstones.contains(&id) {
+                    continue;
+                }
+                let pos = local_index * 4;
+                out.push((id, boxes[pos], boxes[pos + 1], boxes[pos + 2], boxes[pos + 3]));
+            }
+        }
+        out
+    }
+
+    /// Rebuild any slot whose live fraction has dropped below [`COMPACTION_THRESHOLD`], reclaiming
+    /// the space occupied by tombstoned items.
+    pub fn compact(&mut self) {
+        for slot_opt in self.slots.iter_mut() {
+            let needs_compaction = match slot_opt {
+                Some(slot) if slot.tree.num_items() > 0 => {
+                    (slot.live_count as f64) / (slot.tree.num_items() as f64) < COMPACTION_THRESHOLD
+                }
+                _ => false,
+            };
+            if !needs_compaction {
+                continue;
+            }
+
+            let slot = slot_opt.take().unwrap();
+            let live: Vec<_> = slot
+                .ids
+                .iter()
+                .zip(slot.data.iter())
+                .enumerate()
+                .filter(|(_, (id, _))| !self.tombstones.contains(id))
+                .map(|(local_index, (&id, data))| {
+                    let boxes = &slot.boxes;
+                    let pos = local_index * 4;
+                    (
+                        id,
+                        boxes[pos],
+                        boxes[pos + 1],
+                        boxes[pos + 2],
+                        boxes[pos + 3],
+                        data.clone(),
+                    )
+                })
+                .collect();
+
+            if live.is_empty() {
+                *slot_opt = None;
+                continue;
+            }
+
+            *slot_opt = Some(build_slot(live));
+        }
+    }
+
+    fn flush_buffer(&mut self) {
+        let mut items: Vec<_> = self
+            .buffer
+            .drain(..)
+            .map(|item| (item.id, item.min_x, item.min_y, item.max_x, item.max_y, item.data))
+            .collect();
+
+        // Merge with every occupied consecutive low slot, like carrying a binary counter.
+        let mut slot_index = 0;
+        loop {
+            match self.slots.get_mut(slot_index) {
+                Some(slot @ Some(_)) => {
+                    let occupied = slot.take().unwrap();
+                    for (local_index, (id, data)) in
+                        occupied.ids.into_iter().zip(occupied.data).enumerate()
+                    {
+                        let boxes = &occupied.boxes;
+                        let pos = local_index * 4;
+                        items.push((
+                            id,
+                            boxes[pos],
+                            boxes[pos + 1],
+                            boxes[pos + 2],
+                            boxes[pos + 3],
+                            data,
+                        ));
+                    }
+                    slot_index += 1;
+                }
+                Some(None) => break,
+                None => {
+                    self.slots.push(None);
+                    break;
+                }
+            }
+        }
+
+        let mut new_slot = build_slot(items);
+        new_slot.live_count = new_slot
+            .ids
+            .iter()
+            .filter(|id| !self.tombstones.contains(*id))
+            .count();
+        self.slots[slot_index] = Some(new_slot);
+    }
+}
+
+fn build_slot<N: IndexableNum, D>(items: Vec<(u64, N, N, N, N, D)>) -> Slot<N, D> {
+    let mut builder = RTreeBuilder::<N>::new(items.len() as u32);
+    let mut ids = Vec::with_capacity(items.len());
+    let mut data = Vec::with_capacity(items.len());
+    let mut boxes = Vec::with_capacity(items.len() * 4);
+    for (id, min_x, min_y, max_x, max_y, item_data) in items {
+        builder.add(min_x, min_y, max_x, max_y);
+        ids.push(id);
+        data.push(item_data);
+        boxes.extend_from_slice(&[min_x, min_y, max_x, max_y]);
+    }
+    let tree = builder.finish::<HilbertSort>();
+    let live_count = ids.len();
+    Slot {
+        tree,
+        ids,
+        data,
+        boxes,
+        live_count,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_and_search_within_buffer() {
+        let mut tree = DynamicRTree::<f64>::new();
+        let id0 = tree.insert(0., 0., 1., 1., 0u64);
+        let id1 = tree.insert(5., 5., 6., 6., 1u64);
+        assert_eq!(tree.search(0., 0., 2., 2.), vec![id0]);
+        assert_eq!(tree.len(), 2);
+        let _ = id1;
+    }
+
+    #[test]
+    fn flushes_buffer_into_a_tree() {
+        let mut tree = DynamicRTree::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..100 {
+            let x = i as f64;
+            ids.push(tree.insert(x, x, x + 1., x + 1., i));
+        }
+        assert_eq!(tree.len(), 100);
+        let results = tree.search(0., 0., 3., 3.);
+        assert!(results.contains(&ids[0]));
+        assert!(results.contains(&ids[1]));
+        assert!(results.contains(&ids[2]));
+    }
+
+    #[test]
+    fn remove_is_logical_and_hides_results() {
+        let mut tree = DynamicRTree::<f64>::new();
+        let id0 = tree.insert(0., 0., 1., 1., "a");
+        tree.remove(id0);
+        assert_eq!(tree.search(0., 0., 1., 1.), Vec::<u64>::new());
+        assert_eq!(tree.len(), 0);
+    }
+
+    #[test]
+    fn compact_reclaims_tombstoned_slots() {
+        let mut tree = DynamicRTree::<f64>::new();
+        let mut ids = vec![];
+        for i in 0..64 {
+            let x = i as f64;
+            ids.push(tree.insert(x, x, x + 1., x + 1., i));
+        }
+        for &id in &ids[..40] {
+            tree.remove(id);
+        }
+        tree.compact();
+        assert_eq!(tree.len(), 24);
+        for &id in &ids[40..] {
+            assert!(tree.search(0., 0., 100., 100.).contains(&id));
+        }
+    }
+}
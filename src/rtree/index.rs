@@ -8,6 +8,65 @@ use crate::r#type::IndexableNum;
 use crate::rtree::constants::VERSION;
 use crate::rtree::util::compute_num_nodes;
 
+/// The version preceding the `u8` index tier, kept around so [`RTreeMetadata::from_slice`] can
+/// still decode buffers written before it, where indices were always `u16` or `u32`.
+const PRE_U8_TIER_VERSION: u8 = VERSION - 1;
+
+/// The size, in bytes, of the flatbush-ABI header preceding the boxes/indices regions.
+const HEADER_SIZE: usize = 8;
+
+/// Bit set in the version nibble (the upper 4 bits of header byte 1) to mark that an 8-byte xxh3
+/// checksum footer follows the indices region, for [`RTree::verify_checksum`]/
+/// [`RTreeRef::verify_checksum`] to detect buffer corruption.
+///
+/// Transparent to ordinary readers, unlike [`crate::compression::COMPRESSED_FLAG`]: the version is
+/// masked against this bit before being interpreted, and [`RTreeMetadata::data_buffer_length`]
+/// already accounts for the footer, so a checksummed buffer parses and queries normally for
+/// consumers who never call [`RTree::verify_checksum`].
+pub(crate) const CHECKSUM_FLAG: u8 = 0x20;
+
+/// Size, in bytes, of the xxh3 checksum footer appended when [`CHECKSUM_FLAG`] is set.
+pub(crate) const CHECKSUM_FOOTER_SIZE: usize = 8;
+
+/// Like [`bytemuck::try_cast_slice`], but maps the error to a [`GeoIndexError::Misaligned`].
+fn try_cast_slice<T: bytemuck::Pod, U: bytemuck::Pod>(region: &[T]) -> Result<&[U]> {
+    bytemuck::try_cast_slice(region)
+        .map_err(|err| GeoIndexError::Misaligned(format!("{err:?}")))
+}
+
+/// Slice `data[start..end]`, returning [`GeoIndexError::TooShort`] instead of panicking if `data`
+/// isn't long enough.
+fn checked_slice(data: &[u8], start: usize, end: usize) -> Result<&[u8]> {
+    data.get(start..end).ok_or(GeoIndexError::TooShort {
+        expected: end,
+        actual: data.len(),
+    })
+}
+
+/// The number of bytes used per index element, given the total number of nodes in the tree.
+///
+/// Small trees (`num_nodes < 256`) fit their indices in a `u8`, shrinking the index section
+/// further than the `u16`/`u32` split alone, which matters for the many-small-trees case and for
+/// WASM memory footprint.
+fn indices_bytes_per_element(num_nodes: usize) -> usize {
+    if num_nodes < 256 {
+        1
+    } else if num_nodes < 16384 {
+        2
+    } else {
+        4
+    }
+}
+
+/// The pre-v{VERSION} index width rule, where the `u8` tier didn't yet exist.
+fn legacy_indices_bytes_per_element(num_nodes: usize) -> usize {
+    if num_nodes < 16384 {
+        2
+    } else {
+        4
+    }
+}
+
 /// Common metadata to describe an RTree
 ///
 /// You can use the metadata to infer the total byte size of a tree given the provided criteria.
@@ -20,7 +79,9 @@ pub struct RTreeMetadata<N: IndexableNum> {
     level_bounds: Vec<usize>,
     pub(crate) nodes_byte_length: usize,
     pub(crate) indices_byte_length: usize,
+    pub(crate) indices_bytes_per_element: usize,
     phantom: PhantomData<N>,
+    checksummed: bool,
 }
 
 impl<N: IndexableNum> RTreeMetadata<N> {
@@ -30,7 +91,7 @@ impl<N: IndexableNum> RTreeMetadata<N> {
 
         let (num_nodes, level_bounds) = compute_num_nodes(num_items, node_size);
 
-        let indices_bytes_per_element = if num_nodes < 16384 { 2 } else { 4 };
+        let indices_bytes_per_element = indices_bytes_per_element(num_nodes);
         let nodes_byte_length = num_nodes * 4 * N::BYTES_PER_ELEMENT;
         let indices_byte_length = num_nodes * indices_bytes_per_element;
 
@@ -41,13 +102,65 @@ impl<N: IndexableNum> RTreeMetadata<N> {
             level_bounds,
             nodes_byte_length,
             indices_byte_length,
+            indices_bytes_per_element,
             phantom: PhantomData,
+            checksummed: false,
         }
     }
 
+    /// Construct metadata matching the [`PRE_U8_TIER_VERSION`] layout, where indices were always
+    /// `u16` or `u32`, for decoding buffers written before the `u8` tier was added.
+    fn new_legacy(num_items: u32, node_size: u16) -> Self {
+        assert!((2..=65535).contains(&node_size));
+
+        let (num_nodes, level_bounds) = compute_num_nodes(num_items, node_size);
+
+        let indices_bytes_per_element = legacy_indices_bytes_per_element(num_nodes);
+        let nodes_byte_length = num_nodes * 4 * N::BYTES_PER_ELEMENT;
+        let indices_byte_length = num_nodes * indices_bytes_per_element;
+
+        Self {
+            node_size,
+            num_items,
+            num_nodes,
+            level_bounds,
+            nodes_byte_length,
+            indices_byte_length,
+            indices_bytes_per_element,
+            phantom: PhantomData,
+            checksummed: false,
+        }
+    }
+
+    /// Mark this metadata as describing a buffer with an appended checksum footer, adjusting
+    /// [`Self::data_buffer_length`] accordingly. Used by [`Self::from_slice`] when parsing a
+    /// buffer written by [`RTree::to_checksummed`].
+    pub(crate) fn with_checksum(mut self) -> Self {
+        self.checksummed = true;
+        self
+    }
+
+    /// Whether this metadata describes a buffer with an appended xxh3 checksum footer.
+    pub fn has_checksum(&self) -> bool {
+        self.checksummed
+    }
+
     /// Construct a new [`RTreeMetadata`] from an existing byte slice conforming to the "flatbush
     /// ABI", such as what [`RTreeBuilder`] generates.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `data` is too short for its header, too short or too long for the tree
+    /// its header describes, or if a region can't be safely cast to its target numeric type
+    /// because of misalignment.
     pub fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() < HEADER_SIZE {
+            return Err(GeoIndexError::TooShort {
+                expected: HEADER_SIZE,
+                actual: data.len(),
+            });
+        }
+
         let magic = data[0];
         if magic != 0xfb {
             return Err(GeoIndexError::General(
@@ -56,8 +169,9 @@ impl<N: IndexableNum> RTreeMetadata<N> {
         }
 
         let version_and_type = data[1];
-        let version = version_and_type >> 4;
-        if version != VERSION {
+        let checksummed = version_and_type & CHECKSUM_FLAG != 0;
+        let version = (version_and_type & !CHECKSUM_FLAG) >> 4;
+        if version != VERSION && version != PRE_U8_TIER_VERSION {
             return Err(GeoIndexError::General(
                 format!("Got v{version} data when expected v{VERSION}.").to_string(),
             ));
@@ -75,18 +189,27 @@ impl<N: IndexableNum> RTreeMetadata<N> {
             ));
         }
 
-        let node_size: u16 = cast_slice(&data[2..4])[0];
-        let num_items: u32 = cast_slice(&data[4..8])[0];
-
-        let slf = Self::new(num_items, node_size);
-        if slf.data_buffer_length() != data.len() {
+        let node_size: u16 = try_cast_slice::<u8, u16>(&data[2..4])?[0];
+        let num_items: u32 = try_cast_slice::<u8, u32>(&data[4..8])?[0];
+        if !(2..=65535).contains(&node_size) {
             return Err(GeoIndexError::General(format!(
-                "Expected {} bytes but received byte slice with {} bytes",
-                slf.data_buffer_length(),
-                data.len()
+                "Invalid node size {node_size}; must be in 2..=65535."
             )));
         }
 
+        let slf = if version == PRE_U8_TIER_VERSION {
+            Self::new_legacy(num_items, node_size)
+        } else {
+            Self::new(num_items, node_size)
+        };
+        let slf = if checksummed { slf.with_checksum() } else { slf };
+        if slf.data_buffer_length() != data.len() {
+            return Err(GeoIndexError::LengthMismatch {
+                expected: slf.data_buffer_length(),
+                actual: data.len(),
+            });
+        }
+
         Ok(slf)
     }
 
@@ -122,7 +245,13 @@ impl<N: IndexableNum> RTreeMetadata<N> {
     /// assert_eq!(metadata.data_buffer_length(), 960_092);
     /// ```
     pub fn data_buffer_length(&self) -> usize {
-        8 + self.nodes_byte_length + self.indices_byte_length
+        8 + self.nodes_byte_length
+            + self.indices_byte_length
+            + if self.checksummed {
+                CHECKSUM_FOOTER_SIZE
+            } else {
+                0
+            }
     }
 
     /// Access the slice of boxes from the data buffer this metadata represents.
@@ -134,31 +263,117 @@ impl<N: IndexableNum> RTreeMetadata<N> {
     pub fn indices_slice<'a>(&self, data: &'a [u8]) -> Indices<'a> {
         let indices_buf = &data
             [8 + self.nodes_byte_length..8 + self.nodes_byte_length + self.indices_byte_length];
-        Indices::new(indices_buf, self.num_nodes)
+        match self.indices_bytes_per_element {
+            1 => Indices::U8(indices_buf),
+            2 => Indices::U16(cast_slice(indices_buf)),
+            _ => Indices::U32(cast_slice(indices_buf)),
+        }
+    }
+
+    /// Like [`boxes_slice`][Self::boxes_slice], but checks bounds and alignment instead of
+    /// trusting that `data` matches this metadata.
+    fn try_boxes_slice<'a>(&self, data: &'a [u8]) -> Result<&'a [N]> {
+        let boxes_buf = checked_slice(data, HEADER_SIZE, HEADER_SIZE + self.nodes_byte_length)?;
+        try_cast_slice(boxes_buf)
+    }
+
+    /// Like [`indices_slice`][Self::indices_slice], but checks bounds and alignment instead of
+    /// trusting that `data` matches this metadata.
+    fn try_indices_slice<'a>(&self, data: &'a [u8]) -> Result<Indices<'a>> {
+        let indices_buf = checked_slice(
+            data,
+            HEADER_SIZE + self.nodes_byte_length,
+            HEADER_SIZE + self.nodes_byte_length + self.indices_byte_length,
+        )?;
+        Ok(match self.indices_bytes_per_element {
+            1 => Indices::U8(indices_buf),
+            2 => Indices::U16(try_cast_slice(indices_buf)?),
+            _ => Indices::U32(try_cast_slice(indices_buf)?),
+        })
     }
 }
 
-/// An owned RTree buffer.
+/// An RTree buffer, generic over its backing storage.
 ///
-/// Usually this will be created from scratch via [`RTreeBuilder`][crate::rtree::RTreeBuilder].
+/// The default `B = Vec<u8>` (aliased as [`OwnedRTree`]) heap-allocates its own buffer, and is
+/// usually created from scratch via [`RTreeBuilder`][crate::rtree::RTreeBuilder].
+/// [`RTreeBuilder::from_metadata_in`][crate::rtree::RTreeBuilder::from_metadata_in] instead builds
+/// directly into a caller-provided `&mut [u8]` (backed by an `mmap`'d file or bump arena, say),
+/// avoiding a second full-size allocation when the index is ultimately persisted there.
 #[derive(Debug, Clone, PartialEq)]
-pub struct RTree<N: IndexableNum> {
-    pub(crate) buffer: Vec<u8>,
+pub struct RTree<N: IndexableNum, B: AsRef<[u8]> = Vec<u8>> {
+    pub(crate) buffer: B,
     pub(crate) metadata: RTreeMetadata<N>,
 }
 
-impl<N: IndexableNum> RTree<N> {
+/// An [`RTree`] that owns a heap-allocated `Vec<u8>` buffer.
+///
+/// This is the ordinary, default way to build and hold an `RTree`.
+pub type OwnedRTree<N> = RTree<N, Vec<u8>>;
+
+impl<N: IndexableNum, B: AsRef<[u8]>> RTree<N, B> {
     /// Access the underlying buffer of this RTree.
     ///
     /// This buffer can then be persisted and passed to `RTreeRef::try_new`.
-    pub fn into_inner(self) -> Vec<u8> {
+    pub fn into_inner(self) -> B {
         self.buffer
     }
+
+    /// Compress this tree's buffer under the given codec, for cheaper storage or transmission.
+    ///
+    /// The tree itself is never queried in compressed form: this only compresses the serialized
+    /// bytes, and only the region after the 8-byte header (boxes and indices), leaving the header
+    /// itself uncompressed and readable. Pass the result to [`RTree::from_compressed`] to recover
+    /// a normal, zero-copy tree.
+    #[cfg(feature = "compression")]
+    pub fn to_compressed(&self, compression: crate::compression::CompressionType) -> Vec<u8> {
+        crate::rtree::compression::compress(self, compression)
+    }
+
+    /// Append an 8-byte xxh3 checksum footer to this tree's buffer, returning a new buffer that
+    /// [`RTree::verify_checksum`]/[`RTreeRef::verify_checksum`] can use to detect corruption.
+    ///
+    /// The checksum covers the whole buffer (header, boxes, and indices) with [`CHECKSUM_FLAG`]
+    /// already set, so flipping any bit anywhere is caught. Unlike [`Self::to_compressed`], the
+    /// result is still a plain, directly queryable buffer: [`RTreeRef::try_new`] reads it the same
+    /// as an unchecksummed one, since [`RTreeMetadata::data_buffer_length`] already accounts for
+    /// the footer.
+    #[cfg(feature = "compression")]
+    pub fn to_checksummed(&self) -> Vec<u8> {
+        crate::rtree::checksum::append(self.as_ref())
+    }
+
+    /// Verify this tree's checksum footer, detecting buffer corruption.
+    ///
+    /// A no-op returning `Ok(())` if this tree has no checksum footer, i.e. wasn't produced via
+    /// [`Self::to_checksummed`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the footer's xxh3 checksum doesn't match the rest of the buffer.
+    #[cfg(feature = "compression")]
+    pub fn verify_checksum(&self) -> Result<()> {
+        crate::rtree::checksum::verify(self.as_ref(), &self.metadata)
+    }
 }
 
-impl<N: IndexableNum> AsRef<[u8]> for RTree<N> {
+impl<N: IndexableNum, B: AsRef<[u8]>> AsRef<[u8]> for RTree<N, B> {
     fn as_ref(&self) -> &[u8] {
-        &self.buffer
+        self.buffer.as_ref()
+    }
+}
+
+impl<N: IndexableNum> RTree<N, Vec<u8>> {
+    /// Reverse [`Self::to_compressed`], decompressing and checksum-verifying a compressed byte
+    /// stream back into a plain, zero-copy `RTree`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `data` isn't flagged as a compressed stream, if it fails to
+    /// decompress, or if the decompressed buffer fails its xxh3 checksum.
+    #[cfg(feature = "compression")]
+    pub fn from_compressed(data: &[u8]) -> Result<Self> {
+        crate::rtree::compression::decompress(data)
     }
 }
 
@@ -168,6 +383,7 @@ impl<N: IndexableNum> AsRef<[u8]> for RTree<N> {
 /// method, but it can also be created from any existing data buffer.
 #[derive(Debug, Clone, PartialEq)]
 pub struct RTreeRef<'a, N: IndexableNum> {
+    pub(crate) data: &'a [u8],
     pub(crate) boxes: &'a [N],
     pub(crate) indices: Indices<'a>,
     pub(crate) metadata: RTreeMetadata<N>,
@@ -183,16 +399,28 @@ impl<'a, N: IndexableNum> RTreeRef<'a, N> {
     pub fn try_new<T: AsRef<[u8]>>(data: &'a T) -> Result<Self> {
         let data = data.as_ref();
         let metadata = RTreeMetadata::from_slice(data)?;
-        let boxes = metadata.boxes_slice(data);
-        let indices = metadata.indices_slice(data);
+        let boxes = metadata.try_boxes_slice(data)?;
+        let indices = metadata.try_indices_slice(data)?;
 
         Ok(Self {
+            data,
             boxes,
             indices,
             metadata,
         })
     }
 
+    /// Like [`Self::try_new`], but also verifies the checksum footer if this buffer has one,
+    /// rejecting corrupt data up front instead of silently returning wrong query results.
+    ///
+    /// A buffer with no checksum footer passes through unchanged, identically to [`Self::try_new`].
+    #[cfg(feature = "compression")]
+    pub fn try_new_checked<T: AsRef<[u8]>>(data: &'a T) -> Result<Self> {
+        let slf = Self::try_new(data)?;
+        slf.verify_checksum()?;
+        Ok(slf)
+    }
+
     /// Construct a new RTreeRef without doing any validation
     ///
     /// # Safety
@@ -207,9 +435,23 @@ impl<'a, N: IndexableNum> RTreeRef<'a, N> {
         let indices = metadata.indices_slice(data);
 
         Ok(Self {
+            data,
             boxes,
             indices,
             metadata,
         })
     }
+
+    /// Verify this tree's checksum footer, detecting buffer corruption.
+    ///
+    /// A no-op returning `Ok(())` if this tree has no checksum footer, i.e. wasn't produced via
+    /// [`RTree::to_checksummed`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the footer's xxh3 checksum doesn't match the rest of the buffer.
+    #[cfg(feature = "compression")]
+    pub fn verify_checksum(&self) -> Result<()> {
+        crate::rtree::checksum::verify(self.data, &self.metadata)
+    }
 }
@@ -0,0 +1,198 @@
+//! An [`RTree`]-backed index over a column of geometries that may contain nulls or empty entries.
+//!
+//! When indexing a column of geometries, some entries are null or empty and get skipped during
+//! [`RTreeBuilder::add`], so the tree's internal indices no longer line up with the original row
+//! positions. [`IndexedGeometryArray`] stores the tree alongside a mapping from tree insertion
+//! index back to the original row, and offers query methods that transparently translate results
+//! back to original row indices.
+
+use geo_0_31::algorithm::{BoundingRect, Contains, Intersects};
+use geo_0_31::Geometry;
+
+use crate::rtree::{RTree, RTreeBuilder, RTreeIndex};
+use crate::rtree::sort::HilbertSort;
+
+/// An adapter pairing an [`RTree`] with a mapping from tree-internal insertion indices back to
+/// the original row positions of the geometry column that was indexed.
+///
+/// ```
+/// use geo_index::rtree::IndexedGeometryArray;
+/// use geo_0_31::{Geometry, Point};
+///
+/// let geometries: Vec<Option<Geometry<f64>>> = vec![
+///     Some(Geometry::Point(Point::new(0., 0.))),
+///     None, // null row, skipped
+///     Some(Geometry::Point(Point::new(5., 5.))),
+/// ];
+///
+/// let index = IndexedGeometryArray::try_from_geometries(&geometries).unwrap();
+/// // Row 2 is found even though only 2 of the 3 rows were actually inserted into the tree.
+/// assert_eq!(index.search(4., 4., 6., 6.), vec![2]);
+/// ```
+pub struct IndexedGeometryArray {
+    tree: RTree<f64>,
+    /// Maps tree insertion index -> original row index.
+    original_row: Vec<u32>,
+}
+
+impl IndexedGeometryArray {
+    /// Build an index over a slice of optional geometries, skipping null and empty entries.
+    ///
+    /// Returns `None` if every entry is null or empty (no bounding boxes to index).
+    pub fn try_from_geometries(geometries: &[Option<Geometry<f64>>]) -> Option<Self> {
+        let mut original_row = Vec::new();
+        let mut boxes = Vec::new();
+        for (row, geom) in geometries.iter().enumerate() {
+            let Some(geom) = geom else { continue };
+            let Some(rect) = geom.bounding_rect() else {
+                // Empty geometry (e.g. an empty MultiPoint); nothing to index.
+                continue;
+            };
+            boxes.push((rect.min().x, rect.min().y, rect.max().x, rect.max().y));
+            original_row.push(row as u32);
+        }
+
+        if boxes.is_empty() {
+            return None;
+        }
+
+        let mut builder = RTreeBuilder::<f64>::new(boxes.len() as u32);
+        for (min_x, min_y, max_x, max_y) in boxes {
+            builder.add(min_x, min_y, max_x, max_y);
+        }
+        let tree = builder.finish::<HilbertSort>();
+
+        Some(Self { tree, original_row })
+    }
+
+    /// The underlying tree, indexed by insertion position rather than original row.
+    pub fn tree(&self) -> &RTree<f64> {
+        &self.tree
+    }
+
+    fn to_original_rows(&self, tree_indices: impl IntoIterator<Item = u32>) -> Vec<u32> {
+        tree_indices
+            .into_iter()
+            .map(|i| self.original_row[i as usize])
+            .collect()
+    }
+
+    /// Search for original row indices whose geometry's bounding box intersects the query
+    /// rectangle.
+    pub fn search(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Vec<u32> {
+        self.to_original_rows(self.tree.search(min_x, min_y, max_x, max_y))
+    }
+
+    /// Return pairs of original row indices whose bounding boxes intersect between this index and
+    /// another.
+    pub fn intersection_candidates_with_other_tree(
+        &self,
+        other: &Self,
+    ) -> Vec<(u32, u32)> {
+        self.tree
+            .intersection_candidates_with_other_tree(&other.tree)
+            .map(|(left, right)| {
+                (
+                    self.original_row[left as usize],
+                    other.original_row[right as usize],
+                )
+            })
+            .collect()
+    }
+
+    /// Return the original row indices of the `max_results` closest items to the query point.
+    pub fn neighbors(
+        &self,
+        x: f64,
+        y: f64,
+        max_results: Option<usize>,
+        max_distance: Option<f64>,
+    ) -> Vec<u32> {
+        self.to_original_rows(self.tree.neighbors(x, y, max_results, max_distance))
+    }
+
+    /// Refine a set of bounding-box candidate rows (as returned by [`Self::search`]) against the
+    /// real geometries using an exact predicate, returning a boolean mask over `geometries` (one
+    /// entry per original row) indicating confirmed matches.
+    ///
+    /// `predicate` receives the candidate row's geometry and should return whether it truly
+    /// intersects/contains the query.
+    pub fn refine(
+        &self,
+        geometries: &[Option<Geometry<f64>>],
+        candidate_rows: &[u32],
+        predicate: impl Fn(&Geometry<f64>) -> bool,
+    ) -> Vec<bool> {
+        let mut mask = vec![false; geometries.len()];
+        for &row in candidate_rows {
+            if let Some(Some(geom)) = geometries.get(row as usize) {
+                if predicate(geom) {
+                    mask[row as usize] = true;
+                }
+            }
+        }
+        mask
+    }
+
+    /// Refine bounding-box candidates by testing whether each candidate geometry intersects
+    /// `query`.
+    pub fn refine_intersects(
+        &self,
+        geometries: &[Option<Geometry<f64>>],
+        candidate_rows: &[u32],
+        query: &Geometry<f64>,
+    ) -> Vec<bool> {
+        self.refine(geometries, candidate_rows, |geom| geom.intersects(query))
+    }
+
+    /// Refine bounding-box candidates by testing whether each candidate geometry contains `query`.
+    pub fn refine_contains(
+        &self,
+        geometries: &[Option<Geometry<f64>>],
+        candidate_rows: &[u32],
+        query: &Geometry<f64>,
+    ) -> Vec<bool> {
+        self.refine(geometries, candidate_rows, |geom| geom.contains(query))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use geo_0_31::{Point, Polygon};
+
+    fn square(min: f64, max: f64) -> Geometry<f64> {
+        Geometry::Polygon(Polygon::new(
+            geo_0_31::LineString::from(vec![
+                (min, min),
+                (max, min),
+                (max, max),
+                (min, max),
+                (min, min),
+            ]),
+            vec![],
+        ))
+    }
+
+    #[test]
+    fn skips_null_rows_and_maps_back_to_original_index() {
+        let geometries: Vec<Option<Geometry<f64>>> = vec![
+            Some(Geometry::Point(Point::new(0., 0.))),
+            None,
+            Some(Geometry::Point(Point::new(5., 5.))),
+        ];
+        let index = IndexedGeometryArray::try_from_geometries(&geometries).unwrap();
+        assert_eq!(index.search(4., 4., 6., 6.), vec![2]);
+    }
+
+    #[test]
+    fn refine_intersects_filters_false_positives() {
+        let geometries: Vec<Option<Geometry<f64>>> =
+            vec![Some(square(0., 2.)), Some(square(10., 12.))];
+        let index = IndexedGeometryArray::try_from_geometries(&geometries).unwrap();
+        let query = Geometry::Point(Point::new(1., 1.));
+        let candidates = index.search(1., 1., 1., 1.);
+        let mask = index.refine_intersects(&geometries, &candidates, &query);
+        assert_eq!(mask, vec![true, false]);
+    }
+}
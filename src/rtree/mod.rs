@@ -56,15 +56,32 @@
 
 mod builder;
 mod constants;
+#[cfg(feature = "compression")]
+mod checksum;
+#[cfg(feature = "compression")]
+mod compression;
 pub mod distance;
+mod dynamic;
 mod index;
+#[cfg(feature = "use-geo_0_31")]
+mod indexed_geometry_array;
 pub mod sort;
 mod r#trait;
 mod traversal;
 pub mod util;
 
 pub use builder::{RTreeBuilder, DEFAULT_RTREE_NODE_SIZE};
-pub use distance::{DistanceMetric, EuclideanDistance, HaversineDistance, SpheroidDistance};
-pub use index::{RTree, RTreeMetadata, RTreeRef};
-pub use r#trait::RTreeIndex;
-pub use traversal::Node;
+pub use distance::{
+    DistanceMetric, EuclideanDistance, FrechetDistance, GeodesicDistance, HausdorffDistance,
+    HaversineDistance, RhumbDistance, SpheroidDistance,
+};
+pub use dynamic::DynamicRTree;
+pub use index::{OwnedRTree, RTree, RTreeMetadata, RTreeRef};
+#[cfg(feature = "use-geo_0_31")]
+pub use indexed_geometry_array::IndexedGeometryArray;
+pub use r#trait::{ItemRange, NeighborsIter, RTreeIndex};
+// Re-exported `pub(crate)` (rather than `pub`, like the rest of this module) so that
+// `crate::kdtree` can share this geo-independent metric trait for its own `_metric` query
+// variants without making it part of the public API surface of `rtree`.
+pub(crate) use r#trait::SimpleDistanceMetric;
+pub use traversal::{Node, SpatialPredicate};
@@ -0,0 +1,98 @@
+use std::marker::PhantomData;
+
+use crate::indices::MutableIndices;
+use crate::r#type::IndexableNum;
+#[cfg(feature = "rayon")]
+use crate::rtree::sort::util::sort_parallel;
+use crate::rtree::sort::util::sort;
+use crate::rtree::sort::{Sort, SortParams};
+
+/// A user-supplied sort key derivation, plugged into [`ComparatorSort`].
+///
+/// Implement this on your own zero-sized marker type to derive a box's sort key however you
+/// like — a Z-order/Hilbert variant, a priority-R-tree key, or a domain-specific ordering —
+/// without reimplementing the partial-sort plumbing that [`HilbertSort`][crate::rtree::sort::HilbertSort]/
+/// [`ZOrderSort`][crate::rtree::sort::ZOrderSort]/[`STRSort`][crate::rtree::sort::STRSort] each
+/// hardcode their own fixed key formula for.
+pub trait BoxComparator<N: IndexableNum> {
+    /// The sort key type; boxes are ordered by comparing this.
+    type Key: Ord + Copy + Send;
+
+    /// Derive the sort key for a box, given as `[min_x, min_y, max_x, max_y]`.
+    fn key(sort_params: &SortParams<N>, box_: [N; 4]) -> Self::Key;
+}
+
+/// A [`Sort`] strategy whose key is derived from each box by a user-supplied [`BoxComparator`]
+/// `C`, rather than a fixed formula like [`HilbertSort`][crate::rtree::sort::HilbertSort]/
+/// [`ZOrderSort`][crate::rtree::sort::ZOrderSort]'s curve position or
+/// [`STRSort`][crate::rtree::sort::STRSort]'s axis center.
+///
+/// ```
+/// use geo_index::rtree::sort::{BoxComparator, ComparatorSort, SortParams};
+/// use geo_index::rtree::{RTreeBuilder, RTreeIndex};
+///
+/// /// Sort purely by `min_x`.
+/// struct ByMinX;
+///
+/// impl BoxComparator<f64> for ByMinX {
+///     type Key = i64;
+///
+///     fn key(_sort_params: &SortParams<f64>, box_: [f64; 4]) -> Self::Key {
+///         (box_[0] * 1000.).round() as i64
+///     }
+/// }
+///
+/// let mut builder = RTreeBuilder::<f64>::new(3);
+/// builder.add(2., 0., 3., 1.);
+/// builder.add(0., 0., 1., 1.);
+/// builder.add(1., 0., 2., 1.);
+/// let tree = builder.finish::<ComparatorSort<ByMinX>>();
+/// // Three items fit in one node of the default size (16), so they aren't reordered and the
+/// // search returns them in insertion order.
+/// assert_eq!(tree.search(0., 0., 3., 1.), vec![0, 1, 2]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ComparatorSort<C>(PhantomData<C>);
+
+impl<N: IndexableNum, C: BoxComparator<N>> Sort<N> for ComparatorSort<C> {
+    fn sort(params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices) {
+        let mut keys = comparator_keys::<N, C>(params, boxes);
+
+        // sort items by their comparator key (for packing later)
+        sort(
+            &mut keys,
+            boxes,
+            indices,
+            0,
+            params.num_items - 1,
+            params.node_size,
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    fn sort_parallel(params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices) {
+        let mut keys = comparator_keys::<N, C>(params, boxes);
+        sort_parallel(&mut keys, boxes, indices, 0, params.node_size);
+    }
+}
+
+/// Compute each item's comparator key, in insertion order.
+fn comparator_keys<N: IndexableNum, C: BoxComparator<N>>(
+    params: &SortParams<N>,
+    boxes: &[N],
+) -> Vec<C::Key> {
+    let mut keys = Vec::with_capacity(params.num_items);
+
+    let mut pos = 0;
+    for _ in 0..params.num_items {
+        let min_x = boxes[pos];
+        let min_y = boxes[pos + 1];
+        let max_x = boxes[pos + 2];
+        let max_y = boxes[pos + 3];
+        pos += 4;
+
+        keys.push(C::key(params, [min_x, min_y, max_x, max_y]));
+    }
+
+    keys
+}
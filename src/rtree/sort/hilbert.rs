@@ -1,6 +1,8 @@
 use crate::indices::MutableIndices;
 use crate::r#type::IndexableNum;
-use crate::rtree::sort::util::swap;
+#[cfg(feature = "rayon")]
+use crate::rtree::sort::util::sort_parallel;
+use crate::rtree::sort::util::{interleave, sort};
 use crate::rtree::sort::{Sort, SortParams};
 
 /// An implementation of hilbert sorting.
@@ -13,35 +15,7 @@ pub struct HilbertSort;
 
 impl<N: IndexableNum> Sort<N> for HilbertSort {
     fn sort(params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices) {
-        let width = params.max_x - params.min_x; // || 1.0;
-        let height = params.max_y - params.min_y; // || 1.0;
-        let mut hilbert_values: Vec<u32> = Vec::with_capacity(params.num_items);
-        let hilbert_max = ((1 << 16) - 1) as f64;
-
-        {
-            // map item centers into Hilbert coordinate space and calculate Hilbert values
-            let mut pos = 0;
-            for _ in 0..params.num_items {
-                let min_x = boxes[pos];
-                pos += 1;
-                let min_y = boxes[pos];
-                pos += 1;
-                let max_x = boxes[pos];
-                pos += 1;
-                let max_y = boxes[pos];
-                pos += 1;
-
-                let x = (hilbert_max
-                    * ((min_x + max_x).to_f64().unwrap() / 2. - params.min_x.to_f64().unwrap())
-                    / width.to_f64().unwrap())
-                .floor() as u32;
-                let y = (hilbert_max
-                    * ((min_y + max_y).to_f64().unwrap() / 2. - params.min_y.to_f64().unwrap())
-                    / height.to_f64().unwrap())
-                .floor() as u32;
-                hilbert_values.push(hilbert(x, y));
-            }
-        }
+        let mut hilbert_values = hilbert_values(params, boxes);
 
         // sort items by their Hilbert value (for packing later)
         sort(
@@ -53,67 +27,43 @@ impl<N: IndexableNum> Sort<N> for HilbertSort {
             params.node_size,
         );
     }
-}
-
-/// Custom quicksort that partially sorts bbox data alongside the hilbert values.
-// Partially taken from static_aabb2d_index under the MIT/Apache license
-fn sort<N: IndexableNum>(
-    values: &mut [u32],
-    boxes: &mut [N],
-    indices: &mut MutableIndices,
-    left: usize,
-    right: usize,
-    node_size: usize,
-) {
-    debug_assert!(left <= right);
 
-    if left / node_size >= right / node_size {
-        return;
+    #[cfg(feature = "rayon")]
+    fn sort_parallel(params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices) {
+        let mut hilbert_values = hilbert_values(params, boxes);
+        sort_parallel(&mut hilbert_values, boxes, indices, 0, params.node_size);
     }
+}
 
-    // apply median of three method
-    let start = values[left];
-    let mid = values[(left + right) >> 1];
-    let end = values[right];
-
-    let x = start.max(mid);
-    let pivot = if end > x {
-        x
-    } else if x == start {
-        mid.max(end)
-    } else if x == mid {
-        start.max(end)
-    } else {
-        end
-    };
-
-    let mut i = left.wrapping_sub(1);
-    let mut j = right.wrapping_add(1);
-
-    loop {
-        loop {
-            i = i.wrapping_add(1);
-            if values[i] >= pivot {
-                break;
-            }
-        }
-
-        loop {
-            j = j.wrapping_sub(1);
-            if values[j] <= pivot {
-                break;
-            }
-        }
-
-        if i >= j {
-            break;
-        }
-
-        swap(values, boxes, indices, i, j);
+/// Map item centers into Hilbert coordinate space and calculate their Hilbert values.
+fn hilbert_values<N: IndexableNum>(params: &SortParams<N>, boxes: &[N]) -> Vec<u32> {
+    let width = params.max_x - params.min_x; // || 1.0;
+    let height = params.max_y - params.min_y; // || 1.0;
+    let mut hilbert_values: Vec<u32> = Vec::with_capacity(params.num_items);
+    let hilbert_max = ((1 << 16) - 1) as f64;
+
+    let mut pos = 0;
+    for _ in 0..params.num_items {
+        let min_x = boxes[pos];
+        pos += 1;
+        let min_y = boxes[pos];
+        pos += 1;
+        let max_x = boxes[pos];
+        pos += 1;
+        let max_y = boxes[pos];
+        pos += 1;
+
+        let x = (hilbert_max
+            * ((min_x + max_x).to_f64().unwrap() / 2. - params.min_x.to_f64().unwrap())
+            / width.to_f64().unwrap())
+        .floor() as u32;
+        let y = (hilbert_max
+            * ((min_y + max_y).to_f64().unwrap() / 2. - params.min_y.to_f64().unwrap())
+            / height.to_f64().unwrap())
+        .floor() as u32;
+        hilbert_values.push(hilbert(x, y));
     }
-
-    sort(values, boxes, indices, left, j, node_size);
-    sort(values, boxes, indices, j.wrapping_add(1), right, node_size);
+    hilbert_values
 }
 
 // Taken from static_aabb2d_index under the mit/apache license
@@ -160,18 +110,8 @@ fn hilbert(x: u32, y: u32) -> u32 {
     a_1 = c_2 ^ (c_2 >> 1);
     b_1 = d_2 ^ (d_2 >> 1);
 
-    let mut i0 = x ^ y;
-    let mut i1 = b_1 | (0xFFFF ^ (i0 | a_1));
-
-    i0 = (i0 | (i0 << 8)) & 0x00FF_00FF;
-    i0 = (i0 | (i0 << 4)) & 0x0F0F_0F0F;
-    i0 = (i0 | (i0 << 2)) & 0x3333_3333;
-    i0 = (i0 | (i0 << 1)) & 0x5555_5555;
-
-    i1 = (i1 | (i1 << 8)) & 0x00FF_00FF;
-    i1 = (i1 | (i1 << 4)) & 0x0F0F_0F0F;
-    i1 = (i1 | (i1 << 2)) & 0x3333_3333;
-    i1 = (i1 | (i1 << 1)) & 0x5555_5555;
+    let i0 = x ^ y;
+    let i1 = b_1 | (0xFFFF ^ (i0 | a_1));
 
-    (i1 << 1) | i0
+    (interleave(i1 as u16) << 1) | interleave(i0 as u16)
 }
@@ -0,0 +1,141 @@
+use crate::indices::MutableIndices;
+use crate::r#type::IndexableNum;
+use crate::rtree::sort::util::sort;
+#[cfg(feature = "rayon")]
+use crate::rtree::sort::util::sort_parallel;
+use crate::rtree::sort::{Sort, SortParams};
+
+/// A 32-bit-precision variant of [`HilbertSort`][crate::rtree::sort::HilbertSort].
+///
+/// `HilbertSort` quantizes item centers to a 16-bit grid before computing the curve value, which
+/// is plenty for most datasets but collapses many distinct items onto identical Hilbert keys for
+/// high-dynamic-range data, such as continental-scale coordinates at meter precision or an
+/// integer coordinate grid wider than `2^16`. `HilbertSort32` instead quantizes to 32 bits per
+/// axis and packs a `u64` Hilbert key, preserving query locality for such datasets at the cost of
+/// a somewhat more expensive build.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HilbertSort32;
+
+impl<N: IndexableNum> Sort<N> for HilbertSort32 {
+    fn sort(params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices) {
+        let mut hilbert_values = hilbert_values(params, boxes);
+
+        // sort items by their Hilbert value (for packing later)
+        sort(
+            &mut hilbert_values,
+            boxes,
+            indices,
+            0,
+            params.num_items - 1,
+            params.node_size,
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    fn sort_parallel(params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices) {
+        let mut hilbert_values = hilbert_values(params, boxes);
+        sort_parallel(&mut hilbert_values, boxes, indices, 0, params.node_size);
+    }
+}
+
+/// Map item centers into 32-bit Hilbert coordinate space and calculate their Hilbert values.
+fn hilbert_values<N: IndexableNum>(params: &SortParams<N>, boxes: &[N]) -> Vec<u64> {
+    let width = params.max_x - params.min_x;
+    let height = params.max_y - params.min_y;
+    let mut hilbert_values: Vec<u64> = Vec::with_capacity(params.num_items);
+    let hilbert_max = u32::MAX as f64;
+
+    let mut pos = 0;
+    for _ in 0..params.num_items {
+        let min_x = boxes[pos];
+        pos += 1;
+        let min_y = boxes[pos];
+        pos += 1;
+        let max_x = boxes[pos];
+        pos += 1;
+        let max_y = boxes[pos];
+        pos += 1;
+
+        let x = (hilbert_max
+            * ((min_x + max_x).to_f64().unwrap() / 2. - params.min_x.to_f64().unwrap())
+            / width.to_f64().unwrap())
+        .floor() as u32;
+        let y = (hilbert_max
+            * ((min_y + max_y).to_f64().unwrap() / 2. - params.min_y.to_f64().unwrap())
+            / height.to_f64().unwrap())
+        .floor() as u32;
+        hilbert_values.push(hilbert(x, y));
+    }
+    hilbert_values
+}
+
+/// A 32-bit-per-lane generalization of the Gray-code bit-twiddling behind
+/// [`HilbertSort`][crate::rtree::sort::HilbertSort], extending the folding cascade from shifts of
+/// 8/4/2/1 up through 16 and packing the result into a `u64` key via a 32-to-64-bit bit
+/// interleave.
+#[inline]
+fn hilbert(x: u32, y: u32) -> u64 {
+    let mut a_1 = x ^ y;
+    let mut b_1 = u32::MAX ^ a_1;
+    let mut c_1 = u32::MAX ^ (x | y);
+    let mut d_1 = x & (y ^ u32::MAX);
+
+    let mut a_2 = a_1 | (b_1 >> 1);
+    let mut b_2 = (a_1 >> 1) ^ a_1;
+    let mut c_2 = ((c_1 >> 1) ^ (b_1 & (d_1 >> 1))) ^ c_1;
+    let mut d_2 = ((a_1 & (c_1 >> 1)) ^ (d_1 >> 1)) ^ d_1;
+
+    a_1 = a_2;
+    b_1 = b_2;
+    c_1 = c_2;
+    d_1 = d_2;
+    a_2 = (a_1 & (a_1 >> 2)) ^ (b_1 & (b_1 >> 2));
+    b_2 = (a_1 & (b_1 >> 2)) ^ (b_1 & ((a_1 ^ b_1) >> 2));
+    c_2 ^= (a_1 & (c_1 >> 2)) ^ (b_1 & (d_1 >> 2));
+    d_2 ^= (b_1 & (c_1 >> 2)) ^ ((a_1 ^ b_1) & (d_1 >> 2));
+
+    a_1 = a_2;
+    b_1 = b_2;
+    c_1 = c_2;
+    d_1 = d_2;
+    a_2 = (a_1 & (a_1 >> 4)) ^ (b_1 & (b_1 >> 4));
+    b_2 = (a_1 & (b_1 >> 4)) ^ (b_1 & ((a_1 ^ b_1) >> 4));
+    c_2 ^= (a_1 & (c_1 >> 4)) ^ (b_1 & (d_1 >> 4));
+    d_2 ^= (b_1 & (c_1 >> 4)) ^ ((a_1 ^ b_1) & (d_1 >> 4));
+
+    a_1 = a_2;
+    b_1 = b_2;
+    c_1 = c_2;
+    d_1 = d_2;
+    a_2 = (a_1 & (a_1 >> 8)) ^ (b_1 & (b_1 >> 8));
+    b_2 = (a_1 & (b_1 >> 8)) ^ (b_1 & ((a_1 ^ b_1) >> 8));
+    c_2 ^= (a_1 & (c_1 >> 8)) ^ (b_1 & (d_1 >> 8));
+    d_2 ^= (b_1 & (c_1 >> 8)) ^ ((a_1 ^ b_1) & (d_1 >> 8));
+
+    a_1 = a_2;
+    b_1 = b_2;
+    c_1 = c_2;
+    d_1 = d_2;
+    c_2 ^= (a_1 & (c_1 >> 16)) ^ (b_1 & (d_1 >> 16));
+    d_2 ^= (b_1 & (c_1 >> 16)) ^ ((a_1 ^ b_1) & (d_1 >> 16));
+
+    a_1 = c_2 ^ (c_2 >> 1);
+    b_1 = d_2 ^ (d_2 >> 1);
+
+    let i0 = x ^ y;
+    let i1 = b_1 | (u32::MAX ^ (i0 | a_1));
+
+    (interleave(i1) << 1) | interleave(i0)
+}
+
+/// Spread the bits of a 32-bit value across a `u64` so that a zero bit follows each original bit.
+#[inline]
+fn interleave(v: u32) -> u64 {
+    let mut v = v as u64;
+    v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+    v
+}
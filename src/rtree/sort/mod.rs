@@ -1,10 +1,17 @@
 //! Sorting implementations for immutable RTrees.
 
+mod comparator;
 mod hilbert;
+mod hilbert32;
+mod hilbert_nd;
 mod str;
 mod r#trait;
 mod util;
+mod z_order;
 
+pub use comparator::{BoxComparator, ComparatorSort};
 pub use hilbert::HilbertSort;
+pub use hilbert32::HilbertSort32;
 pub use r#str::STRSort;
 pub use r#trait::{Sort, SortParams};
+pub use z_order::ZOrderSort;
@@ -29,4 +29,15 @@ pub trait Sort<N: IndexableNum> {
     ///   swapping the first box with the second box, you must also swap the first index with the
     ///   second index.
     fn sort(sort_params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices);
+
+    /// Like [`Self::sort`], but permitted to sort using multiple threads via `rayon`.
+    ///
+    /// The default implementation just calls [`Self::sort`]. Implementors that can partition
+    /// their sort key into disjoint subranges (as [`HilbertSort`][crate::rtree::sort::HilbertSort]
+    /// does) should override this to recurse with `rayon::join`, used by
+    /// [`RTreeBuilder::finish_parallel`][crate::rtree::RTreeBuilder::finish_parallel].
+    #[cfg(feature = "rayon")]
+    fn sort_parallel(sort_params: &mut SortParams<N>, boxes: &mut [N], indices: &mut MutableIndices) {
+        Self::sort(sort_params, boxes, indices)
+    }
 }
@@ -2,8 +2,13 @@ use crate::indices::MutableIndices;
 use crate::IndexableNum;
 
 /// Swap two values and two corresponding boxes.
+///
+/// `V` is the sort key type (`u32` for [`HilbertSort`][crate::rtree::sort::HilbertSort]/
+/// [`ZOrderSort`][crate::rtree::sort::ZOrderSort], `u64` for
+/// [`HilbertSort32`][crate::rtree::sort::HilbertSort32]); it's otherwise unconstrained since
+/// swapping a slice doesn't need any numeric properties of the key.
 #[inline]
-pub(super) fn swap<V: IndexableNum, N: IndexableNum>(
+pub(super) fn swap<V, N: IndexableNum>(
     values: &mut [V],
     boxes: &mut [N],
     indices: &mut MutableIndices,
@@ -21,3 +26,184 @@ pub(super) fn swap<V: IndexableNum, N: IndexableNum>(
 
     indices.swap(i, j);
 }
+
+/// Spread the bits of a 16-bit value so that a zero bit follows each original bit, the "dilate"
+/// step shared by both [`HilbertSort`][crate::rtree::sort::HilbertSort] and
+/// [`ZOrderSort`][crate::rtree::sort::ZOrderSort]'s bit-interleaving.
+#[inline]
+pub(super) fn interleave(v: u16) -> u32 {
+    let mut v = v as u32;
+    v = (v | (v << 8)) & 0x00FF_00FF;
+    v = (v | (v << 4)) & 0x0F0F_0F0F;
+    v = (v | (v << 2)) & 0x3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555;
+    v
+}
+
+/// Custom quicksort that partially sorts bbox data alongside a sort key (a `u32` Hilbert/Morton
+/// value, or a `u64` [`HilbertSort32`][crate::rtree::sort::HilbertSort32] value).
+// Partially taken from static_aabb2d_index under the MIT/Apache license
+pub(super) fn sort<V: Ord + Copy, N: IndexableNum>(
+    values: &mut [V],
+    boxes: &mut [N],
+    indices: &mut MutableIndices,
+    left: usize,
+    right: usize,
+    node_size: usize,
+) {
+    debug_assert!(left <= right);
+
+    if left / node_size >= right / node_size {
+        return;
+    }
+
+    // apply median of three method
+    let start = values[left];
+    let mid = values[(left + right) >> 1];
+    let end = values[right];
+
+    let x = start.max(mid);
+    let pivot = if end > x {
+        x
+    } else if x == start {
+        mid.max(end)
+    } else if x == mid {
+        start.max(end)
+    } else {
+        end
+    };
+
+    let mut i = left.wrapping_sub(1);
+    let mut j = right.wrapping_add(1);
+
+    loop {
+        loop {
+            i = i.wrapping_add(1);
+            if values[i] >= pivot {
+                break;
+            }
+        }
+
+        loop {
+            j = j.wrapping_sub(1);
+            if values[j] <= pivot {
+                break;
+            }
+        }
+
+        if i >= j {
+            break;
+        }
+
+        swap(values, boxes, indices, i, j);
+    }
+
+    sort(values, boxes, indices, left, j, node_size);
+    sort(values, boxes, indices, j.wrapping_add(1), right, node_size);
+}
+
+/// Tunable threshold below which [`sort_parallel`] falls back to sequential recursion rather
+/// than spawning a `rayon` task, to avoid paying task overhead on small subranges.
+#[cfg(feature = "rayon")]
+const PARALLEL_SORT_THRESHOLD: usize = 10_000;
+
+/// Like [`sort`], but splits the two recursive calls across threads via `rayon::join` once a
+/// subrange exceeds [`PARALLEL_SORT_THRESHOLD`] items.
+///
+/// `sort` indexes into the full buffer with absolute `left`/`right` bounds, which two
+/// concurrently-running recursive calls can't safely share as two `&mut` borrows of the same
+/// slice. This instead always receives `values`/`boxes`/`indices` already sliced down to exactly
+/// the range being sorted, so each half of the partition can be physically split into disjoint
+/// mutable subslices and recursed into concurrently. `offset` tracks the absolute position of
+/// `values[0]` in the full dataset, purely so the node-bucket check below still sees true
+/// absolute positions regardless of how many times the data has already been split.
+#[cfg(feature = "rayon")]
+pub(super) fn sort_parallel<V: Ord + Copy + Send, N: IndexableNum>(
+    values: &mut [V],
+    boxes: &mut [N],
+    indices: &mut MutableIndices,
+    offset: usize,
+    node_size: usize,
+) {
+    let len = values.len();
+    if len == 0 {
+        return;
+    }
+    let right = len - 1;
+
+    let abs_left = offset;
+    let abs_right = offset + right;
+    if abs_left / node_size >= abs_right / node_size {
+        return;
+    }
+
+    // apply median of three method
+    let start = values[0];
+    let mid = values[right >> 1];
+    let end = values[right];
+
+    let x = start.max(mid);
+    let pivot = if end > x {
+        x
+    } else if x == start {
+        mid.max(end)
+    } else if x == mid {
+        start.max(end)
+    } else {
+        end
+    };
+
+    let mut i = 0usize.wrapping_sub(1);
+    let mut j = right.wrapping_add(1);
+
+    loop {
+        loop {
+            i = i.wrapping_add(1);
+            if values[i] >= pivot {
+                break;
+            }
+        }
+
+        loop {
+            j = j.wrapping_sub(1);
+            if values[j] <= pivot {
+                break;
+            }
+        }
+
+        if i >= j {
+            break;
+        }
+
+        swap(values, boxes, indices, i, j);
+    }
+
+    let (left_values, right_values) = values.split_at_mut(j + 1);
+    let (left_boxes, right_boxes) = boxes.split_at_mut(4 * (j + 1));
+    let (mut left_indices, mut right_indices) = indices.split_at_mut(j + 1);
+    let right_offset = offset + j + 1;
+
+    if len > PARALLEL_SORT_THRESHOLD {
+        rayon::join(
+            || sort_parallel(left_values, left_boxes, &mut left_indices, offset, node_size),
+            || {
+                sort_parallel(
+                    right_values,
+                    right_boxes,
+                    &mut right_indices,
+                    right_offset,
+                    node_size,
+                )
+            },
+        );
+    } else {
+        sort_parallel(left_values, left_boxes, &mut left_indices, offset, node_size);
+        sort_parallel(
+            right_values,
+            right_boxes,
+            &mut right_indices,
+            right_offset,
+            node_size,
+        );
+    }
+}
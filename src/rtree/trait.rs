@@ -1,5 +1,6 @@
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, VecDeque};
+use std::ops::ControlFlow;
 use std::vec;
 
 #[cfg(feature = "use-geo_0_31")]
@@ -14,7 +15,7 @@ use crate::r#type::IndexableNum;
 #[cfg(feature = "use-geo_0_31")]
 use crate::rtree::distance::DistanceMetric;
 use crate::rtree::index::{RTree, RTreeRef};
-use crate::rtree::traversal::{IntersectionIterator, Node};
+use crate::rtree::traversal::{IntersectionIterator, Node, SpatialJoinIterator, SpatialPredicate};
 use crate::rtree::util::upper_bound;
 use crate::rtree::RTreeMetadata;
 use crate::GeoIndexError;
@@ -33,6 +34,33 @@ pub trait SimpleDistanceMetric<N: IndexableNum> {
     fn max_distance(&self) -> N {
         N::max_value()
     }
+
+    /// A value usable to *order* the distance between two points, monotonic with
+    /// [`Self::distance`] (`cmp_distance(a) <= cmp_distance(b)` iff `distance(a) <= distance(b)`)
+    /// but not necessarily equal to it.
+    ///
+    /// A neighbor search's priority queue only needs this ordering, not the exact value, so an
+    /// implementation can skip work the exact distance requires. [`EuclideanDistance`] overrides
+    /// this to compare squared distances, avoiding a `sqrt` on every queue comparison; everything
+    /// else defaults to the exact [`Self::distance`].
+    ///
+    /// [`EuclideanDistance`]: crate::rtree::distance::EuclideanDistance
+    fn cmp_distance(&self, x1: N, y1: N, x2: N, y2: N) -> N {
+        self.distance(x1, y1, x2, y2)
+    }
+
+    /// The ordering value for the distance from a point to a bounding box. See
+    /// [`Self::cmp_distance`].
+    fn cmp_distance_to_bbox(&self, x: N, y: N, min_x: N, min_y: N, max_x: N, max_y: N) -> N {
+        self.distance_to_bbox(x, y, min_x, min_y, max_x, max_y)
+    }
+
+    /// Convert an exact distance threshold (e.g. a `max_distance` argument) into the same space
+    /// as [`Self::cmp_distance`]/[`Self::cmp_distance_to_bbox`], so it can be compared against
+    /// them directly instead of computing an exact distance for every candidate.
+    fn cmp_max_distance(&self, max_distance: N) -> N {
+        max_distance
+    }
 }
 
 /// A trait for accessing geometries by index.
@@ -107,20 +135,97 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
         Ok(result)
     }
 
+    /// Returns the ids of the level-1 nodes (the same "partitions" as [`Self::boxes_at_level`]`(1)`)
+    /// whose bounding box intersects the given query box.
+    ///
+    /// This walks the tree from the root down to level 1, pruning subtrees whose boxes are
+    /// disjoint from the query instead of descending into them, so it touches a small fraction
+    /// of the tree rather than every partition. The returned ids index into the same sequential
+    /// `0..n` numbering that [`Self::boxes_at_level`]`(1)` implies, so they can be used directly
+    /// to select which row groups/files a downstream engine needs to read.
+    ///
+    /// Returns [`GeoIndexError::General`] if the tree is too small to have a level 1, matching
+    /// [`Self::boxes_at_level`]`(1)`.
+    fn partitions_for_box(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Result<Vec<u32>> {
+        let level_bounds = self.level_bounds();
+        if level_bounds.len() < 2 {
+            return Err(GeoIndexError::General("Level out of bounds".to_string()));
+        }
+
+        let boxes = self.boxes();
+        let partition_level_start = level_bounds[0];
+        let partition_level_end = level_bounds[1];
+
+        let mut partition_ids = vec![];
+        let mut queue = VecDeque::with_capacity(self.node_size() as usize);
+        queue.push_back(boxes.len() - 4);
+
+        while let Some(node_index) = queue.pop_front() {
+            let end = (node_index + self.node_size() as usize * 4)
+                .min(upper_bound(node_index, level_bounds));
+
+            for pos in (node_index..end).step_by(4) {
+                let node_min_x = boxes[pos];
+                let node_min_y = boxes[pos + 1];
+                let node_max_x = boxes[pos + 2];
+                let node_max_y = boxes[pos + 3];
+
+                if max_x < node_min_x
+                    || max_y < node_min_y
+                    || min_x > node_max_x
+                    || min_y > node_max_y
+                {
+                    continue;
+                }
+
+                if pos >= partition_level_start && pos < partition_level_end {
+                    partition_ids.push(((pos - partition_level_start) / 4) as u32);
+                } else {
+                    queue.push_back(self.indices().get(pos >> 2));
+                }
+            }
+        }
+
+        partition_ids.sort_unstable();
+        Ok(partition_ids)
+    }
+
     /// Search an RTree given the provided bounding box.
     ///
     /// Results are the indexes of the inserted objects in insertion order.
     fn search(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Vec<u32> {
+        let mut results = vec![];
+        self.search_visit(min_x, min_y, max_x, max_y, |index| {
+            results.push(index as u32);
+            ControlFlow::Continue(())
+        });
+        results
+    }
+
+    /// Search an RTree given the provided bounding box, invoking `visit` with the index of each
+    /// matching item instead of collecting them into a `Vec`.
+    ///
+    /// This runs the identical traversal as [`Self::search`], but lets a caller count hits,
+    /// short-circuit on the first match, or write directly into a reused buffer, without paying
+    /// for an intermediate allocation. `visit` returning [`ControlFlow::Break`] stops the
+    /// traversal immediately; [`Self::search`] itself is implemented in terms of this method.
+    fn search_visit(
+        &self,
+        min_x: N,
+        min_y: N,
+        max_x: N,
+        max_y: N,
+        mut visit: impl FnMut(usize) -> ControlFlow<()>,
+    ) {
         let boxes = self.boxes();
         let indices = self.indices();
         if boxes.is_empty() {
-            return vec![];
+            return;
         }
 
         let mut outer_node_index = boxes.len().checked_sub(4);
 
         let mut queue = VecDeque::with_capacity(self.node_size() as usize);
-        let mut results = vec![];
 
         while let Some(node_index) = outer_node_index {
             // find the end index of the node
@@ -152,16 +257,80 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
 
                 if node_index >= self.num_items() as usize * 4 {
                     queue.push_back(index); // node; add it to the search queue
+                } else if visit(index).is_break() {
+                    return;
+                }
+            }
+
+            outer_node_index = queue.pop_front();
+        }
+    }
+
+    /// Search an RTree given the provided bounding box, returning coalesced ranges over the
+    /// sorted leaf buffer instead of individual item positions.
+    ///
+    /// Packing strategies like [`HilbertSort`][crate::rtree::sort::HilbertSort]/
+    /// [`STRSort`][crate::rtree::sort::STRSort] place spatially-nearby items at contiguous
+    /// positions in the backing buffer, so a query's matches are often a handful of contiguous
+    /// runs rather than scattered positions. This walks the tree exactly like [`Self::search`],
+    /// but instead of returning every matching leaf position individually, it sorts them and
+    /// merges touching/overlapping ones into half-open [`ItemRange`]s. Callers reading items from
+    /// mmap'd or remote storage can then issue a few large sequential reads instead of one
+    /// request per item.
+    ///
+    /// Note the returned ranges are positions in the sorted leaf buffer, i.e. the same space as
+    /// [`Self::indices`], *not* the original insertion order returned by [`Self::search`]. Use
+    /// [`Self::indices`] to map a position back to its original insertion index if needed.
+    fn search_ranges(&self, min_x: N, min_y: N, max_x: N, max_y: N) -> Vec<ItemRange> {
+        let boxes = self.boxes();
+        let indices = self.indices();
+        if boxes.is_empty() {
+            return vec![];
+        }
+
+        let mut outer_node_index = boxes.len().checked_sub(4);
+
+        let mut queue = VecDeque::with_capacity(self.node_size() as usize);
+        let mut positions: Vec<u32> = vec![];
+
+        while let Some(node_index) = outer_node_index {
+            // find the end index of the node
+            let end = (node_index + self.node_size() as usize * 4)
+                .min(upper_bound(node_index, self.level_bounds()));
+
+            // search through child nodes
+            for pos in (node_index..end).step_by(4) {
+                // Safety: pos was checked before to be within bounds
+                // Justification: avoiding bounds check improves performance by up to 30%
+                let (node_min_x, node_min_y, node_max_x, node_max_y) = unsafe {
+                    let node_min_x = *boxes.get_unchecked(pos);
+                    let node_min_y = *boxes.get_unchecked(pos + 1);
+                    let node_max_x = *boxes.get_unchecked(pos + 2);
+                    let node_max_y = *boxes.get_unchecked(pos + 3);
+                    (node_min_x, node_min_y, node_max_x, node_max_y)
+                };
+
+                // check if the query box disjoint with the node box
+                if max_x < node_min_x
+                    || max_y < node_min_y
+                    || min_x > node_max_x
+                    || min_y > node_max_y
+                {
+                    continue;
+                }
+
+                if node_index >= self.num_items() as usize * 4 {
+                    queue.push_back(indices.get(pos >> 2)); // node; add it to the search queue
                 } else {
                     // Since the max items of the index is u32, we can coerce to u32
-                    results.push(index.try_into().unwrap()); // leaf item
+                    positions.push((pos >> 2).try_into().unwrap()); // leaf item position
                 }
             }
 
             outer_node_index = queue.pop_front();
         }
 
-        results
+        merge_item_ranges(positions)
     }
 
     /// Search an RTree given the provided bounding box.
@@ -220,9 +389,23 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
         self.neighbors_with_simple_distance(x, y, max_results, max_distance, &simple_distance)
     }
 
+    /// Search items in order of distance from the given point.
+    ///
+    /// Alias for [`neighbors`][Self::neighbors], matching the `nearest` naming
+    /// [`KDTreeIndex::nearest`][crate::kdtree::KDTreeIndex::nearest] and
+    /// [`KdbushIndex::nearest`][crate::kdbush::KdbushIndex::nearest] use for the same kind of
+    /// query.
+    fn nearest(&self, x: N, y: N, max_results: Option<usize>, max_distance: Option<N>) -> Vec<u32> {
+        self.neighbors(x, y, max_results, max_distance)
+    }
+
     /// Search items in order of distance from the given point using a simple distance metric.
     ///
     /// This is the base method for distance-based neighbor searches that works without the geo feature.
+    ///
+    /// A thin wrapper around
+    /// [`neighbors_with_simple_distance_scored`][Self::neighbors_with_simple_distance_scored] that
+    /// drops each result's distance; see that method for the traversal itself.
     fn neighbors_with_simple_distance<M: SimpleDistanceMetric<N> + ?Sized>(
         &self,
         x: N,
@@ -231,13 +414,44 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
         max_distance: Option<N>,
         distance_metric: &M,
     ) -> Vec<u32> {
+        self.neighbors_with_simple_distance_scored(x, y, max_results, max_distance, distance_metric)
+            .into_iter()
+            .map(|(index, _dist)| index)
+            .collect()
+    }
+
+    /// Search items in order of distance from the given point using a simple distance metric,
+    /// returning each item's exact distance alongside its index.
+    ///
+    /// The traversal is a best-first search over a min-heap of `(comparison distance, encoded
+    /// id)` pairs, where a leaf item's id is encoded as `index << 1 | 1` and an internal node's as
+    /// `index << 1` so both kinds can share one heap without a tagged enum; popping an encoded id
+    /// with its low bit set yields a result in ascending distance order, popping one without it
+    /// expands that node's children. This is the same traversal the pre-split Flatbush index used
+    /// for its own `neighbors`, generalized here to accept any [`SimpleDistanceMetric`].
+    ///
+    /// The heap orders by [`SimpleDistanceMetric::cmp_distance_to_bbox`], which may not equal the
+    /// exact distance (see that method's docs), so a result's exact distance is recomputed via
+    /// [`SimpleDistanceMetric::distance_to_bbox`] once it's popped as a final match rather than
+    /// read back out of the heap — one extra call per returned item, not per node visited.
+    fn neighbors_with_simple_distance_scored<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+        distance_metric: &M,
+    ) -> Vec<(u32, N)> {
         let boxes = self.boxes();
         let indices = self.indices();
         let max_distance = max_distance.unwrap_or(distance_metric.max_distance());
+        // Compared in `cmp_distance`/`cmp_distance_to_bbox`'s space rather than the exact
+        // distance's, so the threshold check below never needs the exact value either.
+        let cmp_max_distance = distance_metric.cmp_max_distance(max_distance);
 
         let mut outer_node_index = Some(boxes.len() - 4);
-        let mut queue = BinaryHeap::new();
-        let mut results: Vec<u32> = vec![];
+        let mut queue: BinaryHeap<Reverse<ScoredNeighborNode<N>>> = BinaryHeap::new();
+        let mut results: Vec<(u32, N)> = vec![];
 
         'outer: while let Some(node_index) = outer_node_index {
             // find the end index of the node
@@ -248,8 +462,9 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
             for pos in (node_index..end).step_by(4) {
                 let index = indices.get(pos >> 2);
 
-                // Use the custom distance metric for bbox distance calculation
-                let dist = distance_metric.distance_to_bbox(
+                // Use the custom distance metric's comparison key for bbox distance, which may
+                // be cheaper to compute than the exact distance (see `cmp_distance_to_bbox`).
+                let dist = distance_metric.cmp_distance_to_bbox(
                     x,
                     y,
                     boxes[pos],
@@ -258,21 +473,23 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
                     boxes[pos + 3],
                 );
 
-                if dist > max_distance {
+                if dist > cmp_max_distance {
                     continue;
                 }
 
                 if node_index >= self.num_items() as usize * 4 {
                     // node (use even id)
-                    queue.push(Reverse(NeighborNode {
+                    queue.push(Reverse(ScoredNeighborNode {
                         id: index << 1,
+                        pos,
                         dist,
                     }));
                 } else {
                     // leaf item (use odd id)
                     // Use consistent distance calculation for both nodes and leaf items
-                    queue.push(Reverse(NeighborNode {
+                    queue.push(Reverse(ScoredNeighborNode {
                         id: (index << 1) + 1,
+                        pos,
                         dist,
                     }));
                 }
@@ -281,11 +498,19 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
             // pop items from the queue
             while !queue.is_empty() && queue.peek().is_some_and(|val| (val.0.id & 1) != 0) {
                 let dist = queue.peek().unwrap().0.dist;
-                if dist > max_distance {
+                if dist > cmp_max_distance {
                     break 'outer;
                 }
                 let item = queue.pop().unwrap();
-                results.push((item.0.id >> 1).try_into().unwrap());
+                let exact_dist = distance_metric.distance_to_bbox(
+                    x,
+                    y,
+                    boxes[item.0.pos],
+                    boxes[item.0.pos + 1],
+                    boxes[item.0.pos + 2],
+                    boxes[item.0.pos + 3],
+                );
+                results.push(((item.0.id >> 1).try_into().unwrap(), exact_dist));
                 if max_results.is_some_and(|max_results| results.len() == max_results) {
                     break 'outer;
                 }
@@ -301,6 +526,193 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
         results
     }
 
+    /// Search items in order of distance from the given point, returning a lazy iterator instead
+    /// of eagerly collecting every match into a `Vec`.
+    ///
+    /// This drives the same best-first branch-and-bound traversal as
+    /// [`neighbors_with_simple_distance`][Self::neighbors_with_simple_distance] — a min-heap of
+    /// `(distance, encoded id)` pairs, expanding one node's children per step and yielding a
+    /// leaf once it reaches the front — except it pulls one more node or leaf off the heap on
+    /// each call to [`Iterator::next`] rather than draining it up front. [`Self::neighbors`] and
+    /// [`Self::neighbors_with_simple_distance`] can be re-expressed as `.take(max_results)` /
+    /// `.take_while(|(_, d)| *d <= max_distance)` adapters over this iterator; reach for it
+    /// directly when the stopping condition isn't a simple count or distance threshold.
+    ///
+    /// The yielded distance is [`SimpleDistanceMetric::cmp_distance_to_bbox`]'s comparison key,
+    /// not necessarily the exact distance — see [`SimpleDistanceMetric::cmp_distance`] for when
+    /// they differ.
+    fn neighbors_iter<'a, M: SimpleDistanceMetric<N> + ?Sized>(
+        &'a self,
+        x: N,
+        y: N,
+        distance_metric: &'a M,
+    ) -> NeighborsIter<'a, N, Self, M> {
+        NeighborsIter {
+            tree: self,
+            x,
+            y,
+            distance_metric,
+            queue: BinaryHeap::new(),
+            outer_node_index: self.boxes().len().checked_sub(4),
+        }
+    }
+
+    /// Search items in order of distance from the given point, trading a bounded accuracy loss
+    /// for speed and an optional cap on the number of box distance checks performed.
+    ///
+    /// This is a convenience wrapper around
+    /// [`neighbors_approximate_with_simple_distance`][Self::neighbors_approximate_with_simple_distance]
+    /// using the same squared-distance metric as [`neighbors`][Self::neighbors]. See that method
+    /// for what `epsilon` and `max_checks` control.
+    ///
+    /// - epsilon: approximation factor; must be non-negative
+    /// - max_checks: maximum number of box distance evaluations to perform before returning
+    ///   whatever has been collected
+    fn neighbors_approximate(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+        epsilon: N,
+        max_checks: usize,
+    ) -> Vec<u32> {
+        // Use simple squared distance for backward compatibility
+        struct SimpleSquaredDistance;
+        impl<N: IndexableNum> SimpleDistanceMetric<N> for SimpleSquaredDistance {
+            fn distance(&self, x1: N, y1: N, x2: N, y2: N) -> N {
+                let dx = x2 - x1;
+                let dy = y2 - y1;
+                dx * dx + dy * dy
+            }
+            fn distance_to_bbox(&self, x: N, y: N, min_x: N, min_y: N, max_x: N, max_y: N) -> N {
+                let dx = axis_dist(x, min_x, max_x);
+                let dy = axis_dist(y, min_y, max_y);
+                dx * dx + dy * dy
+            }
+        }
+        let simple_distance = SimpleSquaredDistance;
+        self.neighbors_approximate_with_simple_distance(
+            x,
+            y,
+            max_results,
+            max_distance,
+            epsilon,
+            max_checks,
+            &simple_distance,
+        )
+    }
+
+    /// Search items in order of distance from the given point using a simple distance metric,
+    /// trading a bounded accuracy loss for speed and an optional cap on the number of box
+    /// distance checks performed.
+    ///
+    /// Unlike [`neighbors_with_simple_distance`][Self::neighbors_with_simple_distance], which
+    /// visits nodes strictly in ascending distance order via a single min-heap and is therefore
+    /// always exact, this walks the tree depth-first in the structural (not distance) order its
+    /// nodes are stored in, tracking a bounded `max_results`-sized max-heap of the best
+    /// candidates accepted so far. Once that heap holds `max_results` candidates, its current
+    /// worst distance is `d_k`; a queued node or leaf is then skipped once `(1 + epsilon)` times
+    /// its distance already exceeds `d_k`, rather than the exact `> d_k` that an accuracy-lossless
+    /// search would use. Because the traversal order isn't distance-ordered, this relaxed test
+    /// can prune a subtree that would have held something closer than an already-accepted
+    /// candidate, but every returned item is still guaranteed to be within a factor of
+    /// `(1 + epsilon)` of the true `d_k`. `d_k` is undefined, and no pruning beyond
+    /// `max_distance` happens, until `max_results` candidates have been accepted.
+    ///
+    /// `max_checks` additionally bounds the number of `boxes` distance evaluations performed;
+    /// once the budget is exhausted the search stops descending and returns whatever has been
+    /// collected so far. With `epsilon` of `0.0` and `max_checks` of `usize::MAX` every returned
+    /// item is exact.
+    ///
+    /// - epsilon: approximation factor; must be non-negative
+    /// - max_checks: maximum number of box distance evaluations to perform before returning
+    ///   whatever has been collected
+    fn neighbors_approximate_with_simple_distance<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+        epsilon: N,
+        max_checks: usize,
+        distance_metric: &M,
+    ) -> Vec<u32> {
+        assert!(
+            epsilon >= N::zero(),
+            "epsilon must be non-negative, got {epsilon:?}"
+        );
+
+        let boxes = self.boxes();
+        if boxes.is_empty() {
+            return vec![];
+        }
+        let indices = self.indices();
+        let max_distance = max_distance.unwrap_or(distance_metric.max_distance());
+        let epsilon_factor = N::one() + epsilon;
+
+        // Max-heap of the best `max_results` candidates accepted so far; `peek()` is the current
+        // worst accepted distance, i.e. `d_k`.
+        let mut candidates: BinaryHeap<NeighborNode<N>> = BinaryHeap::new();
+        // Depth-first stack of (node index, distance to that node's box) pairs still to expand.
+        let mut stack: Vec<(usize, N)> = vec![(boxes.len() - 4, N::zero())];
+        let mut checks = 0usize;
+
+        let is_pruned = |candidates: &BinaryHeap<NeighborNode<N>>, dist: N| {
+            max_results.is_some_and(|max_results| {
+                candidates.len() >= max_results
+                    && dist * epsilon_factor > candidates.peek().unwrap().dist
+            })
+        };
+
+        'outer: while let Some((node_index, node_dist)) = stack.pop() {
+            if node_dist > max_distance || is_pruned(&candidates, node_dist) {
+                continue;
+            }
+
+            // find the end index of the node
+            let end = (node_index + self.node_size() as usize * 4)
+                .min(upper_bound(node_index, self.level_bounds()));
+            let is_leaf_level = node_index < self.num_items() as usize * 4;
+
+            for pos in (node_index..end).step_by(4) {
+                if checks >= max_checks {
+                    break 'outer;
+                }
+                checks += 1;
+
+                let dist = distance_metric.distance_to_bbox(
+                    x,
+                    y,
+                    boxes[pos],
+                    boxes[pos + 1],
+                    boxes[pos + 2],
+                    boxes[pos + 3],
+                );
+
+                if dist > max_distance || is_pruned(&candidates, dist) {
+                    continue;
+                }
+
+                let index = indices.get(pos >> 2);
+                if is_leaf_level {
+                    candidates.push(NeighborNode { id: index, dist });
+                    if max_results.is_some_and(|max_results| candidates.len() > max_results) {
+                        candidates.pop();
+                    }
+                } else {
+                    stack.push((index, dist));
+                }
+            }
+        }
+
+        candidates
+            .into_sorted_vec()
+            .into_iter()
+            .map(|c| c.id.try_into().unwrap())
+            .collect()
+    }
+
     /// Search items in order of distance from the given point using a custom distance metric.
     ///
     /// This method allows you to specify a custom distance calculation method, such as
@@ -331,7 +743,79 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
         max_distance: Option<N>,
         distance_metric: &M,
     ) -> Vec<u32> {
-        self.neighbors_with_simple_distance(x, y, max_results, max_distance, distance_metric)
+        self.neighbors_with_distance_scored(x, y, max_results, max_distance, distance_metric)
+            .into_iter()
+            .map(|(index, _dist)| index)
+            .collect()
+    }
+
+    /// Search items in order of distance from the given point using a custom distance metric,
+    /// returning each item's exact distance alongside its index.
+    ///
+    /// A thin wrapper around
+    /// [`neighbors_with_simple_distance_scored`][Self::neighbors_with_simple_distance_scored]; see
+    /// [`neighbors_with_distance`][Self::neighbors_with_distance] for the index-only form.
+    ///
+    /// ```
+    /// use geo_index::rtree::{RTreeBuilder, RTreeIndex};
+    /// use geo_index::rtree::distance::HaversineDistance;
+    /// use geo_index::rtree::sort::HilbertSort;
+    ///
+    /// let mut builder = RTreeBuilder::<f64>::new(3);
+    /// builder.add(-74.0, 40.7, -74.0, 40.7); // New York
+    /// builder.add(-0.1, 51.5, -0.1, 51.5);   // London
+    /// builder.add(139.7, 35.7, 139.7, 35.7); // Tokyo
+    /// let tree = builder.finish::<HilbertSort>();
+    ///
+    /// let haversine = HaversineDistance::default();
+    /// let results = tree.neighbors_with_distance_scored(-74.0, 40.7, Some(2), None, &haversine);
+    /// // Each result pairs an index with its great-circle distance, in meters, from New York.
+    /// let (nearest_index, nearest_distance) = results[0];
+    /// assert_eq!(nearest_index, 0);
+    /// assert_eq!(nearest_distance, 0.0);
+    /// ```
+    #[cfg(feature = "use-geo_0_31")]
+    fn neighbors_with_distance_scored<M: DistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+        distance_metric: &M,
+    ) -> Vec<(u32, N)> {
+        self.neighbors_with_simple_distance_scored(x, y, max_results, max_distance, distance_metric)
+    }
+
+    /// Search for the `k` nearest items using a custom distance metric, trading a bounded
+    /// accuracy loss for speed.
+    ///
+    /// This is a convenience wrapper around
+    /// [`neighbors_approximate_with_simple_distance`][Self::neighbors_approximate_with_simple_distance]
+    /// for callers of [`neighbors_with_distance`][Self::neighbors_with_distance]'s geo-aware
+    /// [`DistanceMetric`], with no cap on the number of box distance checks performed. As
+    /// described there, every returned neighbor's distance is guaranteed to be within a factor of
+    /// `(1 + epsilon)` of the true `k`-th nearest neighbor's distance; with `epsilon` of `0.0` the
+    /// results are exact and identical to `neighbors_with_distance(x, y, Some(k), None, metric)`.
+    ///
+    /// - epsilon: approximation factor; must be non-negative
+    #[cfg(feature = "use-geo_0_31")]
+    fn neighbors_approx<M: DistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        k: usize,
+        epsilon: N,
+        distance_metric: &M,
+    ) -> Vec<u32> {
+        self.neighbors_approximate_with_simple_distance(
+            x,
+            y,
+            Some(k),
+            None,
+            epsilon,
+            usize::MAX,
+            distance_metric,
+        )
     }
 
     /// Search items in order of distance from the given coordinate.
@@ -402,6 +886,33 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
         distance_metric: &M,
         accessor: &A,
     ) -> Vec<u32> {
+        self.neighbors_geometry_scored(
+            query_geometry,
+            max_results,
+            max_distance,
+            distance_metric,
+            accessor,
+        )
+        .into_iter()
+        .map(|(index, _dist)| index)
+        .collect()
+    }
+
+    /// Search items in order of distance from a query geometry using a distance metric and
+    /// geometry accessor, returning each item's exact distance alongside its index.
+    ///
+    /// A thin wrapper around the same traversal as
+    /// [`neighbors_geometry`][Self::neighbors_geometry], reusing the geometry-to-geometry
+    /// distance already computed for each candidate rather than recomputing it.
+    #[cfg(feature = "use-geo_0_31")]
+    fn neighbors_geometry_scored<M: DistanceMetric<N> + ?Sized, A: GeometryAccessor + ?Sized>(
+        &self,
+        query_geometry: &Geometry<f64>,
+        max_results: Option<usize>,
+        max_distance: Option<N>,
+        distance_metric: &M,
+        accessor: &A,
+    ) -> Vec<(u32, N)> {
         let boxes = self.boxes();
         let indices = self.indices();
         let max_distance = max_distance.unwrap_or(distance_metric.max_distance());
@@ -424,7 +935,7 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
 
         let mut outer_node_index = Some(boxes.len() - 4);
         let mut queue = BinaryHeap::new();
-        let mut results: Vec<u32> = vec![];
+        let mut results: Vec<(u32, N)> = vec![];
 
         'outer: while let Some(node_index) = outer_node_index {
             // find the end index of the node
@@ -483,7 +994,10 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
                     break 'outer;
                 }
                 let item = queue.pop().unwrap();
-                results.push((item.0.id >> 1).try_into().unwrap());
+                // The leaf's `dist` is already the exact geometry-to-geometry distance (unlike
+                // `neighbors_with_simple_distance_scored`, nothing here orders by a cheaper
+                // comparison key), so it can be reported as-is.
+                results.push(((item.0.id >> 1).try_into().unwrap(), item.0.dist));
                 if max_results.is_some_and(|max_results| results.len() == max_results) {
                     break 'outer;
                 }
@@ -499,21 +1013,476 @@ pub trait RTreeIndex<N: IndexableNum>: Sized {
         results
     }
 
-    /// Returns an iterator over the indexes of objects in this and another tree that intersect.
+    /// Find every item within `radius` of the given point using a simple distance metric.
     ///
-    /// Each returned object is of the form `(u32, u32)`, where the first is the positional
-    /// index of the "left" tree and the second is the index of the "right" tree.
-    fn intersection_candidates_with_other_tree<'a>(
+    /// A thin wrapper around
+    /// [`within_distance_scored`][Self::within_distance_scored] that drops each result's
+    /// distance; see that method for the traversal itself.
+    fn within_distance<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        radius: N,
+        distance_metric: &M,
+    ) -> Vec<u32> {
+        self.within_distance_scored(x, y, radius, distance_metric)
+            .into_iter()
+            .map(|(index, _dist)| index)
+            .collect()
+    }
+
+    /// Find every item within `radius` of the given point using a simple distance metric, sorted
+    /// in ascending distance order.
+    ///
+    /// A thin wrapper around [`within_distance_scored`][Self::within_distance_scored]; unlike
+    /// [`within_distance`][Self::within_distance] this pays for a sort of the matches, so prefer
+    /// that method when the order doesn't matter.
+    fn within_distance_sorted<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        radius: N,
+        distance_metric: &M,
+    ) -> Vec<u32> {
+        let mut scored = self.within_distance_scored(x, y, radius, distance_metric);
+        scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        scored.into_iter().map(|(index, _dist)| index).collect()
+    }
+
+    /// Find every item within `radius` of the given point using a simple distance metric,
+    /// returning each item's exact distance alongside its index.
+    ///
+    /// Unlike [`neighbors_with_simple_distance`][Self::neighbors_with_simple_distance], which
+    /// visits nodes in ascending distance order over a min-heap to support `max_results`, this is
+    /// an unordered traversal over a plain queue: any node or leaf whose
+    /// [`SimpleDistanceMetric::distance_to_bbox`] exceeds `radius` is pruned, and every surviving
+    /// leaf is reported with no further ordering. This is the "find everything nearby" query that
+    /// the ordered neighbor search makes awkward to express as a `max_results`/`max_distance`
+    /// pair, mirroring petal-neighbors' `query_radius`.
+    fn within_distance_scored<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        x: N,
+        y: N,
+        radius: N,
+        distance_metric: &M,
+    ) -> Vec<(u32, N)> {
+        let boxes = self.boxes();
+        let indices = self.indices();
+        if boxes.is_empty() {
+            return vec![];
+        }
+
+        let mut outer_node_index = boxes.len().checked_sub(4);
+        let mut queue = VecDeque::with_capacity(self.node_size() as usize);
+        let mut results: Vec<(u32, N)> = vec![];
+
+        while let Some(node_index) = outer_node_index {
+            let end = (node_index + self.node_size() as usize * 4)
+                .min(upper_bound(node_index, self.level_bounds()));
+
+            for pos in (node_index..end).step_by(4) {
+                let dist = distance_metric.distance_to_bbox(
+                    x,
+                    y,
+                    boxes[pos],
+                    boxes[pos + 1],
+                    boxes[pos + 2],
+                    boxes[pos + 3],
+                );
+
+                if dist > radius {
+                    continue;
+                }
+
+                let index = indices.get(pos >> 2);
+                if node_index >= self.num_items() as usize * 4 {
+                    queue.push_back(index); // node; add it to the search queue
+                } else {
+                    results.push((index.try_into().unwrap(), dist));
+                }
+            }
+
+            outer_node_index = queue.pop_front();
+        }
+
+        results
+    }
+
+    /// Find every item whose geometry (accessed through `accessor`) is within `radius` of the
+    /// query geometry, using a distance metric.
+    ///
+    /// A thin wrapper around
+    /// [`within_distance_geometry_scored`][Self::within_distance_geometry_scored] that drops each
+    /// result's distance.
+    #[cfg(feature = "use-geo_0_31")]
+    fn within_distance_geometry<M: DistanceMetric<N> + ?Sized, A: GeometryAccessor + ?Sized>(
+        &self,
+        query_geometry: &Geometry<f64>,
+        radius: N,
+        distance_metric: &M,
+        accessor: &A,
+    ) -> Vec<u32> {
+        self.within_distance_geometry_scored(query_geometry, radius, distance_metric, accessor)
+            .into_iter()
+            .map(|(index, _dist)| index)
+            .collect()
+    }
+
+    /// Find every item whose geometry (accessed through `accessor`) is within `radius` of the
+    /// query geometry, using a distance metric, returning each item's exact distance alongside
+    /// its index.
+    ///
+    /// Like [`neighbors_geometry`][Self::neighbors_geometry], this measures an internal node from
+    /// the query's bbox center and computes the exact geometry-to-geometry distance for each
+    /// leaf, but it is unordered and prunes on `radius` instead of maintaining a k-NN heap. An
+    /// internal node is pruned only when its distance exceeds `radius` plus the distance from
+    /// that center to the farthest corner of the query's bbox, so an extended query geometry
+    /// still reaches nodes near any part of it.
+    #[cfg(feature = "use-geo_0_31")]
+    fn within_distance_geometry_scored<
+        M: DistanceMetric<N> + ?Sized,
+        A: GeometryAccessor + ?Sized,
+    >(
+        &self,
+        query_geometry: &Geometry<f64>,
+        radius: N,
+        distance_metric: &M,
+        accessor: &A,
+    ) -> Vec<(u32, N)> {
+        let boxes = self.boxes();
+        let indices = self.indices();
+        if boxes.is_empty() {
+            return vec![];
+        }
+
+        let bounds = query_geometry.bounding_rect();
+        let (query_min_x, query_min_y, query_max_x, query_max_y) = if let Some(rect) = bounds {
+            let min = rect.min();
+            let max = rect.max();
+            (
+                N::from_f64(min.x).unwrap_or(N::zero()),
+                N::from_f64(min.y).unwrap_or(N::zero()),
+                N::from_f64(max.x).unwrap_or(N::zero()),
+                N::from_f64(max.y).unwrap_or(N::zero()),
+            )
+        } else {
+            (N::zero(), N::zero(), N::zero(), N::zero())
+        };
+        let center_x = (query_min_x + query_max_x) / (N::one() + N::one());
+        let center_y = (query_min_y + query_max_y) / (N::one() + N::one());
+        let query_extent = [
+            (query_min_x, query_min_y),
+            (query_min_x, query_max_y),
+            (query_max_x, query_min_y),
+            (query_max_x, query_max_y),
+        ]
+        .into_iter()
+        .map(|(corner_x, corner_y)| distance_metric.distance(center_x, center_y, corner_x, corner_y))
+        .fold(N::zero(), |a, b| if b > a { b } else { a });
+
+        let mut outer_node_index = boxes.len().checked_sub(4);
+        let mut queue = VecDeque::with_capacity(self.node_size() as usize);
+        let mut results: Vec<(u32, N)> = vec![];
+
+        while let Some(node_index) = outer_node_index {
+            let end = (node_index + self.node_size() as usize * 4)
+                .min(upper_bound(node_index, self.level_bounds()));
+
+            for pos in (node_index..end).step_by(4) {
+                let index = indices.get(pos >> 2);
+                let is_leaf = node_index < self.num_items() as usize * 4;
+
+                let dist = if is_leaf {
+                    if let Some(item_geom) = accessor.get_geometry(index) {
+                        distance_metric.distance_to_geometry(query_geometry, item_geom)
+                    } else {
+                        distance_metric.max_distance()
+                    }
+                } else {
+                    distance_metric.distance_to_bbox(
+                        center_x,
+                        center_y,
+                        boxes[pos],
+                        boxes[pos + 1],
+                        boxes[pos + 2],
+                        boxes[pos + 3],
+                    )
+                };
+
+                let limit = if is_leaf { radius } else { radius + query_extent };
+                if dist > limit {
+                    continue;
+                }
+
+                if is_leaf {
+                    results.push((index.try_into().unwrap(), dist));
+                } else {
+                    queue.push_back(index);
+                }
+            }
+
+            outer_node_index = queue.pop_front();
+        }
+
+        results
+    }
+
+    /// Find the indexes of geometries (accessed through `accessor`) that truly contain the query
+    /// point `(x, y)`.
+    ///
+    /// This first collects bounding-box candidates via [`Self::search`], then refines them with
+    /// an exact `geo` point-in-geometry test, so only confirmed matches are returned. Use this
+    /// instead of hand-writing the box-candidates-then-refine loop every caller otherwise needs.
+    ///
+    /// ```
+    /// use geo_index::rtree::{RTreeBuilder, RTreeIndex};
+    /// use geo_index::rtree::distance::SliceGeometryAccessor;
+    /// use geo_index::rtree::sort::HilbertSort;
+    /// use geo_0_31::{Geometry, LineString, Polygon};
+    ///
+    /// let square = Geometry::Polygon(Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.), (0., 0.)]),
+    ///     vec![],
+    /// ));
+    ///
+    /// let mut builder = RTreeBuilder::<f64>::new(1);
+    /// builder.add(0., 0., 2., 2.);
+    /// let tree = builder.finish::<HilbertSort>();
+    ///
+    /// let geometries = vec![square];
+    /// let accessor = SliceGeometryAccessor::new(&geometries);
+    /// assert_eq!(tree.query_point(1., 1., &accessor), vec![0]);
+    /// assert_eq!(tree.query_point(5., 5., &accessor), Vec::<u32>::new());
+    /// ```
+    #[cfg(feature = "use-geo_0_31")]
+    fn query_point<A: GeometryAccessor + ?Sized>(&self, x: f64, y: f64, accessor: &A) -> Vec<u32> {
+        use geo_0_31::algorithm::Contains;
+        use geo_0_31::Point;
+
+        let nx = N::from_f64(x).unwrap_or(N::zero());
+        let ny = N::from_f64(y).unwrap_or(N::zero());
+        let point = Point::new(x, y);
+
+        self.search(nx, ny, nx, ny)
+            .into_iter()
+            .filter(|&index| {
+                accessor
+                    .get_geometry(index as usize)
+                    .is_some_and(|geom| geom.contains(&point))
+            })
+            .collect()
+    }
+
+    /// Find the indexes of geometries (accessed through `accessor`) that truly intersect the
+    /// query rectangle.
+    ///
+    /// This first collects bounding-box candidates via [`Self::search`], then refines them with
+    /// an exact `geo` [`Intersects`][geo_0_31::algorithm::Intersects] test against a rectangle
+    /// built from the query bounds.
+    #[cfg(feature = "use-geo_0_31")]
+    fn query_rect<A: GeometryAccessor + ?Sized>(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+        accessor: &A,
+    ) -> Vec<u32> {
+        use geo_0_31::algorithm::Intersects;
+        use geo_0_31::{coord, Rect};
+
+        let query_rect = Rect::new(coord! { x: min_x, y: min_y }, coord! { x: max_x, y: max_y });
+
+        let nmin_x = N::from_f64(min_x).unwrap_or(N::zero());
+        let nmin_y = N::from_f64(min_y).unwrap_or(N::zero());
+        let nmax_x = N::from_f64(max_x).unwrap_or(N::zero());
+        let nmax_y = N::from_f64(max_y).unwrap_or(N::zero());
+
+        self.search(nmin_x, nmin_y, nmax_x, nmax_y)
+            .into_iter()
+            .filter(|&index| {
+                accessor
+                    .get_geometry(index as usize)
+                    .is_some_and(|geom| geom.intersects(&query_rect))
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the indexes of objects in this and another tree that intersect.
+    ///
+    /// Each returned object is of the form `(u32, u32)`, where the first is the positional
+    /// index of the "left" tree and the second is the index of the "right" tree.
+    fn intersection_candidates_with_other_tree<'a>(
         &'a self,
         other: &'a impl RTreeIndex<N>,
     ) -> impl Iterator<Item = (u32, u32)> + 'a {
         IntersectionIterator::from_trees(self, other)
     }
 
+    /// Returns an iterator over the unordered pairs of objects in this tree that intersect with
+    /// each other, as used for e.g. finding which geometries in a single layer share a border.
+    ///
+    /// This is a dedicated self-join: compared to calling
+    /// [`Self::intersection_candidates_with_other_tree`] with `self` as the other tree, each
+    /// unordered pair `(i, j)` is only ever emitted once (not also as `(j, i)`), and `(i, i)`
+    /// self-pairs are skipped. This avoids the allocation-heavy `HashSet` dedup that callers
+    /// would otherwise need to apply themselves.
+    fn self_intersection_candidates(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        IntersectionIterator::from_tree_self(self)
+    }
+
+    /// Returns an iterator over the pairs of objects in this and another tree whose boxes
+    /// satisfy the given [`SpatialPredicate`], e.g. box containment or a maximum gap distance.
+    ///
+    /// This generalizes [`Self::intersection_candidates_with_other_tree`] (which always tests
+    /// box intersection) to other box predicates, while still pruning the tree walk using
+    /// whichever necessary condition the predicate implies for a pair of parent boxes.
+    fn spatial_join<'a>(
+        &'a self,
+        other: &'a impl RTreeIndex<N>,
+        predicate: SpatialPredicate<N>,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        SpatialJoinIterator::new(self, other, predicate)
+    }
+
     /// Access the root node of the RTree for manual traversal.
     fn root(&self) -> Node<'_, N, Self> {
         Node::from_root(self)
     }
+
+    /// Walk the tree from the root, driven by a caller-supplied predicate instead of a fixed
+    /// bounding-box query.
+    ///
+    /// `descend` is called on every intermediate node and decides whether to recurse into it;
+    /// `visit_leaf` is called on every leaf node reached by a subtree that wasn't pruned. This
+    /// lets a caller implement arbitrary spatial predicates — polygon-in-rect filters, frustum
+    /// culling, "all leaves within distance `d` of a point" — directly against the raw index,
+    /// without the crate needing to special-case each one and without allocating a result `Vec`
+    /// the caller may not need.
+    ///
+    /// Like [`Self::search_visit`], this uses an explicit stack rather than recursion, so it
+    /// doesn't risk a stack overflow on deep trees.
+    fn walk(
+        &self,
+        mut descend: impl FnMut(&Node<'_, N, Self>) -> bool,
+        mut visit_leaf: impl FnMut(&Node<'_, N, Self>),
+    ) {
+        if self.boxes().is_empty() {
+            return;
+        }
+
+        let mut stack = vec![self.root()];
+        while let Some(node) = stack.pop() {
+            if node.is_leaf() {
+                visit_leaf(&node);
+            } else if descend(&node) {
+                stack.extend(node.children());
+            }
+        }
+    }
+
+    /// Validate this tree's internal structural integrity: that every child box is contained
+    /// within its parent's box, and every leaf index is within [`num_items`][Self::num_items].
+    ///
+    /// [`RTreeMetadata::from_slice`][crate::rtree::RTreeMetadata::from_slice]/
+    /// [`RTreeRef::try_new`][crate::rtree::RTreeRef::try_new] only check the header (magic,
+    /// version, type, and total length), so a buffer that's the right *size* but internally
+    /// corrupt — a bit-flipped child index pointing out of bounds, or a node box that no longer
+    /// contains one of its children — passes that validation and silently produces wrong query
+    /// results later. This walks the tree from the root down, recursing into every child, to
+    /// catch that kind of damage directly.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`GeoIndexError::Invalid`] on the first violation found, carrying the full
+    /// `(level, node-offset)` path from the root down to the offending node.
+    fn validate(&self) -> Result<()> {
+        let boxes = self.boxes();
+        if boxes.is_empty() {
+            return Ok(());
+        }
+
+        let root_pos = boxes.len() - 4;
+        let root_level = self.num_levels() - 1;
+        let mut path = vec![(root_level, root_pos)];
+        validate_node(
+            boxes,
+            &self.indices(),
+            self.level_bounds(),
+            self.num_items() as usize,
+            self.node_size() as usize,
+            root_pos,
+            root_level,
+            None,
+            &mut path,
+        )
+    }
+}
+
+/// Recursively validate a single RTree node and its descendants, mutating `path` to track the
+/// nodes visited so far.
+///
+/// `parent_box` is `None` only for the root. A sibling subtree never inherits a dangling `path`
+/// entry: every push here is undone before returning, so on error `path` reflects exactly the
+/// route from the root down to the offending node.
+#[allow(clippy::too_many_arguments)]
+fn validate_node<N: IndexableNum>(
+    boxes: &[N],
+    indices: &Indices<'_>,
+    level_bounds: &[usize],
+    num_items: usize,
+    node_size: usize,
+    pos: usize,
+    level: usize,
+    parent_box: Option<(N, N, N, N)>,
+    path: &mut Vec<(usize, usize)>,
+) -> Result<()> {
+    let (min_x, min_y, max_x, max_y) = (boxes[pos], boxes[pos + 1], boxes[pos + 2], boxes[pos + 3]);
+
+    if let Some((parent_min_x, parent_min_y, parent_max_x, parent_max_y)) = parent_box {
+        if min_x < parent_min_x
+            || min_y < parent_min_y
+            || max_x > parent_max_x
+            || max_y > parent_max_y
+        {
+            return Err(GeoIndexError::Invalid {
+                reason: "child box is not contained within its parent's box".to_string(),
+                path: path.clone(),
+            });
+        }
+    }
+
+    if pos < num_items * 4 {
+        let index = indices.get(pos >> 2);
+        return if index >= num_items {
+            Err(GeoIndexError::Invalid {
+                reason: format!("leaf index {index} is out of bounds for {num_items} items"),
+                path: path.clone(),
+            })
+        } else {
+            Ok(())
+        };
+    }
+
+    let start = indices.get(pos >> 2);
+    let end = (start + node_size * 4).min(upper_bound(start, level_bounds));
+    for child_pos in (start..end).step_by(4) {
+        path.push((level - 1, child_pos));
+        validate_node(
+            boxes,
+            indices,
+            level_bounds,
+            num_items,
+            node_size,
+            child_pos,
+            level - 1,
+            Some((min_x, min_y, max_x, max_y)),
+            path,
+        )?;
+        path.pop();
+    }
+
+    Ok(())
 }
 
 /// A wrapper around a node and its distance for use in the priority queue.
@@ -538,13 +1507,115 @@ impl<N: IndexableNum> PartialOrd for NeighborNode<N> {
     }
 }
 
-impl<N: IndexableNum> RTreeIndex<N> for RTree<N> {
+/// Like [`NeighborNode`], but additionally carries the `boxes` position its distance was computed
+/// against, so a `_scored` method can recompute a popped leaf's exact distance without having to
+/// re-descend the tree to find its box again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScoredNeighborNode<N: IndexableNum> {
+    id: usize,
+    pos: usize,
+    dist: N,
+}
+
+impl<N: IndexableNum> Eq for ScoredNeighborNode<N> {}
+
+impl<N: IndexableNum> Ord for ScoredNeighborNode<N> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // We don't allow NaN. This should only panic on NaN
+        self.dist.partial_cmp(&other.dist).unwrap()
+    }
+}
+
+impl<N: IndexableNum> PartialOrd for ScoredNeighborNode<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A lazy, incremental best-first neighbor search, returned by
+/// [`RTreeIndex::neighbors_iter`].
+///
+/// Each call to [`Iterator::next`] expands exactly as much of the tree as is needed to produce
+/// one more `(index, distance)` pair in nondecreasing distance order, reusing the heap across
+/// calls instead of draining it eagerly.
+pub struct NeighborsIter<'a, N: IndexableNum, T: RTreeIndex<N>, M: SimpleDistanceMetric<N> + ?Sized>
+{
+    tree: &'a T,
+    x: N,
+    y: N,
+    distance_metric: &'a M,
+    queue: BinaryHeap<Reverse<NeighborNode<N>>>,
+    outer_node_index: Option<usize>,
+}
+
+impl<N: IndexableNum, T: RTreeIndex<N>, M: SimpleDistanceMetric<N> + ?Sized> Iterator
+    for NeighborsIter<'_, N, T, M>
+{
+    type Item = (u32, N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.tree.indices();
+
+        loop {
+            if self
+                .queue
+                .peek()
+                .is_some_and(|Reverse(item)| (item.id & 1) != 0)
+            {
+                let Reverse(item) = self.queue.pop().unwrap();
+                return Some(((item.id >> 1).try_into().unwrap(), item.dist));
+            }
+
+            // The very first iteration expands the root seeded in `neighbors_iter`; every
+            // subsequent node to expand is just the smallest item left in the queue, which is
+            // guaranteed to be a node since the check above already drained any leaf at the
+            // front.
+            let node_index = match self.outer_node_index.take() {
+                Some(node_index) => node_index,
+                None => match self.queue.pop() {
+                    Some(Reverse(item)) => item.id >> 1,
+                    None => return None,
+                },
+            };
+
+            let boxes = self.tree.boxes();
+            let end = (node_index + self.tree.node_size() as usize * 4)
+                .min(upper_bound(node_index, self.tree.level_bounds()));
+
+            for pos in (node_index..end).step_by(4) {
+                let index = indices.get(pos >> 2);
+                let dist = self.distance_metric.cmp_distance_to_bbox(
+                    self.x,
+                    self.y,
+                    boxes[pos],
+                    boxes[pos + 1],
+                    boxes[pos + 2],
+                    boxes[pos + 3],
+                );
+
+                if node_index >= self.tree.num_items() as usize * 4 {
+                    self.queue.push(Reverse(NeighborNode {
+                        id: index << 1,
+                        dist,
+                    }));
+                } else {
+                    self.queue.push(Reverse(NeighborNode {
+                        id: (index << 1) + 1,
+                        dist,
+                    }));
+                }
+            }
+        }
+    }
+}
+
+impl<N: IndexableNum, B: AsRef<[u8]>> RTreeIndex<N> for RTree<N, B> {
     fn boxes(&self) -> &[N] {
-        self.metadata.boxes_slice(&self.buffer)
+        self.metadata.boxes_slice(self.buffer.as_ref())
     }
 
     fn indices(&self) -> Indices<'_> {
-        self.metadata.indices_slice(&self.buffer)
+        self.metadata.indices_slice(self.buffer.as_ref())
     }
 
     fn metadata(&self) -> &RTreeMetadata<N> {
@@ -578,6 +1649,36 @@ pub(crate) fn axis_dist<N: IndexableNum>(k: N, min: N, max: N) -> N {
     }
 }
 
+/// A half-open range `[start, end)` of contiguous positions in the sorted leaf-item buffer, as
+/// returned by [`RTreeIndex::search_ranges`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemRange {
+    /// The first position included in the range.
+    pub start: u32,
+    /// One past the last position included in the range.
+    pub end: u32,
+}
+
+/// Sort leaf positions and coalesce adjacent or overlapping ones into half-open [`ItemRange`]s.
+fn merge_item_ranges(mut positions: Vec<u32>) -> Vec<ItemRange> {
+    positions.sort_unstable();
+
+    let mut ranges: Vec<ItemRange> = Vec::new();
+    for pos in positions {
+        if let Some(last) = ranges.last_mut() {
+            if pos <= last.end {
+                last.end = last.end.max(pos + 1);
+                continue;
+            }
+        }
+        ranges.push(ItemRange {
+            start: pos,
+            end: pos + 1,
+        });
+    }
+    ranges
+}
+
 #[cfg(test)]
 mod test {
     // Replication of tests from flatbush js
@@ -612,10 +1713,11 @@ mod test {
 
     #[cfg(feature = "use-geo_0_31")]
     mod distance_metrics {
-        use crate::rtree::distance::{EuclideanDistance, HaversineDistance};
+        use crate::rtree::distance::{to_owned_geometry, EuclideanDistance, HaversineDistance};
         use crate::rtree::r#trait::SimpleDistanceMetric;
         use crate::rtree::sort::HilbertSort;
         use crate::rtree::{RTreeBuilder, RTreeIndex};
+        use geo_traits::GeometryTrait;
 
         #[test]
         fn test_euclidean_distance_neighbors() {
@@ -641,46 +1743,184 @@ mod test {
             builder.add(139.7, 35.7, 139.7, 35.7); // Tokyo
             let tree = builder.finish::<HilbertSort>();
 
-            let haversine = HaversineDistance::default();
-            let results = tree.neighbors_with_distance(-74.0, 40.7, None, None, &haversine);
+            let haversine = HaversineDistance::default();
+            let results = tree.neighbors_with_distance(-74.0, 40.7, None, None, &haversine);
+
+            // From New York, should find New York first, then London, then Tokyo
+            assert_eq!(results, vec![0, 1, 2]);
+        }
+
+        #[test]
+        fn test_backward_compatibility() {
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(0., 0., 1., 1.);
+            builder.add(2., 2., 3., 3.);
+            builder.add(4., 4., 5., 5.);
+            let tree = builder.finish::<HilbertSort>();
+
+            // Test that original neighbors method still works
+            let results_original = tree.neighbors(0., 0., None, None);
+
+            // Test that new method with Euclidean distance gives same results
+            let euclidean = EuclideanDistance;
+            let results_new = tree.neighbors_with_distance(0., 0., None, None, &euclidean);
+
+            assert_eq!(results_original, results_new);
+        }
+
+        #[test]
+        fn test_max_distance_filtering() {
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(0., 0., 1., 1.);
+            builder.add(2., 2., 3., 3.);
+            builder.add(10., 10., 11., 11.);
+            let tree = builder.finish::<HilbertSort>();
+
+            let euclidean = EuclideanDistance;
+            // Only find neighbors within distance 5
+            let results = tree.neighbors_with_distance(0., 0., None, Some(5.0), &euclidean);
+
+            // Should only find first two items, not the distant third one
+            assert_eq!(results.len(), 2);
+            assert_eq!(results, vec![0, 1]);
+        }
+
+        #[test]
+        fn test_cmp_distance_monotonic_with_distance() {
+            // `cmp_distance`/`cmp_distance_to_bbox` must preserve the same order as the exact
+            // `distance`/`distance_to_bbox`, since EuclideanDistance compares squared distances
+            // under the hood while still reporting exact ones.
+            let euclidean = EuclideanDistance;
+            let points = [(0., 0.), (1., 1.), (3., 2.), (-2., 5.), (10., -10.)];
+
+            for &(x1, y1) in &points {
+                for &(x2, y2) in &points {
+                    for &(x3, y3) in &points {
+                        let a = euclidean.distance(x1, y1, x2, y2);
+                        let b = euclidean.distance(x1, y1, x3, y3);
+                        let cmp_a = euclidean.cmp_distance(x1, y1, x2, y2);
+                        let cmp_b = euclidean.cmp_distance(x1, y1, x3, y3);
+                        assert_eq!(a <= b, cmp_a <= cmp_b);
+                    }
+                }
+            }
+
+            // The `max_distance` threshold must be converted into the same comparison space.
+            let max_distance = 5.0;
+            let cmp_max_distance = euclidean.cmp_max_distance(max_distance);
+            for &(x, y) in &points {
+                let dist = euclidean.distance(0., 0., x, y);
+                let cmp_dist = euclidean.cmp_distance(0., 0., x, y);
+                assert_eq!(dist <= max_distance, cmp_dist <= cmp_max_distance);
+            }
+        }
+
+        #[test]
+        fn test_neighbors_iter_matches_neighbors_with_distance() {
+            let mut builder = RTreeBuilder::<f64>::new(4);
+            builder.add(0., 0., 1., 1.);
+            builder.add(2., 2., 3., 3.);
+            builder.add(4., 4., 5., 5.);
+            builder.add(10., 10., 11., 11.);
+            let tree = builder.finish::<HilbertSort>();
+
+            let euclidean = EuclideanDistance;
 
-            // From New York, should find New York first, then London, then Tokyo
-            assert_eq!(results, vec![0, 1, 2]);
+            // Draining the iterator fully should match `neighbors_with_distance`'s ids.
+            let iter_results: Vec<u32> = tree
+                .neighbors_iter(0., 0., &euclidean)
+                .map(|(index, _)| index)
+                .collect();
+            let vec_results = tree.neighbors_with_distance(0., 0., None, None, &euclidean);
+            assert_eq!(iter_results, vec_results);
+
+            // `.take(k)` over the iterator should match `max_results`.
+            let taken: Vec<u32> = tree
+                .neighbors_iter(0., 0., &euclidean)
+                .take(2)
+                .map(|(index, _)| index)
+                .collect();
+            assert_eq!(
+                taken,
+                tree.neighbors_with_distance(0., 0., Some(2), None, &euclidean)
+            );
+
+            // Distances yielded by the iterator must be nondecreasing.
+            let distances: Vec<f64> = tree
+                .neighbors_iter(0., 0., &euclidean)
+                .map(|(_, dist)| dist)
+                .collect();
+            assert!(distances.windows(2).all(|w| w[0] <= w[1]));
         }
 
         #[test]
-        fn test_backward_compatibility() {
-            let mut builder = RTreeBuilder::<f64>::new(3);
+        fn test_neighbors_approx_exact_at_zero_epsilon() {
+            let mut builder = RTreeBuilder::<f64>::new(4);
             builder.add(0., 0., 1., 1.);
             builder.add(2., 2., 3., 3.);
             builder.add(4., 4., 5., 5.);
+            builder.add(10., 10., 11., 11.);
             let tree = builder.finish::<HilbertSort>();
 
-            // Test that original neighbors method still works
-            let results_original = tree.neighbors(0., 0., None, None);
-
-            // Test that new method with Euclidean distance gives same results
             let euclidean = EuclideanDistance;
-            let results_new = tree.neighbors_with_distance(0., 0., None, None, &euclidean);
+            let exact = tree.neighbors_with_distance(0., 0., Some(3), None, &euclidean);
+            let approx = tree.neighbors_approx(0., 0., 3, 0., &euclidean);
+            assert_eq!(exact, approx);
+        }
 
-            assert_eq!(results_original, results_new);
+        #[test]
+        fn test_neighbors_approx_within_epsilon_factor() {
+            let mut builder = RTreeBuilder::<f64>::new(4);
+            for i in 0..50 {
+                let f = i as f64;
+                builder.add(f, f, f, f);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            let euclidean = EuclideanDistance;
+            let k = 5;
+            let exact = tree.neighbors_with_distance(7.3, 7.3, Some(k), None, &euclidean);
+            let exact_kth_distance = exact
+                .iter()
+                .map(|&id| euclidean.distance(7.3, 7.3, id as f64, id as f64))
+                .fold(0.0_f64, f64::max);
+
+            for &epsilon in &[0.0, 0.1, 0.5, 1.0, 5.0] {
+                let approx = tree.neighbors_approx(7.3, 7.3, k, epsilon, &euclidean);
+                assert_eq!(approx.len(), k);
+                for id in approx {
+                    let dist = euclidean.distance(7.3, 7.3, id as f64, id as f64);
+                    assert!(dist <= exact_kth_distance * (1.0 + epsilon) + 1e-9);
+                }
+            }
         }
 
         #[test]
-        fn test_max_distance_filtering() {
+        fn test_neighbors_with_distance_scored() {
             let mut builder = RTreeBuilder::<f64>::new(3);
             builder.add(0., 0., 1., 1.);
             builder.add(2., 2., 3., 3.);
-            builder.add(10., 10., 11., 11.);
+            builder.add(4., 4., 5., 5.);
             let tree = builder.finish::<HilbertSort>();
 
             let euclidean = EuclideanDistance;
-            // Only find neighbors within distance 5
-            let results = tree.neighbors_with_distance(0., 0., None, Some(5.0), &euclidean);
-
-            // Should only find first two items, not the distant third one
-            assert_eq!(results.len(), 2);
-            assert_eq!(results, vec![0, 1]);
+            let scored = tree.neighbors_with_distance_scored(0., 0., None, None, &euclidean);
+            let indices_only = tree.neighbors_with_distance(0., 0., None, None, &euclidean);
+
+            // Index-only form drops exactly the second tuple element.
+            let scored_indices: Vec<u32> = scored.iter().map(|&(index, _)| index).collect();
+            assert_eq!(scored_indices, indices_only);
+            assert_eq!(scored.len(), 3);
+
+            // The nearest box, (0,0)-(1,1), touches the query point.
+            assert_eq!(scored[0], (0, 0.0));
+
+            // Distances are the true Euclidean distance (not the squared comparison key
+            // `EuclideanDistance` orders its queue by), and reported in nondecreasing order.
+            let expected = [0.0, 8.0_f64.sqrt(), 32.0_f64.sqrt()];
+            for (&(_, dist), expected_dist) in scored.iter().zip(expected) {
+                assert!((dist - expected_dist).abs() < 1e-9);
+            }
         }
 
         #[test]
@@ -738,8 +1978,14 @@ mod test {
                 }
             }
             impl<N: IndexableNum> DistanceMetric<N> for SimpleMetric {
-                fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-                    N::from_f64(Euclidean.distance(geom1, geom2)).unwrap_or(N::max_value())
+                fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+                    &self,
+                    geom1: &G1,
+                    geom2: &G2,
+                ) -> N {
+                    let geom1 = to_owned_geometry(geom1);
+                    let geom2 = to_owned_geometry(geom2);
+                    N::from_f64(Euclidean.distance(&geom1, &geom2)).unwrap_or(N::max_value())
                 }
             }
 
@@ -754,6 +2000,169 @@ mod test {
             assert_eq!(results[2], 2);
         }
 
+        #[test]
+        #[cfg(feature = "use-geo_0_31")]
+        fn test_geometry_neighbors_scored() {
+            use crate::rtree::distance::SliceGeometryAccessor;
+            use geo_0_31::{Geometry, Point};
+
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(0., 0., 2., 2.); // Item 0
+            builder.add(5., 5., 7., 7.); // Item 1
+            builder.add(10., 10., 12., 12.); // Item 2
+            let tree = builder.finish::<HilbertSort>();
+
+            let geometries: Vec<Geometry<f64>> = vec![
+                Geometry::Point(Point::new(1.0, 1.0)),
+                Geometry::Point(Point::new(6.0, 6.0)),
+                Geometry::Point(Point::new(11.0, 11.0)),
+            ];
+
+            let query_geom = Geometry::Point(Point::new(3.0, 3.0));
+            let metric = EuclideanDistance;
+            let accessor = SliceGeometryAccessor::new(&geometries);
+
+            let scored =
+                tree.neighbors_geometry_scored(&query_geom, None, None, &metric, &accessor);
+            let indices_only = tree.neighbors_geometry(&query_geom, None, None, &metric, &accessor);
+
+            // Index-only form drops exactly the second tuple element.
+            let scored_indices: Vec<u32> = scored.iter().map(|&(index, _)| index).collect();
+            assert_eq!(scored_indices, indices_only);
+
+            // Each distance is the point-to-point Euclidean distance from the query to the
+            // item's geometry, in nondecreasing order.
+            assert_eq!(scored[0].0, 0);
+            assert!((scored[0].1 - 8.0_f64.sqrt()).abs() < 1e-9);
+            assert!(scored.windows(2).all(|w| w[0].1 <= w[1].1));
+        }
+
+        #[test]
+        fn test_within_distance() {
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(0., 0., 1., 1.);
+            builder.add(2., 2., 3., 3.);
+            builder.add(10., 10., 11., 11.);
+            let tree = builder.finish::<HilbertSort>();
+
+            let euclidean = EuclideanDistance;
+
+            // Radius 5 should find the first two items but not the distant third one, matching
+            // `test_max_distance_filtering`'s reference set for the equivalent ordered query.
+            let mut results = tree.within_distance(0., 0., 5.0, &euclidean);
+            results.sort();
+            assert_eq!(results, vec![0, 1]);
+
+            // A radius covering everything returns every item, unordered.
+            let mut results = tree.within_distance(0., 0., 100.0, &euclidean);
+            results.sort();
+            assert_eq!(results, vec![0, 1, 2]);
+
+            // A radius reaching no box returns nothing.
+            assert!(tree.within_distance(-5., -5., 0.5, &euclidean).is_empty());
+        }
+
+        #[test]
+        fn test_within_distance_scored_matches_neighbors_with_distance() {
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(0., 0., 1., 1.);
+            builder.add(2., 2., 3., 3.);
+            builder.add(4., 4., 5., 5.);
+            builder.add(10., 10., 11., 11.);
+            let tree = builder.finish::<HilbertSort>();
+
+            let euclidean = EuclideanDistance;
+            let radius = 6.0;
+
+            // Every result is within the radius, and every distance is exact (not a squared
+            // comparison key).
+            let scored = tree.within_distance_scored(0., 0., radius, &euclidean);
+            // The query point lies below and left of every box, so each box's nearest point is its
+            // min corner.
+            let min_corners = [(0., 0.), (2., 2.), (4., 4.), (10., 10.)];
+            for &(id, dist) in &scored {
+                assert!(dist <= radius);
+                let (corner_x, corner_y) = min_corners[id as usize];
+                let expected = euclidean.distance(0., 0., corner_x, corner_y);
+                assert!((dist - expected).abs() < 1e-9);
+            }
+
+            // The unordered result set matches the reference set obtained from the ordered,
+            // max_distance-filtered k-NN query.
+            let mut from_within: Vec<u32> = scored.iter().map(|&(index, _)| index).collect();
+            from_within.sort();
+            let mut from_neighbors =
+                tree.neighbors_with_distance(0., 0., None, Some(radius), &euclidean);
+            from_neighbors.sort();
+            assert_eq!(from_within, from_neighbors);
+        }
+
+        #[test]
+        fn test_within_distance_sorted() {
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(4., 4., 5., 5.);
+            builder.add(0., 0., 1., 1.);
+            builder.add(2., 2., 3., 3.);
+            let tree = builder.finish::<HilbertSort>();
+
+            let euclidean = EuclideanDistance;
+            let sorted = tree.within_distance_sorted(0., 0., 100.0, &euclidean);
+
+            // Ascending distance order from the query point, regardless of insertion order.
+            assert_eq!(sorted, vec![1, 2, 0]);
+        }
+
+        #[test]
+        #[cfg(feature = "use-geo_0_31")]
+        fn test_within_distance_geometry() {
+            use crate::rtree::distance::SliceGeometryAccessor;
+            use geo_0_31::{Geometry, Point};
+
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(0., 0., 2., 2.); // Item 0
+            builder.add(5., 5., 7., 7.); // Item 1
+            builder.add(10., 10., 12., 12.); // Item 2
+            let tree = builder.finish::<HilbertSort>();
+
+            let geometries: Vec<Geometry<f64>> = vec![
+                Geometry::Point(Point::new(1.0, 1.0)),
+                Geometry::Point(Point::new(6.0, 6.0)),
+                Geometry::Point(Point::new(11.0, 11.0)),
+            ];
+
+            let query_geom = Geometry::Point(Point::new(3.0, 3.0));
+            let metric = EuclideanDistance;
+            let accessor = SliceGeometryAccessor::new(&geometries);
+
+            let scored =
+                tree.within_distance_geometry_scored(&query_geom, 10.0, &metric, &accessor);
+            let indices_only = tree.within_distance_geometry(&query_geom, 10.0, &metric, &accessor);
+
+            let scored_indices: Vec<u32> = scored.iter().map(|&(index, _)| index).collect();
+            assert_eq!(
+                scored_indices
+                    .iter()
+                    .copied()
+                    .collect::<std::collections::HashSet<_>>(),
+                indices_only
+                    .iter()
+                    .copied()
+                    .collect::<std::collections::HashSet<_>>(),
+            );
+
+            // Items 0 and 1 are within radius 10 of the query point; item 2 is not.
+            assert_eq!(
+                indices_only
+                    .iter()
+                    .copied()
+                    .collect::<std::collections::HashSet<_>>(),
+                [0, 1].into_iter().collect::<std::collections::HashSet<_>>(),
+            );
+            for &(_, dist) in &scored {
+                assert!(dist <= 10.0);
+            }
+        }
+
         #[test]
         #[cfg(feature = "use-geo_0_31")]
         fn test_geometry_neighbors_linestring() {
@@ -818,8 +2227,14 @@ mod test {
                 }
             }
             impl<N: IndexableNum> DistanceMetric<N> for SimpleMetric {
-                fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-                    N::from_f64(Euclidean.distance(geom1, geom2)).unwrap_or(N::max_value())
+                fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+                    &self,
+                    geom1: &G1,
+                    geom2: &G2,
+                ) -> N {
+                    let geom1 = to_owned_geometry(geom1);
+                    let geom2 = to_owned_geometry(geom2);
+                    N::from_f64(Euclidean.distance(&geom1, &geom2)).unwrap_or(N::max_value())
                 }
             }
 
@@ -889,8 +2304,14 @@ mod test {
                 }
             }
             impl<N: IndexableNum> DistanceMetric<N> for SimpleMetric {
-                fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
-                    N::from_f64(Euclidean.distance(geom1, geom2)).unwrap_or(N::max_value())
+                fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+                    &self,
+                    geom1: &G1,
+                    geom2: &G2,
+                ) -> N {
+                    let geom1 = to_owned_geometry(geom1);
+                    let geom2 = to_owned_geometry(geom2);
+                    N::from_f64(Euclidean.distance(&geom1, &geom2)).unwrap_or(N::max_value())
                 }
             }
 
@@ -954,7 +2375,13 @@ mod test {
                 }
             }
             impl<N: IndexableNum> DistanceMetric<N> for HaversineMetric {
-                fn distance_to_geometry(&self, geom1: &Geometry<f64>, geom2: &Geometry<f64>) -> N {
+                fn distance_to_geometry<G1: GeometryTrait<T = f64>, G2: GeometryTrait<T = f64>>(
+                    &self,
+                    geom1: &G1,
+                    geom2: &G2,
+                ) -> N {
+                    let geom1 = to_owned_geometry(geom1);
+                    let geom2 = to_owned_geometry(geom2);
                     let c1 = geom1.centroid().unwrap_or(Point::new(0.0, 0.0));
                     let c2 = geom2.centroid().unwrap_or(Point::new(0.0, 0.0));
                     N::from_f64(Haversine.distance(c1, c2)).unwrap_or(N::max_value())
@@ -970,4 +2397,304 @@ mod test {
             assert_eq!(results[0], 0);
         }
     }
+
+    #[cfg(feature = "use-geo_0_31")]
+    mod exact_predicate {
+        use super::*;
+        use crate::rtree::distance::SliceGeometryAccessor;
+        use geo_0_31::{Geometry, LineString, Polygon};
+
+        fn square(min: f64, max: f64) -> Geometry<f64> {
+            Geometry::Polygon(Polygon::new(
+                LineString::from(vec![
+                    (min, min),
+                    (max, min),
+                    (max, max),
+                    (min, max),
+                    (min, min),
+                ]),
+                vec![],
+            ))
+        }
+
+        #[test]
+        fn query_point_refines_away_box_false_positives() {
+            let mut builder = RTreeBuilder::<f64>::new(2);
+            builder.add(0., 0., 2., 2.);
+            builder.add(10., 10., 12., 12.);
+            let tree = builder.finish::<HilbertSort>();
+
+            let geometries = vec![square(0., 2.), square(10., 12.)];
+            let accessor = SliceGeometryAccessor::new(&geometries);
+
+            // Inside the first square's bbox but not inside the second's.
+            assert_eq!(tree.query_point(1., 1., &accessor), vec![0]);
+            assert_eq!(tree.query_point(50., 50., &accessor), Vec::<u32>::new());
+        }
+
+        #[test]
+        fn query_rect_refines_away_box_false_positives() {
+            let mut builder = RTreeBuilder::<f64>::new(2);
+            builder.add(0., 0., 2., 2.);
+            builder.add(10., 10., 12., 12.);
+            let tree = builder.finish::<HilbertSort>();
+
+            let geometries = vec![square(0., 2.), square(10., 12.)];
+            let accessor = SliceGeometryAccessor::new(&geometries);
+
+            let results = tree.query_rect(1., 1., 3., 3., &accessor);
+            assert_eq!(results, vec![0]);
+        }
+    }
+
+    mod ranges {
+        use crate::rtree::sort::HilbertSort;
+        use crate::rtree::{ItemRange, RTreeBuilder, RTreeIndex};
+
+        #[test]
+        fn search_ranges_covers_same_items_as_search() {
+            let mut builder = RTreeBuilder::<f64>::new(50);
+            for i in 0..50 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            let mut from_search = tree.search(10., 10., 20., 20.);
+            from_search.sort_unstable();
+
+            let indices = tree.indices();
+            let mut from_ranges: Vec<u32> = tree
+                .search_ranges(10., 10., 20., 20.)
+                .into_iter()
+                .flat_map(|range| range.start..range.end)
+                .map(|pos| indices.get(pos as usize).try_into().unwrap())
+                .collect();
+            from_ranges.sort_unstable();
+
+            assert_eq!(from_ranges, from_search);
+        }
+
+        #[test]
+        fn search_ranges_on_empty_tree_is_empty() {
+            let builder = RTreeBuilder::<f64>::new(0);
+            let tree = builder.finish::<HilbertSort>();
+            assert_eq!(tree.search_ranges(0., 0., 1., 1.), vec![]);
+        }
+
+        #[test]
+        fn merge_item_ranges_coalesces_touching_and_overlapping_positions() {
+            assert_eq!(
+                super::super::merge_item_ranges(vec![5, 1, 2, 3, 9, 10, 3]),
+                vec![
+                    ItemRange { start: 1, end: 4 },
+                    ItemRange { start: 5, end: 6 },
+                    ItemRange { start: 9, end: 11 },
+                ]
+            );
+        }
+    }
+
+    mod visit {
+        use std::ops::ControlFlow;
+
+        use crate::rtree::sort::HilbertSort;
+        use crate::rtree::{RTreeBuilder, RTreeIndex};
+
+        #[test]
+        fn search_visit_finds_the_same_items_as_search() {
+            let mut builder = RTreeBuilder::<f64>::new(50);
+            for i in 0..50 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            let mut from_search = tree.search(10., 10., 20., 20.);
+            from_search.sort_unstable();
+
+            let mut from_visit: Vec<u32> = vec![];
+            tree.search_visit(10., 10., 20., 20., |index| {
+                from_visit.push(index as u32);
+                ControlFlow::Continue(())
+            });
+            from_visit.sort_unstable();
+
+            assert_eq!(from_visit, from_search);
+        }
+
+        #[test]
+        fn search_visit_stops_early_on_break() {
+            let mut builder = RTreeBuilder::<f64>::new(50);
+            for i in 0..50 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            let mut visited = 0;
+            tree.search_visit(10., 10., 20., 20., |_index| {
+                visited += 1;
+                ControlFlow::Break(())
+            });
+
+            assert_eq!(visited, 1);
+        }
+    }
+
+    mod walk {
+        use crate::r#type::IndexableNum;
+        use crate::rtree::sort::HilbertSort;
+        use crate::rtree::{RTreeBuilder, RTreeIndex};
+
+        #[test]
+        fn walk_with_a_box_predicate_finds_the_same_items_as_search() {
+            let mut builder = RTreeBuilder::<f64>::new(50);
+            for i in 0..50 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            let mut from_search = tree.search(10., 10., 20., 20.);
+            from_search.sort_unstable();
+
+            fn intersects_query<N: IndexableNum, T: RTreeIndex<N>>(
+                node: &crate::rtree::Node<'_, N, T>,
+            ) -> bool {
+                node.min_x() <= N::from_f64(20.).unwrap()
+                    && node.max_x() >= N::from_f64(10.).unwrap()
+                    && node.min_y() <= N::from_f64(20.).unwrap()
+                    && node.max_y() >= N::from_f64(10.).unwrap()
+            }
+
+            let mut from_walk: Vec<u32> = vec![];
+            tree.walk(
+                |node| intersects_query(node),
+                |node| {
+                    if intersects_query(node) {
+                        from_walk.push(node.index() as u32);
+                    }
+                },
+            );
+            from_walk.sort_unstable();
+
+            assert_eq!(from_walk, from_search);
+        }
+
+        #[test]
+        fn walk_never_visits_leaves_under_a_pruned_node() {
+            let mut builder = RTreeBuilder::<f64>::new(50);
+            for i in 0..50 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            let mut leaves_visited = 0;
+            tree.walk(|_node| false, |_node| leaves_visited += 1);
+
+            assert_eq!(leaves_visited, 0);
+        }
+    }
+
+    mod partitions {
+        use crate::rtree::sort::HilbertSort;
+        use crate::rtree::{RTreeBuilder, RTreeIndex};
+
+        #[test]
+        fn partitions_for_box_only_returns_intersecting_partitions() {
+            let mut builder = RTreeBuilder::<f64>::new(50);
+            for i in 0..50 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            let all_partitions = tree.partitions_for_box(0., 0., 50., 50.).unwrap();
+            let queried = tree.partitions_for_box(10., 10., 11., 11.).unwrap();
+
+            assert!(!queried.is_empty());
+            assert!(queried.len() < all_partitions.len());
+            assert!(queried.iter().all(|id| all_partitions.contains(id)));
+        }
+
+        #[test]
+        fn partitions_for_box_matches_items_found_by_search() {
+            let mut builder = RTreeBuilder::<f64>::new_with_node_size(40, 4);
+            for i in 0..40 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            // Every item returned by `search` must live in a node size worth of one of the
+            // returned partitions.
+            let items = tree.search(10., 10., 12., 12.);
+            assert!(!items.is_empty());
+
+            let partition_ids = tree.partitions_for_box(10., 10., 12., 12.).unwrap();
+            let boxes = tree.boxes_at_level(1).unwrap();
+            for &partition_id in &partition_ids {
+                let pos = partition_id as usize * 4;
+                assert!(boxes[pos] <= 12. && boxes[pos + 2] >= 10.);
+            }
+
+            // Leaf position `p` lies in the level-1 node `p / node_size`.
+            let indices = tree.indices();
+            for pos in 0..40 {
+                if items.contains(&(indices.get(pos) as u32)) {
+                    assert!(partition_ids.contains(&((pos / 4) as u32)));
+                }
+            }
+        }
+
+        #[test]
+        fn partitions_for_box_errors_on_a_tree_too_small_to_have_a_level_1() {
+            let mut builder = RTreeBuilder::<f64>::new(1);
+            builder.add(0., 0., 1., 1.);
+            let tree = builder.finish::<HilbertSort>();
+
+            assert!(tree.partitions_for_box(0., 0., 1., 1.).is_err());
+        }
+    }
+
+    mod validation {
+        use crate::rtree::sort::HilbertSort;
+        use crate::rtree::{RTreeBuilder, RTreeIndex};
+        use crate::GeoIndexError;
+
+        #[test]
+        fn validate_passes_on_a_tree_built_normally() {
+            let mut builder = RTreeBuilder::<f64>::new(50);
+            for i in 0..50 {
+                let x = i as f64;
+                builder.add(x, x, x + 1., x + 1.);
+            }
+            let tree = builder.finish::<HilbertSort>();
+
+            assert!(tree.validate().is_ok());
+        }
+
+        #[test]
+        fn validate_catches_a_child_box_escaping_its_parent() {
+            let mut builder = RTreeBuilder::<f64>::new(3);
+            builder.add(0., 0., 1., 1.);
+            builder.add(2., 2., 3., 3.);
+            builder.add(4., 4., 5., 5.);
+            let mut tree = builder.finish::<HilbertSort>();
+            assert!(tree.validate().is_ok());
+
+            // Corrupt the first leaf box's min_x (the first f64 after the 8-byte header) so it
+            // no longer fits inside its parent's box.
+            tree.buffer[8..16].copy_from_slice(&(-1000.0f64).to_le_bytes());
+
+            let err = tree.validate().unwrap_err();
+            let GeoIndexError::Invalid { path, .. } = err else {
+                panic!("expected GeoIndexError::Invalid");
+            };
+            // The corrupted box is the very first one in the buffer, i.e. `boxes[0..4]`.
+            assert_eq!(path.last().unwrap(), &(0, 0));
+        }
+    }
 }
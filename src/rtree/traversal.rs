@@ -8,8 +8,16 @@ use core::mem::take;
 use std::marker::PhantomData;
 
 /// An internal node in the RTree.
+///
+/// `D` is the number of dimensions a box has (2 for the usual x/y case). A box occupies `2 * D`
+/// consecutive scalars in [`RTreeIndex::boxes`]: the `D` minimums followed by the `D` maximums,
+/// e.g. `[min_x, min_y, max_x, max_y]` for `D = 2`.
+///
+/// Note that [`RTreeBuilder`][crate::rtree::RTreeBuilder] and the [`sort`][crate::rtree::sort]
+/// strategies only build `D = 2` trees today, so `D` other than the default only makes sense once
+/// a tree with that many dimensions actually exists to traverse.
 #[derive(Debug, Clone)]
-pub struct Node<'a, N: IndexableNum, T: RTreeIndex<N>> {
+pub struct Node<'a, N: IndexableNum, T: RTreeIndex<N>, const D: usize = 2> {
     /// The tree that this node is a reference onto
     tree: &'a T,
 
@@ -18,20 +26,25 @@ pub struct Node<'a, N: IndexableNum, T: RTreeIndex<N>> {
     /// ```notest
     /// self.tree.boxes()[self.pos]
     /// ```
-    /// accesses the `min_x` coordinate of this node.
+    /// accesses the minimum value of this node's first dimension (`min_x` when `D == 2`).
     ///
     /// This also relates to the children and the insertion index. When this is `<
-    /// self.tree.num_items() * 4`, it means it's a leaf node at the bottom of the tree. In this
-    /// case, calling `>> 2` on this finds the original insertion index.
+    /// self.tree.num_items() as usize * Self::BOX_LEN`, it means it's a leaf node at the bottom of
+    /// the tree. In this case, dividing this by `Self::BOX_LEN` finds the original insertion
+    /// index.
     ///
-    /// When this is `>= self.tree.num_items() * 4`, it means it's _not_ a leaf node, and calling
-    /// `>> 2` retrieves the `pos` of the first of its children.
+    /// When this is `>= self.tree.num_items() as usize * Self::BOX_LEN`, it means it's _not_ a
+    /// leaf node, and dividing by `Self::BOX_LEN` retrieves the `pos` of the first of its
+    /// children.
     pos: usize,
 
     phantom: PhantomData<N>,
 }
 
-impl<'a, N: IndexableNum, T: RTreeIndex<N>> Node<'a, N, T> {
+impl<'a, N: IndexableNum, T: RTreeIndex<N>, const D: usize> Node<'a, N, T, D> {
+    /// The number of scalars a box occupies: `D` minimums followed by `D` maximums.
+    const BOX_LEN: usize = 2 * D;
+
     fn new(tree: &'a T, pos: usize) -> Self {
         Self {
             tree,
@@ -41,7 +54,7 @@ impl<'a, N: IndexableNum, T: RTreeIndex<N>> Node<'a, N, T> {
     }
 
     pub(crate) fn from_root(tree: &'a T) -> Self {
-        let root_index = tree.boxes().len() - 4;
+        let root_index = tree.boxes().len() - Self::BOX_LEN;
         Self {
             tree,
             pos: root_index,
@@ -49,29 +62,41 @@ impl<'a, N: IndexableNum, T: RTreeIndex<N>> Node<'a, N, T> {
         }
     }
 
+    /// Get the minimum value of this node along the given dimension (`0` is `x`, `1` is `y`, ...).
+    pub fn min(&self, dim: usize) -> N {
+        debug_assert!(dim < D);
+        self.tree.boxes()[self.pos + dim]
+    }
+
+    /// Get the maximum value of this node along the given dimension (`0` is `x`, `1` is `y`, ...).
+    pub fn max(&self, dim: usize) -> N {
+        debug_assert!(dim < D);
+        self.tree.boxes()[self.pos + D + dim]
+    }
+
     /// Get the minimum `x` value of this node.
     pub fn min_x(&self) -> N {
-        self.tree.boxes()[self.pos]
+        self.min(0)
     }
 
     /// Get the minimum `y` value of this node.
     pub fn min_y(&self) -> N {
-        self.tree.boxes()[self.pos + 1]
+        self.min(1)
     }
 
     /// Get the maximum `x` value of this node.
     pub fn max_x(&self) -> N {
-        self.tree.boxes()[self.pos + 2]
+        self.max(0)
     }
 
     /// Get the maximum `y` value of this node.
     pub fn max_y(&self) -> N {
-        self.tree.boxes()[self.pos + 3]
+        self.max(1)
     }
 
     /// Returns `true` if this is a leaf node without children.
     pub fn is_leaf(&self) -> bool {
-        self.pos < self.tree.num_items() * 4
+        self.pos < self.tree.num_items() as usize * Self::BOX_LEN
     }
 
     /// Returns `true` if this is an intermediate node with children.
@@ -80,38 +105,41 @@ impl<'a, N: IndexableNum, T: RTreeIndex<N>> Node<'a, N, T> {
     }
 
     /// Returns `true` if this node intersects another node.
-    pub fn intersects<T2: RTreeIndex<N>>(&self, other: &Node<N, T2>) -> bool {
-        if self.max_x() < other.min_x() {
-            return false;
-        }
-
-        if self.max_y() < other.min_y() {
-            return false;
-        }
-
-        if self.min_x() > other.max_x() {
-            return false;
-        }
+    ///
+    /// The two nodes intersect if their boxes overlap on every dimension.
+    pub fn intersects<T2: RTreeIndex<N>>(&self, other: &Node<N, T2, D>) -> bool {
+        (0..D).all(|dim| self.max(dim) >= other.min(dim) && self.min(dim) <= other.max(dim))
+    }
 
-        if self.min_y() > other.max_y() {
-            return false;
-        }
+    /// Returns `true` if this node's box fully encloses `other`'s box on every dimension.
+    pub fn contains<T2: RTreeIndex<N>>(&self, other: &Node<N, T2, D>) -> bool {
+        (0..D).all(|dim| self.min(dim) <= other.min(dim) && self.max(dim) >= other.max(dim))
+    }
 
-        true
+    /// The minimum gap between this node's box and `other`'s box, i.e. the Euclidean distance
+    /// between their closest points. This is `N::zero()` when the boxes intersect.
+    pub fn box_gap<T2: RTreeIndex<N>>(&self, other: &Node<N, T2, D>) -> Option<N> {
+        let sum_sq = (0..D)
+            .map(|dim| {
+                let d = axis_gap(self.min(dim), self.max(dim), other.min(dim), other.max(dim));
+                d * d
+            })
+            .fold(N::zero(), |acc, v| acc + v);
+        sum_sq.sqrt()
     }
 
     /// Returns an iterator over the child nodes of this node. This must only be called if
     /// `is_parent` is `true`.
-    pub fn children(&self) -> impl Iterator<Item = Node<'_, N, T>> {
+    pub fn children(&self) -> impl Iterator<Item = Node<'_, N, T, D>> {
         debug_assert!(self.is_parent());
 
         // find the start and end indexes of the children of this node
-        let start_child_pos = self.tree.indices().get(self.pos >> 2);
-        let end_children_pos = (start_child_pos + self.tree.node_size() * 4)
+        let start_child_pos = self.tree.indices().get(self.pos / Self::BOX_LEN);
+        let end_children_pos = (start_child_pos + self.tree.node_size() as usize * Self::BOX_LEN)
             .min(upper_bound(start_child_pos, self.tree.level_bounds()));
 
         (start_child_pos..end_children_pos)
-            .step_by(4)
+            .step_by(Self::BOX_LEN)
             .map(|pos| Node::new(self.tree, pos))
     }
 
@@ -119,11 +147,11 @@ impl<'a, N: IndexableNum, T: RTreeIndex<N>> Node<'a, N, T> {
     /// check with `Self::is_leaf`.
     pub fn index(&self) -> usize {
         debug_assert!(self.is_leaf());
-        self.tree.indices().get(self.pos >> 2)
+        self.tree.indices().get(self.pos / Self::BOX_LEN)
     }
 }
 
-/// A single coordinate.
+/// A single 2D coordinate.
 ///
 /// Used in the implementation of RectTrait for Node.
 pub struct Coord<N: IndexableNum> {
@@ -155,7 +183,9 @@ impl<N: IndexableNum> CoordTrait for Coord<N> {
     }
 }
 
-impl<N: IndexableNum, T: RTreeIndex<N>> RectTrait for Node<'_, N, T> {
+// `geo_traits::Dimensions` only models up to 4 dimensions (and geo's own geometry types are
+// 2D/3D), so this predicate-support impl is only provided for the `D = 2` specialization.
+impl<N: IndexableNum, T: RTreeIndex<N>> RectTrait for Node<'_, N, T, 2> {
     type T = N;
     type CoordType<'a>
         = Coord<N>
@@ -193,6 +223,9 @@ where
     right: &'a T2,
     todo_list: Vec<(usize, usize)>,
     candidates: Vec<usize>,
+    /// Set when `left` and `right` are the same tree, so that each unordered pair is only ever
+    /// pushed once (by requiring `node1.pos <= node2.pos`) and `(i, i)` self-pairs are dropped.
+    self_join: bool,
     phantom: PhantomData<N>,
 }
 
@@ -208,6 +241,7 @@ where
             right: root2,
             todo_list: Vec::new(),
             candidates: Vec::new(),
+            self_join: false,
             phantom: PhantomData,
         };
         intersections.add_intersecting_children(&root1.root(), &root2.root());
@@ -221,6 +255,7 @@ where
             right: root2.tree,
             todo_list: Vec::new(),
             candidates: Vec::new(),
+            self_join: false,
             phantom: PhantomData,
         };
         intersections.add_intersecting_children(root1, root2);
@@ -228,6 +263,9 @@ where
     }
 
     fn push_if_intersecting(&mut self, node1: &'_ Node<N, T1>, node2: &'_ Node<N, T2>) {
+        if self.self_join && node1.pos > node2.pos {
+            return;
+        }
         if node1.intersects(node2) {
             self.todo_list.push((node1.pos, node2.pos));
         }
@@ -259,6 +297,30 @@ where
     }
 }
 
+impl<'a, N, T> IntersectionIterator<'a, N, T, T>
+where
+    N: IndexableNum,
+    T: RTreeIndex<N>,
+{
+    /// Construct an iterator over the unordered pairs of a single tree that intersect with
+    /// themselves, as used for "contiguity" queries.
+    ///
+    /// Unlike [`Self::from_trees`] called with the same tree on both sides, this emits each
+    /// unordered pair `(i, j)` at most once (never also `(j, i)`) and skips `(i, i)` self-pairs.
+    pub(crate) fn from_tree_self(tree: &'a T) -> Self {
+        let mut intersections = IntersectionIterator {
+            left: tree,
+            right: tree,
+            todo_list: Vec::new(),
+            candidates: Vec::new(),
+            self_join: true,
+            phantom: PhantomData,
+        };
+        intersections.add_intersecting_children(&tree.root(), &tree.root());
+        intersections
+    }
+}
+
 impl<N, T1, T2> Iterator for IntersectionIterator<'_, N, T1, T2>
 where
     N: IndexableNum,
@@ -272,7 +334,13 @@ where
             let left = Node::new(self.left, left_index);
             let right = Node::new(self.right, right_index);
             match (left.is_leaf(), right.is_leaf()) {
-                (true, true) => return Some((left.index(), right.index())),
+                (true, true) => {
+                    let (left_index, right_index) = (left.index(), right.index());
+                    if self.self_join && left_index == right_index {
+                        continue;
+                    }
+                    return Some((left_index, right_index));
+                }
                 (true, false) => right
                     .children()
                     .for_each(|c| self.push_if_intersecting(&left, &c)),
@@ -286,6 +354,161 @@ where
     }
 }
 
+/// A predicate evaluated between pairs of boxes during a [`SpatialJoinIterator`], as used by
+/// [`RTreeIndex::spatial_join`][crate::rtree::RTreeIndex::spatial_join].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpatialPredicate<N: IndexableNum> {
+    /// The left tree's box fully encloses the right tree's box.
+    Contains,
+    /// The right tree's box fully encloses the left tree's box; the symmetric of [`Self::Contains`].
+    Within,
+    /// The minimum gap between the two boxes is at most the given distance.
+    DistanceWithin(N),
+}
+
+impl<N: IndexableNum> SpatialPredicate<N> {
+    /// Whether a pair of parent boxes could possibly contain a descendant pair satisfying this
+    /// predicate. Used to prune the tree walk before it reaches leaves.
+    fn could_satisfy<T1: RTreeIndex<N>, T2: RTreeIndex<N>>(
+        &self,
+        node1: &Node<N, T1>,
+        node2: &Node<N, T2>,
+    ) -> bool {
+        match self {
+            // Containment implies overlap, so the regular intersection test is a valid (if
+            // loose) necessary condition for pruning.
+            Self::Contains | Self::Within => node1.intersects(node2),
+            Self::DistanceWithin(d) => node1.box_gap(node2).is_some_and(|gap| gap <= *d),
+        }
+    }
+
+    /// Whether a concrete pair of boxes actually satisfies this predicate.
+    fn matches<T1: RTreeIndex<N>, T2: RTreeIndex<N>>(
+        &self,
+        node1: &Node<N, T1>,
+        node2: &Node<N, T2>,
+    ) -> bool {
+        match self {
+            Self::Contains => node1.contains(node2),
+            Self::Within => node2.contains(node1),
+            Self::DistanceWithin(d) => node1.box_gap(node2).is_some_and(|gap| gap <= *d),
+        }
+    }
+}
+
+/// An iterator over the pairs of objects in two trees whose boxes satisfy a [`SpatialPredicate`].
+///
+/// This generalizes [`IntersectionIterator`] (which is hard-coded to box intersection) to
+/// arbitrary box predicates, reusing the same parent-pruning tree walk.
+pub(crate) struct SpatialJoinIterator<'a, N, T1, T2>
+where
+    N: IndexableNum,
+    T1: RTreeIndex<N>,
+    T2: RTreeIndex<N>,
+{
+    left: &'a T1,
+    right: &'a T2,
+    predicate: SpatialPredicate<N>,
+    todo_list: Vec<(usize, usize)>,
+    candidates: Vec<usize>,
+}
+
+impl<'a, N, T1, T2> SpatialJoinIterator<'a, N, T1, T2>
+where
+    N: IndexableNum,
+    T1: RTreeIndex<N>,
+    T2: RTreeIndex<N>,
+{
+    pub(crate) fn new(root1: &'a T1, root2: &'a T2, predicate: SpatialPredicate<N>) -> Self {
+        let mut join = SpatialJoinIterator {
+            left: root1,
+            right: root2,
+            predicate,
+            todo_list: Vec::new(),
+            candidates: Vec::new(),
+        };
+        join.add_candidate_children(&root1.root(), &root2.root());
+        join
+    }
+
+    fn push_if_could_satisfy(&mut self, node1: &'_ Node<N, T1>, node2: &'_ Node<N, T2>) {
+        if self.predicate.could_satisfy(node1, node2) {
+            self.todo_list.push((node1.pos, node2.pos));
+        }
+    }
+
+    fn add_candidate_children(&mut self, parent1: &'_ Node<N, T1>, parent2: &'_ Node<N, T2>) {
+        if !self.predicate.could_satisfy(parent1, parent2) {
+            return;
+        }
+
+        let children1 = parent1
+            .children()
+            .filter(|c1| self.predicate.could_satisfy(c1, parent2));
+
+        let mut children2 = take(&mut self.candidates);
+        children2.extend(
+            parent2
+                .children()
+                .filter(|c2| self.predicate.could_satisfy(c2, parent1))
+                .map(|c| c.pos),
+        );
+
+        for child1 in children1 {
+            for child2 in &children2 {
+                self.push_if_could_satisfy(&child1, &Node::new(self.right, *child2));
+            }
+        }
+
+        children2.clear();
+        self.candidates = children2;
+    }
+}
+
+impl<N, T1, T2> Iterator for SpatialJoinIterator<'_, N, T1, T2>
+where
+    N: IndexableNum,
+    T1: RTreeIndex<N>,
+    T2: RTreeIndex<N>,
+{
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((left_index, right_index)) = self.todo_list.pop() {
+            let left = Node::new(self.left, left_index);
+            let right = Node::new(self.right, right_index);
+            match (left.is_leaf(), right.is_leaf()) {
+                (true, true) => {
+                    if self.predicate.matches(&left, &right) {
+                        return Some((left.index(), right.index()));
+                    }
+                }
+                (true, false) => right
+                    .children()
+                    .for_each(|c| self.push_if_could_satisfy(&left, &c)),
+                (false, true) => left
+                    .children()
+                    .for_each(|c| self.push_if_could_satisfy(&c, &right)),
+                (false, false) => self.add_candidate_children(&left, &right),
+            }
+        }
+        None
+    }
+}
+
+/// 1D gap between two ranges `[a_min, a_max]` and `[b_min, b_max]`. Zero when the ranges overlap
+/// or touch.
+#[inline]
+fn axis_gap<N: IndexableNum>(a_min: N, a_max: N, b_min: N, b_max: N) -> N {
+    if a_max < b_min {
+        b_min - a_max
+    } else if b_max < a_min {
+        a_min - b_max
+    } else {
+        N::zero()
+    }
+}
+
 /**
  * Binary search for the first value in the array bigger than the given.
  * @param {number} value
@@ -335,6 +558,88 @@ mod test {
         let level_1 = root_node.children().collect::<Vec<_>>();
         assert_eq!(level_1.len(), level_1_boxes.len() / 4);
     }
+
+    #[test]
+    fn dim_indexed_accessors_agree_with_the_x_y_named_ones() {
+        let tree = flatbush_js_test_index();
+        let root_node = tree.root();
+
+        assert_eq!(root_node.min(0), root_node.min_x());
+        assert_eq!(root_node.min(1), root_node.min_y());
+        assert_eq!(root_node.max(0), root_node.max_x());
+        assert_eq!(root_node.max(1), root_node.max_y());
+    }
+
+    #[test]
+    fn self_intersection_candidates_matches_deduped_other_tree_join() {
+        use std::collections::HashSet;
+
+        use crate::rtree::sort::HilbertSort;
+        use crate::rtree::RTreeBuilder;
+
+        let mut builder = RTreeBuilder::<f64>::new(6);
+        builder.add(0., 0., 2., 2.);
+        builder.add(1., 1., 3., 3.);
+        builder.add(10., 10., 11., 11.);
+        builder.add(10.5, 10.5, 11.5, 11.5);
+        builder.add(20., 20., 21., 21.);
+        builder.add(0.5, 0.5, 1.5, 1.5);
+        let tree = builder.finish::<HilbertSort>();
+
+        let self_join: HashSet<(u32, u32)> = tree.self_intersection_candidates().collect();
+
+        // No `(i, i)` self-pairs, and no duplicate unordered pairs.
+        assert!(self_join.iter().all(|(i, j)| i != j));
+        for (i, j) in &self_join {
+            assert!(!self_join.contains(&(*j, *i)) || i == j);
+        }
+
+        let expected: HashSet<(u32, u32)> = tree
+            .intersection_candidates_with_other_tree(&tree)
+            .filter(|(i, j)| i != j)
+            .map(|(i, j)| if i < j { (i, j) } else { (j, i) })
+            .collect();
+
+        assert_eq!(self_join, expected);
+    }
+
+    #[test]
+    fn spatial_join_supports_contains_within_and_distance_within() {
+        use crate::rtree::sort::HilbertSort;
+        use crate::rtree::{RTreeBuilder, SpatialPredicate};
+
+        // A big box (0) that encloses a small one (1), plus an unrelated far-away box (2).
+        let mut left_builder = RTreeBuilder::<f64>::new(3);
+        left_builder.add(0., 0., 10., 10.);
+        left_builder.add(4., 4., 6., 6.);
+        left_builder.add(100., 100., 101., 101.);
+        let left = left_builder.finish::<HilbertSort>();
+
+        let mut right_builder = RTreeBuilder::<f64>::new(2);
+        right_builder.add(4., 4., 6., 6.);
+        right_builder.add(20., 20., 21., 21.);
+        let right = right_builder.finish::<HilbertSort>();
+
+        // Left box 1 and right box 0 are identical, and a box encloses an identical box, so
+        // both pairs match.
+        let mut contains: Vec<_> = left
+            .spatial_join(&right, SpatialPredicate::Contains)
+            .collect();
+        contains.sort_unstable();
+        assert_eq!(contains, vec![(0, 0), (1, 0)]);
+
+        let mut within: Vec<_> = right
+            .spatial_join(&left, SpatialPredicate::Within)
+            .collect();
+        within.sort_unstable();
+        assert_eq!(within, vec![(0, 0), (0, 1)]);
+
+        let mut distance_within: Vec<_> = left
+            .spatial_join(&right, SpatialPredicate::DistanceWithin(1.0))
+            .collect();
+        distance_within.sort_unstable();
+        assert_eq!(distance_within, vec![(0, 0), (1, 0)]);
+    }
 }
 
 #[cfg(test)]
@@ -20,6 +20,26 @@ pub(crate) fn compute_num_nodes(num_items: u32, node_size: u16) -> (usize, Vec<u
     (num_nodes, level_bounds)
 }
 
+/// Find the smallest value in the sorted `level_bounds` that is strictly greater than `value`.
+///
+/// Used to cap a node's children range at the end of its level, since the last node in a level
+/// may have fewer than `node_size` children.
+pub(crate) fn upper_bound(value: usize, level_bounds: &[usize]) -> usize {
+    let mut i = 0;
+    let mut j = level_bounds.len() - 1;
+
+    while i < j {
+        let m = (i + j) >> 1;
+        if level_bounds[m] > value {
+            j = m;
+        } else {
+            i = m + 1;
+        }
+    }
+
+    level_bounds[i]
+}
+
 /// Cast a bounding box with `f64` precision to `f32` precision. This uses the [`float_next_after`]
 /// crate to ensure the resulting box is no smaller than the `f64` box.
 #[inline]
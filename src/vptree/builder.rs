@@ -0,0 +1,281 @@
+use bytemuck::cast_slice_mut;
+
+use crate::indices::MutableIndices;
+use crate::r#type::IndexableNum;
+use crate::rtree::SimpleDistanceMetric;
+use crate::vptree::constants::{VPTREE_HEADER_SIZE, VPTREE_MAGIC, VPTREE_VERSION};
+use crate::vptree::index::{VPTree, VPTreeMetadata};
+
+/// Default node size in [`VPTreeBuilder::new`].
+pub const DEFAULT_VPTREE_NODE_SIZE: u16 = 16;
+
+/// A builder to create a [`VPTree`].
+///
+/// ```
+/// use geo_index::rtree::distance::EuclideanDistance;
+/// use geo_index::vptree::VPTreeBuilder;
+///
+/// let mut builder = VPTreeBuilder::<f64>::new(3);
+/// builder.add(0., 0.);
+/// builder.add(1., 1.);
+/// builder.add(2., 2.);
+/// let tree = builder.finish(&EuclideanDistance);
+/// ```
+///
+/// Generic over its backing storage `B`. The default `B = Vec<u8>` heap-allocates its own
+/// buffer; use [`Self::from_metadata_in`]/[`Self::new_in`] to instead build directly into a
+/// caller-owned `&mut [u8]` (an `mmap`'d file or bump arena, say), avoiding a second full-size
+/// allocation when the index is ultimately persisted there.
+#[derive(Debug)]
+pub struct VPTreeBuilder<N: IndexableNum, B: AsMut<[u8]> + AsRef<[u8]> = Vec<u8>> {
+    /// data buffer
+    data: B,
+    metadata: VPTreeMetadata<N>,
+    pos: usize,
+}
+
+impl<N: IndexableNum> VPTreeBuilder<N, Vec<u8>> {
+    /// Create a new builder with the provided number of items and the default node size.
+    pub fn new(num_items: u32) -> Self {
+        Self::new_with_node_size(num_items, DEFAULT_VPTREE_NODE_SIZE)
+    }
+
+    /// Create a new builder with the provided number of items and node size.
+    pub fn new_with_node_size(num_items: u32, node_size: u16) -> Self {
+        let metadata = VPTreeMetadata::new(num_items, node_size);
+        Self::from_metadata(metadata)
+    }
+
+    /// Create a new builder with the provided metadata, heap-allocating its own buffer.
+    pub fn from_metadata(metadata: VPTreeMetadata<N>) -> Self {
+        let data = vec![0; metadata.data_buffer_length()];
+        Self::from_metadata_in(metadata, data)
+    }
+}
+
+impl<N: IndexableNum, B: AsMut<[u8]> + AsRef<[u8]>> VPTreeBuilder<N, B> {
+    /// Create a new builder with the provided metadata, writing into a caller-provided buffer.
+    ///
+    /// `buffer` must be exactly `metadata.data_buffer_length()` bytes long.
+    pub fn from_metadata_in(metadata: VPTreeMetadata<N>, mut buffer: B) -> Self {
+        debug_assert_eq!(
+            buffer.as_ref().len(),
+            metadata.data_buffer_length(),
+            "buffer must be exactly `metadata.data_buffer_length()` bytes long"
+        );
+
+        let data = buffer.as_mut();
+        // Set data header
+        data[0] = VPTREE_MAGIC;
+        data[1] = (VPTREE_VERSION << 4) + N::TYPE_INDEX;
+        cast_slice_mut(&mut data[2..4])[0] = metadata.node_size();
+        cast_slice_mut(&mut data[4..8])[0] = metadata.num_items();
+
+        Self {
+            data: buffer,
+            metadata,
+            pos: 0,
+        }
+    }
+
+    /// Create a new builder with the provided number of items and node size, writing into a
+    /// caller-provided buffer.
+    ///
+    /// `buffer` must be exactly as long as
+    /// `VPTreeMetadata::new(num_items, node_size).data_buffer_length()`.
+    pub fn new_in(num_items: u32, node_size: u16, buffer: B) -> Self {
+        let metadata = VPTreeMetadata::new(num_items, node_size);
+        Self::from_metadata_in(metadata, buffer)
+    }
+
+    /// Access the underlying [`VPTreeMetadata`] of this instance.
+    pub fn metadata(&self) -> &VPTreeMetadata<N> {
+        &self.metadata
+    }
+
+    /// Add a point to the VPTree.
+    ///
+    /// This returns the insertion index, which provides a lookup back into the original data.
+    #[inline]
+    pub fn add(&mut self, x: N, y: N) -> u32 {
+        let index = self.pos >> 1;
+        let (coords, mut indices, _mu) = split_data_borrow(self.data.as_mut(), &self.metadata);
+
+        indices.set(index, index);
+        coords[self.pos] = x;
+        self.pos += 1;
+        coords[self.pos] = y;
+        self.pos += 1;
+
+        index.try_into().unwrap()
+    }
+
+    /// Consume this builder, recursively choosing vantage points and generating a VPTree ready
+    /// for queries.
+    ///
+    /// `metric` is used both to choose split radii while building and must be passed again
+    /// (and must agree) on every subsequent query, since the tree itself stores no metric state.
+    pub fn finish<M: SimpleDistanceMetric<N> + ?Sized>(mut self, metric: &M) -> VPTree<N, B> {
+        assert_eq!(
+            self.pos >> 1,
+            self.metadata.num_items() as usize,
+            "Added {} items when expected {}.",
+            self.pos >> 1,
+            self.metadata.num_items()
+        );
+
+        let (coords, mut indices, mu) = split_data_borrow(self.data.as_mut(), &self.metadata);
+
+        if self.metadata.num_items() > 0 {
+            build(
+                coords,
+                &mut indices,
+                mu,
+                self.metadata.node_size() as usize,
+                0,
+                self.metadata.num_items() as usize - 1,
+                metric,
+            );
+        }
+
+        VPTree {
+            buffer: self.data,
+            metadata: self.metadata,
+        }
+    }
+}
+
+/// Mutable borrow of coords, indices and mu
+fn split_data_borrow<'a, N: IndexableNum>(
+    data: &'a mut [u8],
+    metadata: &'a VPTreeMetadata<N>,
+) -> (&'a mut [N], MutableIndices<'a>, &'a mut [N]) {
+    let (indices_buf, rest) =
+        data[VPTREE_HEADER_SIZE..].split_at_mut(metadata.indices_byte_size);
+    let (padded_coords_buf, mu_buf_with_pad) = rest.split_at_mut(
+        metadata.pad_indices_byte_size + metadata.coords_byte_size,
+    );
+    let coords_buf = &mut padded_coords_buf[metadata.pad_indices_byte_size..];
+    debug_assert_eq!(coords_buf.len(), metadata.coords_byte_size);
+    let mu_buf = &mut mu_buf_with_pad[metadata.pad_coords_byte_size..];
+    debug_assert_eq!(mu_buf.len(), metadata.mu_byte_size);
+
+    let indices = if metadata.num_items() < 65536 {
+        MutableIndices::U16(cast_slice_mut(indices_buf))
+    } else {
+        MutableIndices::U32(cast_slice_mut(indices_buf))
+    };
+    let coords = cast_slice_mut(coords_buf);
+    let mu = cast_slice_mut(mu_buf);
+
+    (coords, indices, mu)
+}
+
+/// Recursively partition `coords[left..=right]` (and their matching `indices`) around a vantage
+/// point chosen as `coords[left]`.
+///
+/// Items closer to the vantage point than the median distance `mu` are moved to
+/// `left + 1..=mid`, farther items to `mid + 1..=right`, and `mu[left]` (keyed by coordinate
+/// position, not original insertion index) is set to that median distance so that
+/// [`VPTreeIndex::within`][crate::vptree::VPTreeIndex::within] and
+/// [`VPTreeIndex::neighbors`][crate::vptree::VPTreeIndex::neighbors] can prune using the
+/// triangle inequality. Ranges of at most `node_size` items are left unsorted and scanned
+/// linearly at query time.
+fn build<N: IndexableNum, M: SimpleDistanceMetric<N> + ?Sized>(
+    coords: &mut [N],
+    indices: &mut MutableIndices,
+    mu: &mut [N],
+    node_size: usize,
+    left: usize,
+    right: usize,
+    metric: &M,
+) {
+    if right - left < node_size {
+        return;
+    }
+
+    let vx = coords[2 * left];
+    let vy = coords[2 * left + 1];
+
+    // Distance from the vantage point to every other item in the range, tagged with its current
+    // position so the partition can be written back below.
+    let mut by_distance: Vec<(usize, N)> = (left + 1..=right)
+        .map(|i| (i, metric.distance(vx, vy, coords[2 * i], coords[2 * i + 1])))
+        .collect();
+
+    let inner_len = by_distance.len().div_ceil(2);
+    by_distance.select_nth_unstable_by(inner_len - 1, |a, b| a.1.partial_cmp(&b.1).unwrap());
+    mu[left] = by_distance[inner_len - 1].1;
+
+    // Read the desired new order out before writing, since source and destination positions
+    // overlap.
+    let reordered: Vec<(usize, N, N)> = by_distance
+        .iter()
+        .map(|&(i, _)| (indices.get(i), coords[2 * i], coords[2 * i + 1]))
+        .collect();
+    for (offset, (id, x, y)) in reordered.into_iter().enumerate() {
+        let pos = left + 1 + offset;
+        indices.set(pos, id);
+        coords[2 * pos] = x;
+        coords[2 * pos + 1] = y;
+    }
+
+    let mid = left + inner_len;
+    build(coords, indices, mu, node_size, left + 1, mid, metric);
+    if mid < right {
+        build(coords, indices, mu, node_size, mid + 1, right, metric);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rtree::distance::EuclideanDistance;
+    use crate::vptree::VPTreeIndex;
+
+    #[test]
+    fn builds_a_queryable_tree() {
+        let mut builder = VPTreeBuilder::<f64>::new(5);
+        builder.add(0., 0.);
+        builder.add(10., 10.);
+        builder.add(1., 1.);
+        builder.add(1., 0.);
+        builder.add(20., 20.);
+        let tree = builder.finish(&EuclideanDistance);
+
+        let nearest = tree.neighbors(0., 0., 3, &EuclideanDistance);
+        assert_eq!(nearest, vec![0, 3, 2]);
+    }
+
+    #[test]
+    fn single_item_tree_does_not_panic() {
+        let mut builder = VPTreeBuilder::<f64>::new(1);
+        builder.add(5., 5.);
+        let tree = builder.finish(&EuclideanDistance);
+        assert_eq!(tree.neighbors(0., 0., 1, &EuclideanDistance), vec![0]);
+    }
+
+    #[test]
+    fn builds_and_queries_under_a_non_euclidean_metric() {
+        use crate::rtree::distance::HaversineDistance;
+
+        // Lon/lat points spread around the globe, where the triangle-inequality pruning only
+        // holds if it's evaluated under the same great-circle metric the tree was built with.
+        let metric = HaversineDistance::default();
+        let mut builder = VPTreeBuilder::<f64>::new_with_node_size(5, 2);
+        builder.add(0., 0.); // 0: null island
+        builder.add(2., 2.); // 1: near null island
+        builder.add(170., -40.); // 2: near New Zealand
+        builder.add(172., -41.); // 3: also near New Zealand
+        builder.add(-122., 37.); // 4: San Francisco
+        let tree = builder.finish(&metric);
+
+        let mut nearest = tree.neighbors(1., 1., 2, &metric);
+        nearest.sort();
+        assert_eq!(nearest, vec![0, 1]);
+
+        let mut within = tree.within(171., -40.5, 500_000., &metric);
+        within.sort();
+        assert_eq!(within, vec![2, 3]);
+    }
+}
@@ -0,0 +1,10 @@
+//! ABI constants for the buffer-backed [`VPTree`][crate::vptree::VPTree].
+
+/// Magic byte identifying a VPTree buffer, stored at `data[0]`.
+pub const VPTREE_MAGIC: u8 = 0xfc;
+
+/// The current version of the VPTree ABI, stored in the upper nibble of `data[1]`.
+pub const VPTREE_VERSION: u8 = 1;
+
+/// The number of header bytes preceding the indices/coords/mu buffers.
+pub const VPTREE_HEADER_SIZE: usize = 8;
@@ -0,0 +1,231 @@
+use std::marker::PhantomData;
+
+use bytemuck::cast_slice;
+
+use crate::error::{GeoIndexError, Result};
+use crate::indices::Indices;
+use crate::r#type::IndexableNum;
+use crate::vptree::constants::{VPTREE_HEADER_SIZE, VPTREE_MAGIC, VPTREE_VERSION};
+
+/// Common metadata to describe a [`VPTree`].
+///
+/// You can use the metadata to infer the total byte size of a tree given the provided criteria.
+/// See [`data_buffer_length`][Self::data_buffer_length].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VPTreeMetadata<N: IndexableNum> {
+    node_size: u16,
+    num_items: u32,
+    phantom: PhantomData<N>,
+    pub(crate) indices_byte_size: usize,
+    pub(crate) pad_indices_byte_size: usize,
+    pub(crate) coords_byte_size: usize,
+    pub(crate) pad_coords_byte_size: usize,
+    pub(crate) mu_byte_size: usize,
+}
+
+impl<N: IndexableNum> VPTreeMetadata<N> {
+    /// Construct a new [`VPTreeMetadata`] from a number of items and node size.
+    pub fn new(num_items: u32, node_size: u16) -> Self {
+        assert!((2..=65535).contains(&node_size));
+
+        let indices_bytes_per_element = if num_items < 65536 { 2 } else { 4 };
+        let indices_byte_size = (num_items as usize) * indices_bytes_per_element;
+        let pad_indices_byte_size = (8 - (indices_byte_size % 8)) % 8;
+
+        let coords_byte_size = (num_items as usize) * 2 * N::BYTES_PER_ELEMENT;
+        let pad_coords_byte_size = (8 - (coords_byte_size % 8)) % 8;
+
+        let mu_byte_size = (num_items as usize) * N::BYTES_PER_ELEMENT;
+
+        Self {
+            node_size,
+            num_items,
+            phantom: PhantomData,
+            indices_byte_size,
+            pad_indices_byte_size,
+            coords_byte_size,
+            pad_coords_byte_size,
+            mu_byte_size,
+        }
+    }
+
+    /// Construct a new [`VPTreeMetadata`] from an existing byte slice conforming to the VPTree
+    /// ABI, such as what [`VPTreeBuilder`][crate::vptree::VPTreeBuilder] generates.
+    pub fn from_slice(data: &[u8]) -> Result<Self> {
+        if data[0] != VPTREE_MAGIC {
+            return Err(GeoIndexError::General(
+                "Data not in VPTree format.".to_string(),
+            ));
+        }
+
+        let version_and_type = data[1];
+        let version = version_and_type >> 4;
+        if version != VPTREE_VERSION {
+            return Err(GeoIndexError::General(
+                format!("Got v{version} data when expected v{VPTREE_VERSION}.").to_string(),
+            ));
+        }
+
+        let type_ = version_and_type & 0x0f;
+        if type_ != N::TYPE_INDEX {
+            return Err(GeoIndexError::General(
+                format!(
+                    "Got type {} data when expected type {}.",
+                    type_,
+                    N::TYPE_INDEX
+                )
+                .to_string(),
+            ));
+        }
+
+        let node_size: u16 = cast_slice(&data[2..4])[0];
+        let num_items: u32 = cast_slice(&data[4..8])[0];
+
+        let slf = Self::new(num_items, node_size);
+        if slf.data_buffer_length() != data.len() {
+            return Err(GeoIndexError::General(format!(
+                "Expected {} bytes but received byte slice with {} bytes",
+                slf.data_buffer_length(),
+                data.len()
+            )));
+        }
+
+        Ok(slf)
+    }
+
+    /// The maximum number of items per leaf, scanned linearly instead of through a vantage point.
+    pub fn node_size(&self) -> u16 {
+        self.node_size
+    }
+
+    /// The number of items indexed in the tree.
+    pub fn num_items(&self) -> u32 {
+        self.num_items
+    }
+
+    /// The number of bytes that a VPTree with this metadata would have.
+    pub fn data_buffer_length(&self) -> usize {
+        VPTREE_HEADER_SIZE
+            + self.indices_byte_size
+            + self.pad_indices_byte_size
+            + self.coords_byte_size
+            + self.pad_coords_byte_size
+            + self.mu_byte_size
+    }
+
+    /// Access the slice of indices from the data buffer this metadata represents.
+    pub fn indices_slice<'a>(&self, data: &'a [u8]) -> Indices<'a> {
+        let indices_buf = &data[VPTREE_HEADER_SIZE..VPTREE_HEADER_SIZE + self.indices_byte_size];
+        Indices::new(indices_buf, self.num_items as usize)
+    }
+
+    /// Access the slice of coordinates from the data buffer this metadata represents.
+    pub fn coords_slice<'a>(&self, data: &'a [u8]) -> &'a [N] {
+        let coords_byte_start = VPTREE_HEADER_SIZE
+            + self.indices_byte_size
+            + self.pad_indices_byte_size;
+        let coords_byte_end = coords_byte_start + self.coords_byte_size;
+        cast_slice(&data[coords_byte_start..coords_byte_end])
+    }
+
+    /// Access the slice of per-vantage-point split radii from the data buffer this metadata
+    /// represents, aligned with [`Self::coords_slice`] (position `i` holds the split radius for
+    /// the node whose vantage point lives at coordinate index `i`, or is unused for positions
+    /// that are leaves).
+    pub fn mu_slice<'a>(&self, data: &'a [u8]) -> &'a [N] {
+        let mu_byte_start = VPTREE_HEADER_SIZE
+            + self.indices_byte_size
+            + self.pad_indices_byte_size
+            + self.coords_byte_size
+            + self.pad_coords_byte_size;
+        let mu_byte_end = mu_byte_start + self.mu_byte_size;
+        cast_slice(&data[mu_byte_start..mu_byte_end])
+    }
+}
+
+/// A VPTree buffer, generic over its backing storage.
+///
+/// The default `B = Vec<u8>` (aliased as [`OwnedVPTree`]) heap-allocates its own buffer, and is
+/// usually created from scratch via [`VPTreeBuilder`][crate::vptree::VPTreeBuilder].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VPTree<N: IndexableNum, B: AsRef<[u8]> = Vec<u8>> {
+    pub(crate) buffer: B,
+    pub(crate) metadata: VPTreeMetadata<N>,
+}
+
+/// A [`VPTree`] that owns a heap-allocated `Vec<u8>` buffer.
+///
+/// This is the ordinary, default way to build and hold a `VPTree`.
+pub type OwnedVPTree<N> = VPTree<N, Vec<u8>>;
+
+impl<N: IndexableNum, B: AsRef<[u8]>> VPTree<N, B> {
+    /// Consume this VPTree, returning the underlying buffer.
+    ///
+    /// This buffer can then be persisted and passed to `VPTreeRef::try_new`.
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+
+    /// Access the underlying [`VPTreeMetadata`] of this instance.
+    pub fn metadata(&self) -> &VPTreeMetadata<N> {
+        &self.metadata
+    }
+}
+
+impl<N: IndexableNum, B: AsRef<[u8]>> AsRef<[u8]> for VPTree<N, B> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+}
+
+/// A reference on an external VPTree buffer.
+///
+/// Usually this will be created from a [`VPTree`] via its [`as_ref`][VPTree::as_ref] method, but
+/// it can also be created from any existing data buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VPTreeRef<'a, N: IndexableNum> {
+    pub(crate) coords: &'a [N],
+    pub(crate) mu: &'a [N],
+    pub(crate) indices: Indices<'a>,
+    pub(crate) metadata: VPTreeMetadata<N>,
+}
+
+impl<'a, N: IndexableNum> VPTreeRef<'a, N> {
+    /// Construct a new VPTreeRef from an external byte slice.
+    pub fn try_new<T: AsRef<[u8]>>(data: &'a T) -> Result<Self> {
+        let data = data.as_ref();
+        let metadata = VPTreeMetadata::from_slice(data)?;
+        let coords = metadata.coords_slice(data);
+        let mu = metadata.mu_slice(data);
+        let indices = metadata.indices_slice(data);
+
+        Ok(Self {
+            coords,
+            mu,
+            indices,
+            metadata,
+        })
+    }
+
+    /// Construct a new VPTreeRef without doing any validation
+    ///
+    /// # Safety
+    ///
+    /// `metadata` must be valid for this data buffer.
+    pub unsafe fn new_unchecked<T: AsRef<[u8]>>(
+        data: &'a T,
+        metadata: VPTreeMetadata<N>,
+    ) -> Result<Self> {
+        let data = data.as_ref();
+        let coords = metadata.coords_slice(data);
+        let mu = metadata.mu_slice(data);
+        let indices = metadata.indices_slice(data);
+
+        Ok(Self {
+            coords,
+            mu,
+            indices,
+            metadata,
+        })
+    }
+}
@@ -0,0 +1,302 @@
+//! A vantage-point tree: an index for nearest-neighbor search in arbitrary metric spaces.
+//!
+//! Unlike [`RTree`][crate::rtree::RTree] and [`KDTree`][crate::kdtree::KDTree], which split on
+//! axis-aligned coordinate bounds, a [`VpTree`] only ever asks for the distance between two
+//! items via a [`Metric`]. That makes it the right structure for non-Euclidean or non-coordinate
+//! metrics (geodesic distance, edit distance, or any function satisfying the triangle
+//! inequality) where a bounding-box-based tree's pruning doesn't apply.
+//!
+//! ## Creation
+//!
+//! [`VpTree::new`] bulk-loads a tree from a `Vec` of items and a [`Metric`].
+//!
+//! ## Search
+//!
+//! Use [`VpTree::neighbors`] to find the `k` nearest items to a query point.
+//!
+//! ## Example
+//!
+//! ```
+//! use geo_index::vptree::{Metric, VpTree};
+//!
+//! struct Euclidean1D;
+//!
+//! impl Metric<f64> for Euclidean1D {
+//!     fn distance(&self, a: &f64, b: &f64) -> f64 {
+//!         (a - b).abs()
+//!     }
+//! }
+//!
+//! let tree = VpTree::new(vec![0.0, 5.0, 10.0, 10.5], Euclidean1D);
+//! let neighbors = tree.neighbors(&10.0, 2);
+//! assert_eq!(neighbors.len(), 2);
+//! assert_eq!(neighbors[0].0, 2); // index of the item `10.0`
+//! ```
+//!
+//! ## Buffer-backed, ABI-stable variant
+//!
+//! The [`VpTree`] above owns its items directly and isn't meant to be persisted. For 2D points
+//! keyed by coordinates under a [`SimpleDistanceMetric`][crate::rtree::SimpleDistanceMetric] (the
+//! same metrics [`RTree`][crate::rtree::RTree]/[`KDTree`][crate::kdtree::KDTree] use, such as
+//! [`HaversineDistance`][crate::rtree::HaversineDistance]), use [`VPTreeBuilder`] instead: it
+//! produces an immutable, zero-copy [`VPTree`] that can be persisted via [`VPTree::into_inner`]
+//! and recovered via [`VPTreeRef::try_new`], just like [`RTree`][crate::rtree::RTree] and
+//! [`KDTree`][crate::kdtree::KDTree].
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+mod builder;
+pub(crate) mod constants;
+mod index;
+mod r#trait;
+
+pub use builder::{VPTreeBuilder, DEFAULT_VPTREE_NODE_SIZE};
+pub use index::{OwnedVPTree, VPTree, VPTreeMetadata, VPTreeRef};
+pub use r#trait::VPTreeIndex;
+
+/// A distance function between two items of type `T`.
+///
+/// Implementations must satisfy the triangle inequality (`distance(a, c) <= distance(a, b) +
+/// distance(b, c)`) for the vantage-point tree's pruning to be correct.
+pub trait Metric<T> {
+    /// The distance between `a` and `b`. Must be non-negative and symmetric.
+    fn distance(&self, a: &T, b: &T) -> f64;
+}
+
+/// One node of the bulk-loaded vantage-point tree.
+///
+/// Nodes are stored in a flat `Vec` in build order; `inside`/`outside` are indices into that
+/// `Vec`, with `None` marking an empty subtree.
+struct VpNode {
+    /// Index into [`VpTree::items`] of this node's vantage point.
+    item_index: usize,
+    /// The median distance from the vantage point that separates the inside and outside
+    /// subtrees.
+    mu: f64,
+    inside: Option<usize>,
+    outside: Option<usize>,
+}
+
+/// A bulk-loaded vantage-point tree over items of type `T`, queried via a [`Metric`].
+pub struct VpTree<T, M: Metric<T>> {
+    items: Vec<T>,
+    metric: M,
+    nodes: Vec<VpNode>,
+    root: Option<usize>,
+}
+
+impl<T, M: Metric<T>> VpTree<T, M> {
+    /// Build a vantage-point tree over `items` using `metric`.
+    pub fn new(items: Vec<T>, metric: M) -> Self {
+        let mut nodes = Vec::with_capacity(items.len());
+        let mut remaining: Vec<usize> = (0..items.len()).collect();
+        let root = build(&items, &metric, &mut remaining, &mut nodes);
+        Self {
+            items,
+            metric,
+            nodes,
+            root,
+        }
+    }
+
+    /// The number of items in this tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if this tree contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The items that were indexed, in their original insertion order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Find the `k` nearest items to `query`, sorted by ascending distance.
+    ///
+    /// Returns `(item_index, distance)` pairs, with `item_index` indexing into
+    /// [`Self::items`].
+    pub fn neighbors(&self, query: &T, k: usize) -> Vec<(usize, f64)> {
+        if k == 0 || self.root.is_none() {
+            return Vec::new();
+        }
+
+        // Max-heap of the current best `k` candidates, ordered so the farthest is on top and
+        // gets evicted first as better candidates are found.
+        let mut heap: BinaryHeap<HeapItem> = BinaryHeap::with_capacity(k + 1);
+        let mut tau = f64::MAX;
+
+        self.search(self.root, query, k, &mut heap, &mut tau);
+
+        let mut results: Vec<(usize, f64)> = heap
+            .into_iter()
+            .map(|entry| (entry.item_index, entry.dist))
+            .collect();
+        results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        results
+    }
+
+    fn search(
+        &self,
+        node_index: Option<usize>,
+        query: &T,
+        k: usize,
+        heap: &mut BinaryHeap<HeapItem>,
+        tau: &mut f64,
+    ) {
+        let Some(node_index) = node_index else {
+            return;
+        };
+        let node = &self.nodes[node_index];
+        let d = self.metric.distance(query, &self.items[node.item_index]);
+
+        if d < *tau || heap.len() < k {
+            if heap.len() == k {
+                heap.pop();
+            }
+            heap.push(HeapItem {
+                item_index: node.item_index,
+                dist: d,
+            });
+            if heap.len() == k {
+                *tau = heap.peek().unwrap().dist;
+            }
+        }
+
+        // Recurse into whichever child is more likely to contain the query first, then prune
+        // the other child using the triangle inequality: if `|d - mu| >= tau`, no item on the
+        // far side of `mu` can be closer to `query` than the current worst kept candidate.
+        if d < node.mu {
+            if d - *tau <= node.mu {
+                self.search(node.inside, query, k, heap, tau);
+            }
+            if d + *tau >= node.mu {
+                self.search(node.outside, query, k, heap, tau);
+            }
+        } else {
+            if d + *tau >= node.mu {
+                self.search(node.outside, query, k, heap, tau);
+            }
+            if d - *tau <= node.mu {
+                self.search(node.inside, query, k, heap, tau);
+            }
+        }
+    }
+}
+
+struct HeapItem {
+    item_index: usize,
+    dist: f64,
+}
+
+impl PartialEq for HeapItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+impl Eq for HeapItem {}
+impl PartialOrd for HeapItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for HeapItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist.partial_cmp(&other.dist).unwrap()
+    }
+}
+
+/// Recursively build the tree over the items at `remaining`, appending nodes to `nodes` and
+/// returning the index of the subtree's root node.
+///
+/// Each call picks the first remaining item as the vantage point (arbitrary but deterministic),
+/// partitions the rest by their distance to it around the median (`mu`), and recurses on each
+/// half.
+fn build<T, M: Metric<T>>(
+    items: &[T],
+    metric: &M,
+    remaining: &mut [usize],
+    nodes: &mut Vec<VpNode>,
+) -> Option<usize> {
+    if remaining.is_empty() {
+        return None;
+    }
+    if remaining.len() == 1 {
+        let item_index = remaining[0];
+        nodes.push(VpNode {
+            item_index,
+            mu: 0.0,
+            inside: None,
+            outside: None,
+        });
+        return Some(nodes.len() - 1);
+    }
+
+    let vantage_item = remaining[0];
+    let rest = &mut remaining[1..];
+
+    let mid = rest.len() / 2;
+    // Partition `rest` by distance to the vantage point so that the closer half precedes the
+    // farther half, with `mu` the distance of the median item.
+    let mut indexed: Vec<(usize, f64)> = rest
+        .iter()
+        .map(|&i| (i, metric.distance(&items[vantage_item], &items[i])))
+        .collect();
+    indexed.select_nth_unstable_by(mid, |a, b| a.1.partial_cmp(&b.1).unwrap());
+    let mu = indexed[mid].1;
+    for (slot, (idx, _)) in rest.iter_mut().zip(indexed.iter()) {
+        *slot = *idx;
+    }
+
+    let (inside_items, outside_items) = rest.split_at_mut(mid);
+    let inside = build(items, metric, inside_items, nodes);
+    let outside = build(items, metric, outside_items, nodes);
+
+    nodes.push(VpNode {
+        item_index: vantage_item,
+        mu,
+        inside,
+        outside,
+    });
+    Some(nodes.len() - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Euclidean2D;
+
+    impl Metric<(f64, f64)> for Euclidean2D {
+        fn distance(&self, a: &(f64, f64), b: &(f64, f64)) -> f64 {
+            let dx = a.0 - b.0;
+            let dy = a.1 - b.1;
+            (dx * dx + dy * dy).sqrt()
+        }
+    }
+
+    #[test]
+    fn finds_exact_nearest_neighbors() {
+        let points = vec![(0., 0.), (10., 10.), (1., 1.), (1., 0.), (20., 20.)];
+        let tree = VpTree::new(points, Euclidean2D);
+
+        let neighbors = tree.neighbors(&(0., 0.), 3);
+        let indices: Vec<usize> = neighbors.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, vec![0, 3, 2]);
+    }
+
+    #[test]
+    fn returns_fewer_than_k_when_tree_is_smaller() {
+        let tree = VpTree::new(vec![(0., 0.), (1., 1.)], Euclidean2D);
+        assert_eq!(tree.neighbors(&(0., 0.), 5).len(), 2);
+    }
+
+    #[test]
+    fn empty_tree_returns_no_neighbors() {
+        let tree: VpTree<(f64, f64), Euclidean2D> = VpTree::new(vec![], Euclidean2D);
+        assert!(tree.neighbors(&(0., 0.), 3).is_empty());
+    }
+}
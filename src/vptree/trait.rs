@@ -0,0 +1,370 @@
+use std::collections::BinaryHeap;
+
+use crate::indices::Indices;
+use crate::r#type::IndexableNum;
+use crate::rtree::SimpleDistanceMetric;
+use crate::vptree::{VPTree, VPTreeMetadata, VPTreeRef};
+
+/// A trait for searching and accessing data out of a [`VPTree`].
+///
+/// Unlike [`KDTreeIndex`][crate::kdtree::KDTreeIndex]/[`RTreeIndex`][crate::rtree::RTreeIndex],
+/// which split on axis-aligned coordinate bounds, every search here is expressed purely in terms
+/// of a [`SimpleDistanceMetric`], making this the right index to reach for when points live in a
+/// genuinely non-Euclidean metric space (great-circle, spheroid, or a custom metric). The same
+/// `metric` used to [`finish`][crate::vptree::VPTreeBuilder::finish] a tree must be passed again
+/// on every query — the tree itself stores no metric state, only vantage indices and split radii.
+pub trait VPTreeIndex<N: IndexableNum>: Sized {
+    /// The underlying raw coordinate buffer of this tree
+    fn coords(&self) -> &[N];
+
+    /// The underlying raw per-vantage-point split radii of this tree
+    fn mu(&self) -> &[N];
+
+    /// The underlying raw indices buffer of this tree
+    fn indices(&self) -> Indices<'_>;
+
+    /// Access the metadata describing this VPTree
+    fn metadata(&self) -> &VPTreeMetadata<N>;
+
+    /// The number of items in this VPTree
+    fn num_items(&self) -> u32 {
+        self.metadata().num_items()
+    }
+
+    /// The node size of this VPTree
+    fn node_size(&self) -> u16 {
+        self.metadata().node_size()
+    }
+
+    /// Search the index for items within a given radius of a query point.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - r: radius
+    /// - metric: the same [`SimpleDistanceMetric`] the tree was built with
+    ///
+    /// Returns indices of found items.
+    fn within<M: SimpleDistanceMetric<N> + ?Sized>(&self, qx: N, qy: N, r: N, metric: &M) -> Vec<u32> {
+        if self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let mu = self.mu();
+        let node_size = self.node_size() as usize;
+
+        let mut result = vec![];
+        search_within(
+            coords,
+            &indices,
+            mu,
+            node_size,
+            0,
+            indices.len() - 1,
+            qx,
+            qy,
+            r,
+            metric,
+            &mut result,
+        );
+        result
+    }
+
+    /// Find the `k` nearest neighbors to a query point, in ascending order of distance.
+    ///
+    /// - qx: x value of query point
+    /// - qy: y value of query point
+    /// - k: number of neighbors to find
+    /// - metric: the same [`SimpleDistanceMetric`] the tree was built with
+    ///
+    /// Returns indices of found items, ordered by ascending distance. If `k` is greater than or
+    /// equal to the number of items in the tree, all items are returned.
+    fn neighbors<M: SimpleDistanceMetric<N> + ?Sized>(
+        &self,
+        qx: N,
+        qy: N,
+        k: usize,
+        metric: &M,
+    ) -> Vec<u32> {
+        if k == 0 || self.num_items() == 0 {
+            return vec![];
+        }
+
+        let indices = self.indices();
+        let coords = self.coords();
+        let mu = self.mu();
+        let node_size = self.node_size() as usize;
+
+        let mut best: BinaryHeap<KnnCandidate> = BinaryHeap::with_capacity(k + 1);
+        search_knn(
+            coords,
+            &indices,
+            mu,
+            node_size,
+            0,
+            indices.len() - 1,
+            qx,
+            qy,
+            k,
+            metric,
+            &mut best,
+        );
+
+        best.into_sorted_vec().into_iter().map(|c| c.id).collect()
+    }
+}
+
+impl<N: IndexableNum, B: AsRef<[u8]>> VPTreeIndex<N> for VPTree<N, B> {
+    fn coords(&self) -> &[N] {
+        self.metadata.coords_slice(self.buffer.as_ref())
+    }
+
+    fn mu(&self) -> &[N] {
+        self.metadata.mu_slice(self.buffer.as_ref())
+    }
+
+    fn indices(&self) -> Indices<'_> {
+        self.metadata.indices_slice(self.buffer.as_ref())
+    }
+
+    fn metadata(&self) -> &VPTreeMetadata<N> {
+        &self.metadata
+    }
+}
+
+impl<N: IndexableNum> VPTreeIndex<N> for VPTreeRef<'_, N> {
+    fn coords(&self) -> &[N] {
+        self.coords
+    }
+
+    fn mu(&self) -> &[N] {
+        self.mu
+    }
+
+    fn indices(&self) -> Indices<'_> {
+        self.indices
+    }
+
+    fn metadata(&self) -> &VPTreeMetadata<N> {
+        &self.metadata
+    }
+}
+
+/// Recursive radius search over a vantage-point-partitioned `coords`/`indices`/`mu` triple.
+///
+/// `coords[left]` is the range's vantage point; ranges of at most `node_size` items were left
+/// unpartitioned by the builder and are scanned linearly instead.
+#[allow(clippy::too_many_arguments)]
+fn search_within<N: IndexableNum, M: SimpleDistanceMetric<N> + ?Sized>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    mu: &[N],
+    node_size: usize,
+    left: usize,
+    right: usize,
+    qx: N,
+    qy: N,
+    r: N,
+    metric: &M,
+    result: &mut Vec<u32>,
+) {
+    if right - left < node_size {
+        for i in left..=right {
+            if metric.distance(coords[2 * i], coords[2 * i + 1], qx, qy) <= r {
+                result.push(indices.get(i).try_into().unwrap());
+            }
+        }
+        return;
+    }
+
+    let d = metric.distance(coords[2 * left], coords[2 * left + 1], qx, qy);
+    if d <= r {
+        result.push(indices.get(left).try_into().unwrap());
+    }
+
+    let mid = left + (right - left).div_ceil(2);
+    let split_mu = mu[left];
+
+    if d - r <= split_mu {
+        search_within(
+            coords,
+            indices,
+            mu,
+            node_size,
+            left + 1,
+            mid,
+            qx,
+            qy,
+            r,
+            metric,
+            result,
+        );
+    }
+    if mid < right && d + r >= split_mu {
+        search_within(
+            coords,
+            indices,
+            mu,
+            node_size,
+            mid + 1,
+            right,
+            qx,
+            qy,
+            r,
+            metric,
+            result,
+        );
+    }
+}
+
+/// A candidate `(distance, id)` pair in [`VPTreeIndex::neighbors`]'s bounded max-heap, ordered so
+/// that the farthest of the current best candidates sits at the top of the heap, ready to be
+/// evicted in `O(log k)` once a closer candidate turns up.
+struct KnnCandidate {
+    dist: f64,
+    id: u32,
+}
+
+impl PartialEq for KnnCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+
+impl Eq for KnnCandidate {}
+
+impl PartialOrd for KnnCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KnnCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist
+            .partial_cmp(&other.dist)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+#[inline]
+fn offer_candidate(best: &mut BinaryHeap<KnnCandidate>, k: usize, candidate: KnnCandidate) {
+    if best.len() < k {
+        best.push(candidate);
+    } else if let Some(worst) = best.peek() {
+        if candidate.dist < worst.dist {
+            best.pop();
+            best.push(candidate);
+        }
+    }
+}
+
+/// Recursive best-first k-nearest-neighbor search, mirroring [`search_within`] but maintaining a
+/// bounded max-heap of the `k` best candidates seen so far and replacing the query radius with
+/// the heap's current worst distance (`tau`) as the search tightens, per the classic vantage-point
+/// tree kNN algorithm.
+#[allow(clippy::too_many_arguments)]
+fn search_knn<N: IndexableNum, M: SimpleDistanceMetric<N> + ?Sized>(
+    coords: &[N],
+    indices: &Indices<'_>,
+    mu: &[N],
+    node_size: usize,
+    left: usize,
+    right: usize,
+    qx: N,
+    qy: N,
+    k: usize,
+    metric: &M,
+    best: &mut BinaryHeap<KnnCandidate>,
+) {
+    if right - left < node_size {
+        for i in left..=right {
+            let dist = metric
+                .distance(coords[2 * i], coords[2 * i + 1], qx, qy)
+                .to_f64()
+                .unwrap_or(f64::MAX);
+            offer_candidate(
+                best,
+                k,
+                KnnCandidate {
+                    dist,
+                    id: indices.get(i).try_into().unwrap(),
+                },
+            );
+        }
+        return;
+    }
+
+    let d = metric
+        .distance(coords[2 * left], coords[2 * left + 1], qx, qy)
+        .to_f64()
+        .unwrap_or(f64::MAX);
+    offer_candidate(
+        best,
+        k,
+        KnnCandidate {
+            dist: d,
+            id: indices.get(left).try_into().unwrap(),
+        },
+    );
+
+    let mid = left + (right - left).div_ceil(2);
+    let split_mu = mu[left].to_f64().unwrap_or(f64::MAX);
+
+    if d < split_mu {
+        if d - tau(best, k) <= split_mu {
+            search_knn(
+                coords, indices, mu, node_size, left + 1, mid, qx, qy, k, metric, best,
+            );
+        }
+        if mid < right && d + tau(best, k) >= split_mu {
+            search_knn(
+                coords,
+                indices,
+                mu,
+                node_size,
+                mid + 1,
+                right,
+                qx,
+                qy,
+                k,
+                metric,
+                best,
+            );
+        }
+    } else {
+        if mid < right && d + tau(best, k) >= split_mu {
+            search_knn(
+                coords,
+                indices,
+                mu,
+                node_size,
+                mid + 1,
+                right,
+                qx,
+                qy,
+                k,
+                metric,
+                best,
+            );
+        }
+        if d - tau(best, k) <= split_mu {
+            search_knn(
+                coords, indices, mu, node_size, left + 1, mid, qx, qy, k, metric, best,
+            );
+        }
+    }
+}
+
+/// The current k-th best distance in `best` (or infinity until it holds `k` candidates), standing
+/// in for the query radius in [`search_within`]'s pruning tests. Re-read after each recursive
+/// call, since the heap may have tightened in the meantime.
+#[inline]
+fn tau(best: &BinaryHeap<KnnCandidate>, k: usize) -> f64 {
+    if best.len() < k {
+        f64::MAX
+    } else {
+        best.peek().unwrap().dist
+    }
+}